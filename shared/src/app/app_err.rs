@@ -1,3 +1,4 @@
+use crate::domain::{logic::AttrViolation, model::meta::Dependency};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
@@ -16,8 +17,9 @@ pub enum AppError {
 
     /// Commonly used to indicate that an item deletion cannot be done since
     /// it is referred (mainly at the database level through a foreign key).
+    /// The referring elements are included, if known.
     #[error("dependencies exist")]
-    DependenciesExist,
+    DependenciesExist(Vec<Dependency>),
 
     #[error("")]
     Ignorable,
@@ -25,9 +27,26 @@ pub enum AppError {
     #[error("internal error")]
     InternalErr,
 
+    /// The provided input is not valid, the details being included.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
     #[error("login wrong credentials")]
     LoginWrongCredentials,
 
+    /// The credentials are right, but the user must set a new password (as an admin requested) to log in.
+    #[error("login password reset required")]
+    LoginPasswordResetRequired,
+
+    /// There were too many failed logins (of the username or from the client),
+    /// thus another one is allowed only after the provided number of seconds.
+    #[error("too many failed logins, retry after {0} seconds")]
+    LoginThrottled(u64),
+
+    /// The account is temporarily locked (for the provided number of seconds), due to repeated failed logins.
+    #[error("the account is locked, retry after {0} seconds")]
+    LoginLocked(u64),
+
     /// Generic error.
     #[error("{0}")]
     Err(String),
@@ -39,6 +58,10 @@ pub enum AppError {
     #[error("unauthorized: {0}")]
     Unauthorized(String),
 
+    /// The user is authenticated, but lacks the permission that is required.
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
     #[error("The pair of name and description must be unique.")]
     NameDescriptionNotUnique,
 
@@ -47,6 +70,15 @@ pub enum AppError {
 
     #[error("{0} is read-only")]
     ReadOnly(String),
+
+    /// The element was saved by someone else meanwhile, thus the provided version is stale.
+    /// The current (saved) copy of the element is included, as JSON.
+    #[error("it was changed by someone else meanwhile")]
+    StaleVersion(serde_json::Value),
+
+    /// The item is not valid against its template, each violating attribute being included.
+    #[error("validation failed ({} violations)", .0.len())]
+    ValidationFailed(Vec<AttrViolation>),
 }
 
 impl From<&str> for AppError {
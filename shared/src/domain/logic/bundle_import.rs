@@ -0,0 +1,756 @@
+use crate::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::{AttrViolation, validate_item},
+        model::{
+            AccessLevel, Id,
+            meta::{AttrTemplate, Item, ItemLink, ItemTemplate, ItemTemplateLink, Kind},
+        },
+    },
+    dtos::{
+        BUNDLE_FORMAT_VERSION, BundleClash, BundleClashPolicy, BundleIdsPolicy, BundleImportReport,
+        BundleImportRequest, WorkspaceBundle,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+/// An element that exists in the workspace, that an element of an imported bundle may clash with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkspaceElement {
+    pub kind: Kind,
+    pub id: Id,
+    pub name: String,
+
+    /// The description, as part of the unique name of the attribute templates. It's empty for the other kinds.
+    pub description: String,
+
+    pub version: i32,
+
+    /// Whether it cannot be changed, as it happens for the built-in access levels.
+    pub read_only: bool,
+
+    /// Whether it's in the Trash, thus its name is free to be taken.
+    pub deleted: bool,
+}
+
+/// The plan of importing a workspace bundle.
+///
+/// It has the elements to be written (in this order), with their ids and references resolved against
+/// the workspace. The ones that overwrite existing elements have the versions of those.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundleImportPlan {
+    pub access_levels: Vec<AccessLevel>,
+    pub attr_templates: Vec<AttrTemplate>,
+    pub item_templates: Vec<ItemTemplate>,
+
+    /// The items, whose existing outgoing links are replaced by the ones of the plan.
+    pub items: Vec<Item>,
+
+    pub item_links: Vec<ItemLink>,
+    pub report: BundleImportReport,
+}
+
+impl BundleImportPlan {
+    /// Get the ids of the item templates that the items use, but that are not written by the plan
+    /// (as they are skipped), thus they are the ones that exist in the workspace.
+    pub fn kept_item_tmpl_ids(&self) -> Vec<Id> {
+        let written: HashSet<&Id> = self.item_templates.iter().map(|it| &it.id).collect();
+        let mut ids: Vec<Id> = self
+            .items
+            .iter()
+            .filter_map(|item| item.tmpl_id.clone())
+            .filter(|id| !written.contains(id))
+            .collect();
+        ids.sort_by(|a, b| a.0.cmp(&b.0));
+        ids.dedup();
+        ids
+    }
+
+    /// Validate the items against their (resolved) templates, that are the ones of the plan or, otherwise,
+    /// the provided ones of the workspace. The name of each violating attribute is prefixed by the listing
+    /// attribute value of its item, to tell them apart.
+    pub fn validate_items(&self, workspace_tmpls: &[ItemTemplate]) -> Vec<AttrViolation> {
+        let mut violations = Vec::new();
+        for item in &self.items {
+            let tmpl = item.tmpl_id.as_ref().and_then(|tmpl_id| {
+                self.item_templates
+                    .iter()
+                    .chain(workspace_tmpls)
+                    .find(|it| &it.id == tmpl_id)
+            });
+            violations.extend(validate_item(item, tmpl).into_iter().map(|v| {
+                AttrViolation::new(
+                    format!("{}: {}", item.listing_attr_value, v.attr_name),
+                    v.kind,
+                )
+            }));
+        }
+        violations
+    }
+}
+
+/// Plan the import of a workspace bundle into a workspace that has the provided (existing) elements.
+///
+/// The elements of the bundle keep or get new ids (as `req.ids` says), and an element clashes with
+/// an existing one if it has the same id (when the ids are preserved) or the same unique name, that is
+/// the name of an access level, or the name and description of an attribute template.
+/// The clashes are resolved as `req.clashes` says, except for the read-only elements, that are always kept.
+///
+/// The import is refused if the bundle has another format version, has duplicates, or is not self-contained.
+pub fn plan_bundle_import(
+    req: &BundleImportRequest,
+    existing: &[WorkspaceElement],
+    mut new_id: impl FnMut() -> Id,
+) -> AppResult<BundleImportPlan> {
+    let bundle = &req.bundle;
+    check_bundle(bundle)?;
+
+    let mut resolver = Resolver::new(req, existing);
+    let mut plan = BundleImportPlan::default();
+
+    for al in &bundle.access_levels {
+        let r = resolver.resolve(&Kind::AccessLevel, &al.id, &al.name, "", &mut new_id);
+        resolver.ids.insert(al.id.clone(), r.id.clone());
+        if let Some(version) = r.version {
+            plan.access_levels.push(AccessLevel {
+                id: r.id,
+                name: r.name,
+                version,
+                ..al.clone()
+            });
+        }
+    }
+
+    for at in &bundle.attr_templates {
+        let r = resolver.resolve(
+            &Kind::AttributeTemplate,
+            &at.id,
+            &at.name,
+            &at.description,
+            &mut new_id,
+        );
+        let resolved = AttrTemplate {
+            id: r.id,
+            name: r.name,
+            version: r.version.unwrap_or_default(),
+            ..at.clone()
+        };
+        if r.version.is_some() {
+            plan.attr_templates.push(resolved.clone());
+        }
+        resolver.attr_tmpls.insert(at.id.clone(), resolved);
+    }
+
+    // All of them are resolved first, since they may link to each other.
+    let item_tmpls: Vec<_> = bundle
+        .item_templates
+        .iter()
+        .map(|it| {
+            (
+                it,
+                resolver.resolve(&Kind::ItemTemplate, &it.id, &it.name, "", &mut new_id),
+            )
+        })
+        .collect();
+    for (it, r) in &item_tmpls {
+        resolver.ids.insert(it.id.clone(), r.id.clone());
+        resolver.tmpl_names.insert(it.id.clone(), r.name.clone());
+    }
+    for (it, r) in item_tmpls {
+        if let Some(version) = r.version {
+            plan.item_templates
+                .push(resolver.item_template(it, r.id, version));
+        }
+    }
+
+    // All of them are resolved first, since they may link to each other.
+    let items: Vec<_> = bundle
+        .items
+        .iter()
+        .map(|i| {
+            (
+                i,
+                resolver.resolve(&Kind::Item, &i.id, &i.listing_attr_value, "", &mut new_id),
+            )
+        })
+        .collect();
+    for (item, r) in &items {
+        resolver.ids.insert(item.id.clone(), r.id.clone());
+    }
+    for (item, r) in items {
+        if let Some(version) = r.version {
+            plan.items
+                .push(resolver.item(item, r.id, version, &mut new_id));
+        }
+    }
+
+    // The links of the skipped items are left as they are.
+    let written: HashSet<Id> = plan.items.iter().map(|i| i.id.clone()).collect();
+    for link in &bundle.item_links {
+        let source_item_id = resolver.mapped(&link.source_item_id);
+        if !written.contains(&source_item_id) {
+            continue;
+        }
+        let id = if source_item_id == link.source_item_id {
+            link.id.clone()
+        } else {
+            new_id()
+        };
+        plan.item_links.push(ItemLink {
+            id,
+            source_item_id,
+            target_item_id: resolver.mapped(&link.target_item_id),
+            ..link.clone()
+        });
+    }
+
+    plan.report = resolver.report;
+    plan.report.item_links = plan.item_links.len();
+    Ok(plan)
+}
+
+/// How an element of the bundle ends up in the workspace.
+struct Resolved {
+    id: Id,
+    name: String,
+
+    /// The version that it's written with, or `None` if it's skipped.
+    version: Option<i32>,
+}
+
+/// It resolves the ids and the clashes of the elements of a bundle, one at a time, keeping track of
+/// the unique names that are taken.
+struct Resolver<'a> {
+    existing: &'a [WorkspaceElement],
+    ids_policy: BundleIdsPolicy,
+    clash_policy: BundleClashPolicy,
+
+    /// The unique names (as kind, name and description) that are taken, by existing or imported elements.
+    taken: HashSet<(Kind, String, String)>,
+
+    /// The unique names that the elements of the bundle have, that a renamed element must not take either.
+    reserved: HashSet<(Kind, String, String)>,
+
+    report: BundleImportReport,
+
+    /// The ids that the (resolved) elements have in the workspace, by their ids in the bundle.
+    ids: HashMap<Id, Id>,
+
+    /// The (resolved) attribute templates, as they are in the workspace, by their ids in the bundle.
+    attr_tmpls: HashMap<Id, AttrTemplate>,
+
+    /// The names that the (resolved) item templates have in the workspace, by their ids in the bundle.
+    tmpl_names: HashMap<Id, String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(req: &BundleImportRequest, existing: &'a [WorkspaceElement]) -> Self {
+        let taken = existing
+            .iter()
+            .filter(|e| has_unique_name(&e.kind) && !e.deleted)
+            .map(|e| (e.kind.clone(), e.name.clone(), e.description.clone()))
+            .collect();
+        let bundle = &req.bundle;
+        let reserved = bundle
+            .access_levels
+            .iter()
+            .map(|al| (Kind::AccessLevel, al.name.clone(), String::new()))
+            .chain(bundle.attr_templates.iter().map(|at| {
+                (
+                    Kind::AttributeTemplate,
+                    at.name.clone(),
+                    at.description.clone(),
+                )
+            }))
+            .collect();
+        Self {
+            existing,
+            ids_policy: req.ids,
+            clash_policy: req.clashes,
+            taken,
+            reserved,
+            report: BundleImportReport::default(),
+            ids: HashMap::new(),
+            attr_tmpls: HashMap::new(),
+            tmpl_names: HashMap::new(),
+        }
+    }
+
+    /// Get the id that an element has in the workspace, by its id in the bundle.
+    fn mapped(&self, id: &Id) -> Id {
+        self.ids.get(id).cloned().unwrap_or_else(|| id.clone())
+    }
+
+    /// Get the attribute template as it is in the workspace.
+    fn attr_tmpl(&self, at: &AttrTemplate) -> AttrTemplate {
+        self.attr_tmpls
+            .get(&at.id)
+            .cloned()
+            .unwrap_or_else(|| at.clone())
+    }
+
+    /// Get the item template as it's written to the workspace, with the provided id and version,
+    /// and with its references resolved.
+    fn item_template(&self, it: &ItemTemplate, id: Id, version: i32) -> ItemTemplate {
+        ItemTemplate {
+            id,
+            name: self.tmpl_names.get(&it.id).cloned().unwrap_or_default(),
+            description: it.description.clone(),
+            attributes: it.attributes.iter().map(|at| self.attr_tmpl(at)).collect(),
+            listing_attr: self.attr_tmpl(&it.listing_attr),
+            links: it
+                .links
+                .iter()
+                .map(|link| ItemTemplateLink {
+                    item_template_id: self.mapped(&link.item_template_id),
+                    ..link.clone()
+                })
+                .collect(),
+            access_level_id: it.access_level_id.as_ref().map(|id| self.mapped(id)),
+            version,
+        }
+    }
+
+    /// Get the item as it's written to the workspace, with the provided id and version,
+    /// and with its references resolved.
+    fn item(&self, item: &Item, id: Id, version: i32, new_id: &mut impl FnMut() -> Id) -> Item {
+        let mut item = item.clone();
+        let renew_attr_ids = item.id != id;
+        item.id = id;
+        item.version = version;
+        if let Some(tmpl_id) = &item.tmpl_id {
+            item.kind = self.tmpl_names.get(tmpl_id).cloned().unwrap_or_default();
+            item.tmpl_id = Some(self.mapped(tmpl_id));
+        }
+        item.access_level_id = item.access_level_id.as_ref().map(|id| self.mapped(id));
+        if let Some(at) = self.attr_tmpls.get(&item.listing_attr_tmpl_id) {
+            item.listing_attr_tmpl_id = at.id.clone();
+            item.listing_attr_name = at.name.clone();
+        }
+        macro_rules! resolve_attrs {
+            ($attrs:expr) => {
+                for a in &mut $attrs {
+                    a.owner_id = item.id.clone();
+                    if let Some(at) = a.tmpl_id.as_ref().and_then(|id| self.attr_tmpls.get(id)) {
+                        a.tmpl_id = Some(at.id.clone());
+                        a.name = at.name.clone();
+                    }
+                }
+            };
+        }
+        resolve_attrs!(item.text_attributes);
+        resolve_attrs!(item.numeric_attributes);
+        resolve_attrs!(item.boolean_attributes);
+        resolve_attrs!(item.date_attributes);
+        resolve_attrs!(item.datetime_attributes);
+        // The attributes of an existing item are replaced, thus they may keep their ids only if it's the same item.
+        if renew_attr_ids {
+            item.renew_attribute_ids(new_id);
+        }
+        item.refresh_listing_attr();
+        item
+    }
+
+    fn resolve(
+        &mut self,
+        kind: &Kind,
+        id: &Id,
+        name: &str,
+        description: &str,
+        new_id: &mut impl FnMut() -> Id,
+    ) -> Resolved {
+        let key = |name: &str| (kind.clone(), name.to_owned(), description.to_owned());
+        let preserve = self.ids_policy == BundleIdsPolicy::Preserve;
+        let same_id = |e: &&WorkspaceElement| preserve && &e.kind == kind && &e.id == id;
+        let same_name = |e: &&WorkspaceElement| {
+            has_unique_name(kind)
+                && &e.kind == kind
+                && !e.deleted
+                && e.name == name
+                && e.description == description
+        };
+
+        // A trashed element with the same id is left alone, by giving the imported one a new id.
+        let clash = self
+            .existing
+            .iter()
+            .find(|e| (same_id(e) && !e.deleted) || same_name(e));
+        let Some(existing) = clash else {
+            let id = if preserve && !self.existing.iter().any(|e| same_id(&e)) {
+                id.clone()
+            } else {
+                new_id()
+            };
+            self.report.created += 1;
+            self.taken.insert(key(name));
+            return Resolved {
+                id,
+                name: name.to_owned(),
+                version: Some(0),
+            };
+        };
+
+        let resolution = if existing.read_only {
+            BundleClashPolicy::Skip
+        } else {
+            self.clash_policy
+        };
+        let mut clash = BundleClash {
+            kind: kind.clone(),
+            name: name.to_owned(),
+            resolution,
+            renamed_to: None,
+        };
+        let resolved = match resolution {
+            BundleClashPolicy::Skip => {
+                self.report.skipped += 1;
+                Resolved {
+                    id: existing.id.clone(),
+                    name: existing.name.clone(),
+                    version: None,
+                }
+            }
+            BundleClashPolicy::Overwrite => {
+                self.report.overwritten += 1;
+                self.taken.insert(key(name));
+                Resolved {
+                    id: existing.id.clone(),
+                    name: name.to_owned(),
+                    version: Some(existing.version),
+                }
+            }
+            BundleClashPolicy::Rename => {
+                let mut new_name = name.to_owned();
+                if has_unique_name(kind) && self.taken.contains(&key(name)) {
+                    new_name = (2..)
+                        .map(|n| format!("{name} ({n})"))
+                        .find(|n| !self.taken.contains(&key(n)) && !self.reserved.contains(&key(n)))
+                        .unwrap_or_default();
+                    clash.renamed_to = Some(new_name.clone());
+                }
+                self.report.created += 1;
+                self.taken.insert(key(&new_name));
+                Resolved {
+                    id: new_id(),
+                    name: new_name,
+                    version: Some(0),
+                }
+            }
+        };
+        self.report.clashes.push(clash);
+        resolved
+    }
+}
+
+/// Whether the elements of this kind have unique names (in the workspace).
+fn has_unique_name(kind: &Kind) -> bool {
+    matches!(kind, Kind::AccessLevel | Kind::AttributeTemplate)
+}
+
+/// Check that the bundle has the supported format version, that it has no duplicates,
+/// and that it's self-contained, thus its elements refer only to each other.
+fn check_bundle(bundle: &WorkspaceBundle) -> AppResult<()> {
+    if bundle.format_version != BUNDLE_FORMAT_VERSION {
+        return Err(AppError::InvalidInput(format!(
+            "the bundle format version {} is not supported, only {BUNDLE_FORMAT_VERSION} is",
+            bundle.format_version
+        )));
+    }
+
+    let access_level_ids =
+        unique_ids("access level", bundle.access_levels.iter().map(|al| &al.id))?;
+    let attr_tmpl_ids = unique_ids(
+        "attribute template",
+        bundle.attr_templates.iter().map(|at| &at.id),
+    )?;
+    let item_tmpl_ids = unique_ids(
+        "item template",
+        bundle.item_templates.iter().map(|it| &it.id),
+    )?;
+    let item_ids = unique_ids("item", bundle.items.iter().map(|i| &i.id))?;
+    unique_ids("item link", bundle.item_links.iter().map(|l| &l.id))?;
+
+    let mut names = HashSet::new();
+    if let Some(al) = bundle
+        .access_levels
+        .iter()
+        .find(|al| !names.insert(&al.name))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "the bundle has more than one access level named '{}'",
+            al.name
+        )));
+    }
+    let mut names = HashSet::new();
+    if let Some(at) = bundle
+        .attr_templates
+        .iter()
+        .find(|at| !names.insert((&at.name, &at.description)))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "the bundle has more than one attribute template named '{}' with the same description",
+            at.name
+        )));
+    }
+
+    for it in &bundle.item_templates {
+        let element = format!("item template '{}'", it.name);
+        if let Some(id) = &it.access_level_id
+            && !access_level_ids.contains(id)
+        {
+            return missing(&element, "access level", id);
+        }
+        for at in it.attributes.iter().chain([&it.listing_attr]) {
+            if !attr_tmpl_ids.contains(&at.id) {
+                return missing(&element, "attribute template", &at.id);
+            }
+        }
+        for link in &it.links {
+            if !item_tmpl_ids.contains(&link.item_template_id) {
+                return missing(&element, "item template", &link.item_template_id);
+            }
+        }
+    }
+    for item in &bundle.items {
+        check_item_refs(item, &item_tmpl_ids, &attr_tmpl_ids, &access_level_ids)?;
+    }
+    for link in &bundle.item_links {
+        let element = format!("item link '{}'", link.name);
+        for id in [&link.source_item_id, &link.target_item_id] {
+            if !item_ids.contains(id) {
+                return missing(&element, "item", id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that the elements that an item refers are in the bundle.
+fn check_item_refs(
+    item: &Item,
+    item_tmpl_ids: &HashSet<&Id>,
+    attr_tmpl_ids: &HashSet<&Id>,
+    access_level_ids: &HashSet<&Id>,
+) -> AppResult<()> {
+    let element = format!("item '{}'", item.listing_attr_value);
+    if let Some(tmpl_id) = &item.tmpl_id
+        && !item_tmpl_ids.contains(tmpl_id)
+    {
+        return missing(&element, "item template", tmpl_id);
+    }
+    if let Some(id) = &item.access_level_id
+        && !access_level_ids.contains(id)
+    {
+        return missing(&element, "access level", id);
+    }
+    let mut attr_tmpl_refs = vec![];
+    if !item.listing_attr_tmpl_id.is_zero() {
+        attr_tmpl_refs.push(&item.listing_attr_tmpl_id);
+    }
+    attr_tmpl_refs.extend(
+        (item.text_attributes.iter().map(|a| &a.tmpl_id))
+            .chain(item.numeric_attributes.iter().map(|a| &a.tmpl_id))
+            .chain(item.boolean_attributes.iter().map(|a| &a.tmpl_id))
+            .chain(item.date_attributes.iter().map(|a| &a.tmpl_id))
+            .chain(item.datetime_attributes.iter().map(|a| &a.tmpl_id))
+            .flatten(),
+    );
+    if let Some(id) = attr_tmpl_refs
+        .into_iter()
+        .find(|id| !attr_tmpl_ids.contains(id))
+    {
+        return missing(&element, "attribute template", id);
+    }
+    Ok(())
+}
+
+fn missing<T>(element: &str, refers: &str, id: &Id) -> AppResult<T> {
+    Err(AppError::InvalidInput(format!(
+        "{element} refers the {refers} '{id}', that the bundle does not have"
+    )))
+}
+
+/// Get the ids of the elements (of a kind), refusing the bundle if any of them is there more than once.
+fn unique_ids<'a>(kind: &str, ids: impl Iterator<Item = &'a Id>) -> AppResult<HashSet<&'a Id>> {
+    let mut unique = HashSet::new();
+    for id in ids {
+        if !unique.insert(id) {
+            return Err(AppError::InvalidInput(format!(
+                "the bundle has the {kind} '{id}' more than once"
+            )));
+        }
+    }
+    Ok(unique)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorkspaceElement, plan_bundle_import};
+    use crate::{
+        domain::{
+            logic::{
+                AttrViolation, AttrViolationKind,
+                test_fixtures::{attr_tmpl, required_attr_tmpl, server_tmpl},
+            },
+            model::{
+                Id,
+                meta::{AttributeValueType, Item, ItemLink, Kind},
+            },
+        },
+        dtos::{
+            BUNDLE_FORMAT_VERSION, BundleClashPolicy, BundleIdsPolicy, BundleImportRequest,
+            WorkspaceBundle,
+        },
+    };
+
+    fn bundle() -> WorkspaceBundle {
+        let hostname = attr_tmpl("at-1", "Hostname", AttributeValueType::Text);
+        let tmpl = server_tmpl(vec![hostname.clone()]);
+        let item = |id: &str| {
+            let mut item = Item::from(&tmpl);
+            item.id = Id::from(id);
+            item
+        };
+        WorkspaceBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            attr_templates: vec![hostname],
+            items: vec![item("i-1"), item("i-2")],
+            item_templates: vec![tmpl],
+            item_links: vec![ItemLink {
+                id: Id::from("l-1"),
+                name: "peer".to_owned(),
+                source_item_id: Id::from("i-1"),
+                target_item_id: Id::from("i-2"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn existing_hostname() -> WorkspaceElement {
+        WorkspaceElement {
+            kind: Kind::AttributeTemplate,
+            id: Id::from("at-9"),
+            name: "Hostname".to_owned(),
+            version: 3,
+            ..Default::default()
+        }
+    }
+
+    fn request(ids: BundleIdsPolicy, clashes: BundleClashPolicy) -> BundleImportRequest {
+        BundleImportRequest {
+            bundle: bundle(),
+            ids,
+            clashes,
+        }
+    }
+
+    fn new_ids() -> impl FnMut() -> Id {
+        let mut n = 0;
+        move || {
+            n += 1;
+            Id::from(format!("new-{n}"))
+        }
+    }
+
+    #[test]
+    fn clashes_are_skipped_overwritten_or_renamed() {
+        let existing = [existing_hostname()];
+
+        let req = request(BundleIdsPolicy::Preserve, BundleClashPolicy::Skip);
+        let plan = plan_bundle_import(&req, &existing, new_ids()).expect("the bundle is valid");
+        assert!(plan.attr_templates.is_empty());
+        let listing_attr_ids = plan
+            .item_templates
+            .iter()
+            .map(|it| &it.listing_attr.id)
+            .collect::<Vec<_>>();
+        assert_eq!(listing_attr_ids, vec![&Id::from("at-9")]);
+        assert_eq!((plan.report.created, plan.report.skipped), (3, 1));
+
+        let req = request(BundleIdsPolicy::Preserve, BundleClashPolicy::Overwrite);
+        let plan = plan_bundle_import(&req, &existing, new_ids()).expect("the bundle is valid");
+        let versions = plan
+            .attr_templates
+            .iter()
+            .map(|at| (&at.id, at.version))
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![(&Id::from("at-9"), 3)]);
+
+        let req = request(BundleIdsPolicy::Remap, BundleClashPolicy::Rename);
+        let plan = plan_bundle_import(&req, &existing, new_ids()).expect("the bundle is valid");
+        let names = plan
+            .attr_templates
+            .iter()
+            .map(|at| at.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Hostname (2)"]);
+        let attr_names = plan
+            .items
+            .iter()
+            .flat_map(|i| &i.text_attributes)
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(attr_names, vec!["Hostname (2)", "Hostname (2)"]);
+        assert!(
+            plan.report
+                .clashes
+                .iter()
+                .any(|c| c.renamed_to.as_deref() == Some("Hostname (2)"))
+        );
+    }
+
+    #[test]
+    fn remapped_ids_are_resolved_in_the_references() {
+        let req = request(BundleIdsPolicy::Remap, BundleClashPolicy::Skip);
+
+        let plan = plan_bundle_import(&req, &[], new_ids()).expect("the bundle is valid");
+
+        let item_ids = plan.items.iter().map(|i| i.id.clone()).collect::<Vec<_>>();
+        let tmpl_ids = plan
+            .item_templates
+            .iter()
+            .map(|it| Some(it.id.clone()))
+            .collect::<Vec<_>>();
+        assert!(plan.items.iter().all(|i| tmpl_ids.contains(&i.tmpl_id)));
+        assert!(
+            plan.item_links
+                .iter()
+                .all(|l| item_ids.contains(&l.source_item_id)
+                    && item_ids.contains(&l.target_item_id))
+        );
+        assert!(!item_ids.contains(&Id::from("i-1")));
+
+        let mut broken = request(BundleIdsPolicy::Remap, BundleClashPolicy::Skip);
+        broken.bundle.items.clear();
+        assert!(plan_bundle_import(&broken, &[], new_ids()).is_err());
+    }
+
+    #[test]
+    fn items_are_validated_against_their_resolved_templates() {
+        let owner = required_attr_tmpl("at-2", "Owner", AttributeValueType::Text);
+        let mut req = request(BundleIdsPolicy::Preserve, BundleClashPolicy::Skip);
+        req.bundle.attr_templates.push(owner.clone());
+        for it in &mut req.bundle.item_templates {
+            it.attributes.push(owner.clone());
+        }
+        for item in &mut req.bundle.items {
+            let hostname = format!("web-{}", item.id);
+            for a in &mut item.text_attributes {
+                a.value.clone_from(&hostname);
+            }
+            item.refresh_listing_attr();
+        }
+
+        let plan = plan_bundle_import(&req, &[], new_ids()).expect("the bundle is valid");
+
+        assert!(plan.kept_item_tmpl_ids().is_empty());
+        assert_eq!(
+            plan.validate_items(&[]),
+            vec![
+                AttrViolation::new("web-i-1: Owner", AttrViolationKind::MissingRequired),
+                AttrViolation::new("web-i-2: Owner", AttrViolationKind::MissingRequired),
+            ]
+        );
+    }
+}
@@ -0,0 +1,561 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use std::{fmt::Display, str::FromStr};
+use thiserror::Error;
+
+/// A query for filtering items by their attributes, such as
+/// `Status = "open" AND Due < 2026-12-01 AND Priority >= 3`.
+///
+/// The grammar is:
+/// ```text
+/// query     := and_expr ( OR and_expr )*
+/// and_expr  := unary ( AND unary )*
+/// unary     := NOT unary | "(" query ")" | condition
+/// condition := attr_name op value
+/// op        := = | != | < | <= | > | >= | CONTAINS
+/// ```
+/// The keywords are case insensitive. An attribute name is a word or a quoted text (if it has spaces).
+/// The type of a value is given by how it is written: `"text"` (or a word), `3` or `-1.5` (numeric),
+/// `true` or `false` (boolean), `2026-12-01` (date), and `2026-12-01T10:30` (datetime).
+/// A condition matches an item that has an attribute with that name and type, whose value matches it.
+///
+/// A query can have at most [`MAX_QUERY_LENGTH`] characters, and at most [`MAX_NESTING_DEPTH`]
+/// nested parentheses and `NOT`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemQuery {
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Not(Box<Self>),
+    Cond(AttrCondition),
+}
+
+/// The maximum number of characters of a query.
+pub const MAX_QUERY_LENGTH: usize = 2000;
+
+/// The maximum depth of nested parentheses and `NOT`s of a query.
+pub const MAX_NESTING_DEPTH: usize = 32;
+
+/// A condition on the value of an item's attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttrCondition {
+    pub attr_name: String,
+    pub op: CmpOp,
+    pub value: QueryValue,
+}
+
+/// The comparison operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// A text value contains another one (case insensitive).
+    Contains,
+}
+
+/// A (typed) value of a condition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueryValue {
+    Text(String),
+    Numeric(Decimal),
+    Boolean(bool),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+/// The error of parsing an item query, telling what is wrong and where (as a character position).
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("{message} (at position {position})")]
+pub struct ItemQueryError {
+    pub message: String,
+    pub position: usize,
+}
+
+impl ItemQueryError {
+    fn new(message: impl Into<String>, position: usize) -> Self {
+        Self {
+            message: message.into(),
+            position,
+        }
+    }
+}
+
+impl FromStr for ItemQuery {
+    type Err = ItemQueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let end = s.chars().count();
+        if end > MAX_QUERY_LENGTH {
+            return Err(ItemQueryError::new(
+                format!("the query is longer than {MAX_QUERY_LENGTH} characters"),
+                MAX_QUERY_LENGTH,
+            ));
+        }
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end,
+            depth: 0,
+        };
+        let query = parser.query()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some((_, pos)) => Err(ItemQueryError::new("unexpected text after the query", *pos)),
+        }
+    }
+}
+
+impl Display for CmpOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Contains => "CONTAINS",
+        };
+        write!(f, "{op}")
+    }
+}
+
+impl ItemQuery {
+    /// Compile the query to an SQL condition on the `items` table (that must be aliased as `i`),
+    /// which checks the typed attribute tables. The values are not included in the SQL, but
+    /// added to `params` (as text), and referred as `$n` placeholders (with `n` continuing
+    /// the numbering of the existing `params`) that are cast to the proper type.
+    pub fn to_sql(&self, params: &mut Vec<String>) -> String {
+        match self {
+            Self::And(l, r) => format!("({} AND {})", l.to_sql(params), r.to_sql(params)),
+            Self::Or(l, r) => format!("({} OR {})", l.to_sql(params), r.to_sql(params)),
+            Self::Not(q) => format!("(NOT {})", q.to_sql(params)),
+            Self::Cond(cond) => cond.to_sql(params),
+        }
+    }
+}
+
+impl AttrCondition {
+    fn to_sql(&self, params: &mut Vec<String>) -> String {
+        params.push(self.attr_name.clone());
+        let name = format!("${}", params.len());
+        let exists = |table: &str, value_cmp: String| {
+            format!(
+                "EXISTS (SELECT 1 FROM {table} a WHERE a.owner_id = i.id AND lower(a.name) = lower({name}) AND {value_cmp})"
+            )
+        };
+        let op = self.op;
+
+        match &self.value {
+            QueryValue::Text(value) => {
+                if op == CmpOp::Contains {
+                    // The LIKE wildcards are escaped, so that they are matched as such.
+                    let escaped = value
+                        .replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_");
+                    params.push(escaped);
+                    exists(
+                        "text_attributes",
+                        format!("a.value ILIKE '%' || ${} || '%'", params.len()),
+                    )
+                } else {
+                    params.push(value.clone());
+                    exists("text_attributes", format!("a.value {op} ${}", params.len()))
+                }
+            }
+            QueryValue::Numeric(value) => {
+                params.push(value.to_string());
+                // The values are stored as text, thus only the valid ones are compared.
+                let value_cmp = format!(
+                    "(CASE WHEN a.value ~ '^\\s*-?[0-9]+(\\.[0-9]+)?\\s*$' THEN a.value::numeric END) {op} ${}::numeric",
+                    params.len()
+                );
+                exists("numeric_attributes", value_cmp)
+            }
+            QueryValue::Boolean(value) => {
+                params.push(value.to_string());
+                exists(
+                    "boolean_attributes",
+                    format!("a.value {op} ${}::boolean", params.len()),
+                )
+            }
+            QueryValue::Date(value) => {
+                params.push(value.format("%Y-%m-%d").to_string());
+                let n = params.len();
+                format!(
+                    "({} OR {})",
+                    exists("date_attributes", format!("a.value {op} ${n}::date")),
+                    exists(
+                        "datetime_attributes",
+                        format!("a.value::date {op} ${n}::date")
+                    )
+                )
+            }
+            QueryValue::DateTime(value) => {
+                params.push(value.format("%Y-%m-%d %H:%M:%S").to_string());
+                let n = params.len();
+                format!(
+                    "({} OR {})",
+                    exists(
+                        "datetime_attributes",
+                        format!("a.value {op} ${n}::timestamp")
+                    ),
+                    exists(
+                        "date_attributes",
+                        format!("a.value::timestamp {op} ${n}::timestamp")
+                    )
+                )
+            }
+        }
+    }
+}
+
+// ---------
+// Tokenizer
+// ---------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    Op(CmpOp),
+    And,
+    Or,
+    Not,
+    /// A quoted text.
+    Quoted(String),
+    /// Any other (unquoted) word.
+    Word(String),
+}
+
+/// The characters that end an unquoted word.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '=' | '!' | '<' | '>' | '"')
+}
+
+fn tokenize(s: &str) -> Result<Vec<(Token, usize)>, ItemQueryError> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(&c) = chars.get(pos) {
+        let start = pos;
+        let next = chars.get(pos + 1).copied();
+        let token = match c {
+            _ if c.is_whitespace() => {
+                pos += 1;
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '=' => Token::Op(CmpOp::Eq),
+            '!' if next == Some('=') => Token::Op(CmpOp::Ne),
+            '<' if next == Some('=') => Token::Op(CmpOp::Le),
+            '<' if next == Some('>') => Token::Op(CmpOp::Ne),
+            '<' => Token::Op(CmpOp::Lt),
+            '>' if next == Some('=') => Token::Op(CmpOp::Ge),
+            '>' => Token::Op(CmpOp::Gt),
+            '!' => return Err(ItemQueryError::new("'!' must be followed by '='", start)),
+            '"' => {
+                let mut text = String::new();
+                pos += 1;
+                loop {
+                    match chars.get(pos) {
+                        None => {
+                            return Err(ItemQueryError::new(
+                                "the quoted text is not closed",
+                                start,
+                            ));
+                        }
+                        Some('"') => break,
+                        Some('\\') if chars.get(pos + 1).is_some() => {
+                            pos += 1;
+                            text.extend(chars.get(pos));
+                        }
+                        Some(&c) => text.push(c),
+                    }
+                    pos += 1;
+                }
+                Token::Quoted(text)
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.get(pos)
+                    && !is_delimiter(c)
+                {
+                    word.push(c);
+                    pos += 1;
+                }
+                tokens.push((keyword_or_word(word), start));
+                continue;
+            }
+        };
+        pos += match token {
+            Token::Op(CmpOp::Ne | CmpOp::Le | CmpOp::Ge) => 2,
+            _ => 1,
+        };
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+fn keyword_or_word(word: String) -> Token {
+    match word.to_uppercase().as_str() {
+        "AND" => Token::And,
+        "OR" => Token::Or,
+        "NOT" => Token::Not,
+        "CONTAINS" => Token::Op(CmpOp::Contains),
+        _ => Token::Word(word),
+    }
+}
+
+// ------
+// Parser
+// ------
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// The position of the end of the query, used for reporting what is missing at the end.
+    end: usize,
+    /// The current depth of nested parentheses and `NOT`s.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<(Token, usize), ItemQueryError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| ItemQueryError::new(format!("{expected} is expected"), self.end))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn next_is(&mut self, token: &Token) -> bool {
+        if self.peek().is_some_and(|(t, _)| t == token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn query(&mut self) -> Result<ItemQuery, ItemQueryError> {
+        let mut query = self.and_expr()?;
+        while self.next_is(&Token::Or) {
+            query = ItemQuery::Or(Box::new(query), Box::new(self.and_expr()?));
+        }
+        Ok(query)
+    }
+
+    fn and_expr(&mut self) -> Result<ItemQuery, ItemQueryError> {
+        let mut query = self.unary()?;
+        while self.next_is(&Token::And) {
+            query = ItemQuery::And(Box::new(query), Box::new(self.unary()?));
+        }
+        Ok(query)
+    }
+
+    fn unary(&mut self) -> Result<ItemQuery, ItemQueryError> {
+        if self.next_is(&Token::Not) {
+            self.nest()?;
+            let query = ItemQuery::Not(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(query);
+        }
+        if self.next_is(&Token::LParen) {
+            self.nest()?;
+            let query = self.query()?;
+            self.depth -= 1;
+            return match self.next("')'")? {
+                (Token::RParen, _) => Ok(query),
+                (_, pos) => Err(ItemQueryError::new("')' is expected", pos)),
+            };
+        }
+        self.condition().map(ItemQuery::Cond)
+    }
+
+    /// Enter a nested `NOT` or parentheses (that was just consumed), if it is not too deep.
+    fn nest(&mut self) -> Result<(), ItemQueryError> {
+        if self.depth == MAX_NESTING_DEPTH {
+            let pos = self
+                .tokens
+                .get(self.pos - 1)
+                .map_or(self.end, |(_, pos)| *pos);
+            return Err(ItemQueryError::new(
+                format!("the query has more than {MAX_NESTING_DEPTH} nested levels"),
+                pos,
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn condition(&mut self) -> Result<AttrCondition, ItemQueryError> {
+        let attr_name = match self.next("an attribute name")? {
+            (Token::Word(name) | Token::Quoted(name), _) => name,
+            (_, pos) => return Err(ItemQueryError::new("an attribute name is expected", pos)),
+        };
+        let op = match self.next("an operator")? {
+            (Token::Op(op), _) => op,
+            (_, pos) => return Err(ItemQueryError::new("an operator is expected", pos)),
+        };
+        let (value, pos) = match self.next("a value")? {
+            (Token::Quoted(text), pos) => (QueryValue::Text(text), pos),
+            (Token::Word(word), pos) => (typed_value(word), pos),
+            (_, pos) => return Err(ItemQueryError::new("a value is expected", pos)),
+        };
+
+        match (&value, op) {
+            (QueryValue::Text(_), _) | (_, CmpOp::Eq | CmpOp::Ne) => {}
+            (_, CmpOp::Contains) => {
+                return Err(ItemQueryError::new(
+                    "CONTAINS can be used only with a text value",
+                    pos,
+                ));
+            }
+            (QueryValue::Boolean(_), _) => {
+                return Err(ItemQueryError::new(
+                    "a boolean value can be compared only using = or !=",
+                    pos,
+                ));
+            }
+            _ => {}
+        }
+
+        Ok(AttrCondition {
+            attr_name,
+            op,
+            value,
+        })
+    }
+}
+
+/// Get the typed value out of an unquoted word. Anything that is not recognized is a text.
+fn typed_value(word: String) -> QueryValue {
+    let is_number = |w: &str| {
+        let digits = w.strip_prefix('-').unwrap_or(w);
+        !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+    };
+    if word.eq_ignore_ascii_case("true") || word.eq_ignore_ascii_case("false") {
+        return QueryValue::Boolean(word.eq_ignore_ascii_case("true"));
+    }
+    if is_number(&word)
+        && let Ok(number) = Decimal::from_str(&word)
+    {
+        return QueryValue::Numeric(number);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&word, "%Y-%m-%d") {
+        return QueryValue::Date(date);
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(&word, format) {
+            return QueryValue::DateTime(datetime);
+        }
+    }
+    QueryValue::Text(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AttrCondition, CmpOp, ItemQuery, MAX_NESTING_DEPTH, MAX_QUERY_LENGTH, QueryValue};
+    use chrono::NaiveDate;
+    use rust_decimal::Decimal;
+    use std::str::FromStr as _;
+
+    fn cond(attr_name: &str, op: CmpOp, value: QueryValue) -> ItemQuery {
+        ItemQuery::Cond(AttrCondition {
+            attr_name: String::from(attr_name),
+            op,
+            value,
+        })
+    }
+
+    #[test]
+    fn values_are_typed_and_and_binds_tighter_than_or() {
+        let query =
+            ItemQuery::from_str(r#"Status = "open" AND Due < 2026-12-01 OR NOT Priority >= 3"#)
+                .expect("the query is valid");
+
+        let due = NaiveDate::from_ymd_opt(2026, 12, 1).expect("the date is valid");
+        assert_eq!(
+            query,
+            ItemQuery::Or(
+                Box::new(ItemQuery::And(
+                    Box::new(cond(
+                        "Status",
+                        CmpOp::Eq,
+                        QueryValue::Text(String::from("open"))
+                    )),
+                    Box::new(cond("Due", CmpOp::Lt, QueryValue::Date(due))),
+                )),
+                Box::new(ItemQuery::Not(Box::new(cond(
+                    "Priority",
+                    CmpOp::Ge,
+                    QueryValue::Numeric(Decimal::from(3))
+                )))),
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_queries_are_reported_with_their_position() {
+        let err = |q: &str| ItemQuery::from_str(q).map(|_| ()).map_err(|e| e.position);
+
+        assert_eq!(err(r#"Status = "open"#), Err(9));
+        assert_eq!(err("Status open"), Err(7));
+        assert_eq!(err("Done < true"), Err(7));
+        assert_eq!(err("(Priority > 3"), Err(13));
+        assert_eq!(err(r#"Name contains "a" AND Name contains "b""#), Ok(()));
+    }
+
+    #[test]
+    fn too_long_or_too_deeply_nested_queries_are_rejected() {
+        let err = |q: &str| ItemQuery::from_str(q).map(|_| ()).map_err(|e| e.position);
+        let nested = |depth: usize| format!("{}A = 1{}", "(".repeat(depth), ")".repeat(depth));
+
+        assert_eq!(err(&nested(MAX_NESTING_DEPTH)), Ok(()));
+        assert_eq!(err(&nested(MAX_NESTING_DEPTH + 1)), Err(MAX_NESTING_DEPTH));
+        assert_eq!(
+            err(&"NOT ".repeat(MAX_NESTING_DEPTH + 1)),
+            Err(4 * MAX_NESTING_DEPTH)
+        );
+        assert_eq!(err(&"(".repeat(100_000)), Err(MAX_QUERY_LENGTH));
+    }
+
+    #[test]
+    fn sql_refers_the_values_as_typed_params() {
+        let query =
+            ItemQuery::from_str(r#""Due date" >= 2026-01-01T10:30 AND Notes CONTAINS "50%""#)
+                .expect("the query is valid");
+        let mut params = vec![String::from("existing")];
+
+        let sql = query.to_sql(&mut params);
+
+        assert!(sql.contains("lower(a.name) = lower($2)"));
+        assert!(sql.contains("a.value >= $3::timestamp"));
+        assert!(sql.contains("a.value ILIKE '%' || $5 || '%'"));
+        assert_eq!(
+            params,
+            vec![
+                "existing",
+                "Due date",
+                "2026-01-01 10:30:00",
+                "Notes",
+                "50\\%"
+            ]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use crate::domain::model::{
+    Id,
+    meta::{Attr, AttrTemplate, AttributeValueType, Item, ItemTemplate},
+};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The changes of an item template (from its old to its new shape) that affect
+/// the items created from it. It is used both as a dry-run preview and for
+/// migrating the existing items.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemTemplateChangePlan {
+    /// The attributes that are added to the template.
+    /// The existing items get them with their default values.
+    pub added_attrs: Vec<AttrTemplate>,
+
+    /// The attributes that are removed from the template.
+    /// The existing items get them dropped or detached, as the `OrphanAttrsPolicy` says.
+    pub removed_attrs: Vec<AttrTemplate>,
+
+    /// The attributes whose value type (or attribute template, for the same name) is changed.
+    pub type_changes: Vec<AttrTypeChange>,
+
+    /// The names of the links that are removed from the template.
+    /// The existing item links having these names are deleted.
+    pub removed_link_names: Vec<String>,
+
+    /// The number of existing items that are created from the template.
+    #[serde(default)]
+    pub affected_items: usize,
+}
+
+/// An attribute of an item template whose value type is changed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttrTypeChange {
+    /// The attribute (template) in the old shape of the item template.
+    pub from: AttrTemplate,
+
+    /// The attribute (template) in the new shape of the item template.
+    pub to: AttrTemplate,
+}
+
+/// What happens to the attributes of the existing items
+/// when their template is removed from the item template.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OrphanAttrsPolicy {
+    /// The attributes are removed from the items.
+    Drop,
+
+    /// The attributes are kept as free-form (not template based) attributes of the items.
+    #[default]
+    Detach,
+}
+
+impl ItemTemplateChangePlan {
+    /// Compute the plan for changing an item template from its `old` to its `new` shape.
+    /// An attribute that is replaced by one with the same name but another value type
+    /// is considered a type change, not a removal plus an addition.
+    pub fn new(old: &ItemTemplate, new: &ItemTemplate) -> Self {
+        let mut plan = Self::default();
+
+        for old_attr in &old.attributes {
+            let same_id = new.attributes.iter().find(|a| a.id == old_attr.id);
+            let same_name = || {
+                new.attributes.iter().find(|a| {
+                    a.name == old_attr.name && !old.attributes.iter().any(|oa| oa.id == a.id)
+                })
+            };
+            match same_id.or_else(same_name) {
+                // Replaced by one with the same name (but maybe the same value type,
+                // in which case the items only get the new attribute template).
+                Some(new_attr)
+                    if new_attr.value_type != old_attr.value_type || new_attr.id != old_attr.id =>
+                {
+                    plan.type_changes.push(AttrTypeChange {
+                        from: old_attr.clone(),
+                        to: new_attr.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => plan.removed_attrs.push(old_attr.clone()),
+            }
+        }
+
+        for new_attr in &new.attributes {
+            let existing = old.attributes.iter().any(|a| a.id == new_attr.id)
+                || plan.type_changes.iter().any(|tc| tc.to.id == new_attr.id);
+            if !existing {
+                plan.added_attrs.push(new_attr.clone());
+            }
+        }
+
+        plan.removed_link_names = old
+            .links
+            .iter()
+            .filter(|ol| !new.links.iter().any(|nl| nl.name == ol.name))
+            .map(|ol| ol.name.clone())
+            .collect();
+
+        plan
+    }
+
+    /// Tell if the plan has no changes to apply to the existing items.
+    pub fn is_empty(&self) -> bool {
+        self.added_attrs.is_empty()
+            && self.removed_attrs.is_empty()
+            && self.type_changes.is_empty()
+            && self.removed_link_names.is_empty()
+    }
+
+    /// Apply the plan on an item that was created from the (new) template `tmpl`.
+    /// The links are not part of the item, thus they must be handled separately.
+    pub fn apply_to(&self, item: &mut Item, tmpl: &ItemTemplate, orphans: OrphanAttrsPolicy) {
+        for removed in &self.removed_attrs {
+            if let Some((value_type, id)) = find_attr(item, &removed.id) {
+                match orphans {
+                    OrphanAttrsPolicy::Drop => item.remove_attribute(&value_type, &id),
+                    OrphanAttrsPolicy::Detach => item.detach_attribute(&value_type, &id),
+                }
+            }
+        }
+
+        for tc in &self.type_changes {
+            let Some((value_type, id)) = find_attr(item, &tc.from.id) else {
+                item.add_attribute_from_template(&tc.to);
+                continue;
+            };
+            let pos = item
+                .attributes_order
+                .iter()
+                .position(|(vt, oid)| vt == &value_type && oid == &id);
+            let old_value = item
+                .attribute(&value_type, &id)
+                .map(|a| a.value)
+                .unwrap_or_default();
+            item.remove_attribute(&value_type, &id);
+            item.add_attribute_from_template(&tc.to);
+
+            // The value is kept, if it is a valid one for the new value type.
+            let new_id = Id::from(tc.to.name.clone());
+            if Attr::validate_value(&tc.to.value_type, &old_value).is_ok() {
+                let attr = Attr {
+                    id: new_id.clone(),
+                    name: tc.to.name.clone(),
+                    value_type: Some(tc.to.value_type.clone()),
+                    value: old_value,
+                };
+                if let Err(e) = item.update_attribute(&attr) {
+                    log::warn!(
+                        "Failed to keep the value of attribute '{}': {}",
+                        attr.name,
+                        e
+                    );
+                }
+            }
+            // The attribute keeps its show position.
+            if let Some(pos) = pos
+                && let Some(entry) = item.attributes_order.pop()
+            {
+                item.attributes_order
+                    .insert(pos.min(item.attributes_order.len()), entry);
+            }
+        }
+
+        for added in &self.added_attrs {
+            if find_attr(item, &added.id).is_none() {
+                item.add_attribute_from_template(added);
+            }
+        }
+
+        item.kind = tmpl.name.clone();
+        item.listing_attr_tmpl_id = tmpl.listing_attr.id.clone();
+        item.listing_attr_name = tmpl.listing_attr.name.clone();
+        item.refresh_listing_attr();
+    }
+}
+
+/// Find the value type and the id of the item's attribute that is based on an attribute template.
+fn find_attr(item: &Item, attr_tmpl_id: &Id) -> Option<(AttributeValueType, Id)> {
+    let is_it = |tmpl_id: &Option<Id>| tmpl_id.as_ref() == Some(attr_tmpl_id);
+    item.text_attributes
+        .iter()
+        .find(|a| is_it(&a.tmpl_id))
+        .map(|a| (AttributeValueType::Text, a.id.clone()))
+        .or_else(|| {
+            item.numeric_attributes
+                .iter()
+                .find(|a| is_it(&a.tmpl_id))
+                .map(|a| (AttributeValueType::Numeric, a.id.clone()))
+        })
+        .or_else(|| {
+            item.boolean_attributes
+                .iter()
+                .find(|a| is_it(&a.tmpl_id))
+                .map(|a| (AttributeValueType::Boolean, a.id.clone()))
+        })
+        .or_else(|| {
+            item.date_attributes
+                .iter()
+                .find(|a| is_it(&a.tmpl_id))
+                .map(|a| (AttributeValueType::Date, a.id.clone()))
+        })
+        .or_else(|| {
+            item.datetime_attributes
+                .iter()
+                .find(|a| is_it(&a.tmpl_id))
+                .map(|a| (AttributeValueType::DateTime, a.id.clone()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ItemTemplateChangePlan, OrphanAttrsPolicy};
+    use crate::domain::{
+        logic::{
+            AttrViolation, AttrViolationKind,
+            test_fixtures::{attr_tmpl, server_tmpl},
+            validate_item,
+        },
+        model::{
+            Id,
+            meta::{AttrTemplate, AttributeValueType, Item, ItemTemplate, ItemTemplateLink},
+        },
+    };
+
+    fn with_default(attr_tmpl: AttrTemplate, default_value: &str) -> AttrTemplate {
+        AttrTemplate {
+            default_value: default_value.to_owned(),
+            ..attr_tmpl
+        }
+    }
+
+    fn old_template() -> ItemTemplate {
+        ItemTemplate {
+            links: vec![ItemTemplateLink {
+                name: "runs on".to_owned(),
+                item_template_id: Id::from("it-2"),
+            }],
+            ..server_tmpl(vec![
+                with_default(attr_tmpl("at-1", "name", AttributeValueType::Text), "srv"),
+                with_default(attr_tmpl("at-2", "cores", AttributeValueType::Text), "8"),
+                with_default(attr_tmpl("at-3", "rack", AttributeValueType::Text), "r1"),
+            ])
+        }
+    }
+
+    fn new_template() -> ItemTemplate {
+        server_tmpl(vec![
+            with_default(attr_tmpl("at-1", "name", AttributeValueType::Text), "srv"),
+            with_default(attr_tmpl("at-4", "cores", AttributeValueType::Numeric), "0"),
+            with_default(
+                attr_tmpl("at-5", "active", AttributeValueType::Boolean),
+                "true",
+            ),
+        ])
+    }
+
+    #[test]
+    fn plan_includes_all_the_changes() {
+        let plan = ItemTemplateChangePlan::new(&old_template(), &new_template());
+
+        assert_eq!(
+            plan.added_attrs
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["active"]
+        );
+        assert_eq!(
+            plan.removed_attrs
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["rack"]
+        );
+        assert_eq!(
+            plan.type_changes
+                .iter()
+                .map(|tc| (tc.to.name.as_str(), &tc.to.value_type))
+                .collect::<Vec<_>>(),
+            vec![("cores", &AttributeValueType::Numeric)]
+        );
+        assert_eq!(plan.removed_link_names, vec!["runs on".to_owned()]);
+    }
+
+    #[test]
+    fn unchanged_template_gives_an_empty_plan() {
+        assert!(ItemTemplateChangePlan::new(&old_template(), &old_template()).is_empty());
+    }
+
+    #[test]
+    fn applied_plan_makes_the_item_valid_against_the_new_template_except_for_detached_attrs() {
+        let (old, new) = (old_template(), new_template());
+        let plan = ItemTemplateChangePlan::new(&old, &new);
+
+        for orphans in [OrphanAttrsPolicy::Drop, OrphanAttrsPolicy::Detach] {
+            let mut item = Item::from(&old);
+            plan.apply_to(&mut item, &new, orphans);
+
+            // The detached attributes are not declared by the new template, thus they are to be removed.
+            let violations = match orphans {
+                OrphanAttrsPolicy::Drop => vec![],
+                OrphanAttrsPolicy::Detach => {
+                    vec![AttrViolation::new("rack", AttrViolationKind::Unknown)]
+                }
+            };
+            assert_eq!(validate_item(&item, Some(&new)), violations);
+            assert_eq!(
+                item.has_attribute("rack"),
+                orphans == OrphanAttrsPolicy::Detach
+            );
+            let names: Vec<String> = item
+                .attributes()
+                .into_iter()
+                .map(|a| a.name)
+                .take(3)
+                .collect();
+            assert_eq!(
+                names,
+                [
+                    "name",
+                    "cores",
+                    if orphans == OrphanAttrsPolicy::Drop {
+                        "active"
+                    } else {
+                        "rack"
+                    }
+                ]
+            );
+            let cores = item
+                .attributes()
+                .into_iter()
+                .find(|a| a.name == "cores")
+                .expect("the attribute exists");
+            assert_eq!(cores.value, "8");
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::domain::model::{
+    Id,
+    meta::{Attr, AttributeValueType, Item, ItemTemplate},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A violation of an item's attribute against the item's template.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttrViolation {
+    /// The name of the violating attribute.
+    pub attr_name: String,
+    pub kind: AttrViolationKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttrViolationKind {
+    /// A required attribute (as declared by the template) is missing or has an empty value.
+    MissingRequired,
+
+    /// The value type of the attribute is not the one declared by the template.
+    TypeMismatch {
+        expected: AttributeValueType,
+        actual: AttributeValueType,
+    },
+
+    /// The attribute is not declared by the template.
+    Unknown,
+
+    /// The value of the attribute is not valid for its value type.
+    InvalidValue(String),
+
+    /// The listing attribute is not the one declared by the template.
+    WrongListingAttr,
+}
+
+impl AttrViolation {
+    pub fn new(attr_name: impl Into<String>, kind: AttrViolationKind) -> Self {
+        Self {
+            attr_name: attr_name.into(),
+            kind,
+        }
+    }
+}
+
+impl Display for AttrViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            AttrViolationKind::MissingRequired => {
+                write!(f, "'{}' is required", self.attr_name)
+            }
+            AttrViolationKind::TypeMismatch { expected, actual } => {
+                write!(f, "'{}' must be {expected}, not {actual}", self.attr_name)
+            }
+            AttrViolationKind::Unknown => {
+                write!(
+                    f,
+                    "'{}' is not declared by the item template",
+                    self.attr_name
+                )
+            }
+            AttrViolationKind::InvalidValue(reason) => {
+                write!(f, "'{}' has an invalid value: {reason}", self.attr_name)
+            }
+            AttrViolationKind::WrongListingAttr => {
+                write!(
+                    f,
+                    "'{}' is not the listing attribute of the item template",
+                    self.attr_name
+                )
+            }
+        }
+    }
+}
+
+/// Validate an item against its template (if it has one).
+/// For items created from scratch, only the values of the attributes are validated.
+pub fn validate_item(item: &Item, tmpl: Option<&ItemTemplate>) -> Vec<AttrViolation> {
+    let attrs = attrs_with_tmpl_ids(item);
+    let mut violations = Vec::new();
+
+    for (attr, _) in &attrs {
+        let value_type = attr.value_type.clone().unwrap_or_default();
+        if let Err(reason) = Attr::validate_value(&value_type, &attr.value) {
+            violations.push(AttrViolation::new(
+                &attr.name,
+                AttrViolationKind::InvalidValue(reason),
+            ));
+        }
+    }
+
+    let Some(tmpl) = tmpl else {
+        if !item.listing_attr_tmpl_id.is_zero() {
+            violations.push(AttrViolation::new(
+                &item.listing_attr_name,
+                AttrViolationKind::WrongListingAttr,
+            ));
+        }
+        return violations;
+    };
+
+    // An item attribute corresponds to a template one either by its template id or,
+    // if that's missing (as when its value type was changed), by its name.
+    let find_attr = |tmpl_attr_id: &Id, tmpl_attr_name: &str| {
+        attrs
+            .iter()
+            .find(|(_, tmpl_id)| tmpl_id.as_ref() == Some(tmpl_attr_id))
+            .or_else(|| attrs.iter().find(|(a, _)| a.name == tmpl_attr_name))
+            .map(|(a, _)| a)
+    };
+
+    for attr_tmpl in &tmpl.attributes {
+        match find_attr(&attr_tmpl.id, &attr_tmpl.name) {
+            None => {
+                if attr_tmpl.is_required {
+                    violations.push(AttrViolation::new(
+                        &attr_tmpl.name,
+                        AttrViolationKind::MissingRequired,
+                    ));
+                }
+            }
+            Some(attr) => {
+                let actual = attr.value_type.clone().unwrap_or_default();
+                if actual != attr_tmpl.value_type {
+                    violations.push(AttrViolation::new(
+                        &attr.name,
+                        AttrViolationKind::TypeMismatch {
+                            expected: attr_tmpl.value_type.clone(),
+                            actual,
+                        },
+                    ));
+                } else if attr_tmpl.is_required && attr.value.trim().is_empty() {
+                    violations.push(AttrViolation::new(
+                        &attr.name,
+                        AttrViolationKind::MissingRequired,
+                    ));
+                }
+            }
+        }
+    }
+
+    // Neither the attributes based on an attribute template that is not declared are allowed,
+    // nor the free-form ones (without a template id), unless they correspond to a template one by name.
+    for (attr, tmpl_id) in &attrs {
+        let declared = match tmpl_id {
+            Some(tmpl_id) => tmpl.attributes.iter().any(|at| &at.id == tmpl_id),
+            None => tmpl.attributes.iter().any(|at| at.name == attr.name),
+        };
+        if !declared {
+            violations.push(AttrViolation::new(&attr.name, AttrViolationKind::Unknown));
+        }
+    }
+
+    if item.listing_attr_tmpl_id != tmpl.listing_attr.id {
+        violations.push(AttrViolation::new(
+            &item.listing_attr_name,
+            AttrViolationKind::WrongListingAttr,
+        ));
+    }
+
+    violations
+}
+
+/// Get the attributes of an item, together with their (optional) template ids.
+fn attrs_with_tmpl_ids(item: &Item) -> Vec<(Attr, Option<Id>)> {
+    let mut out = Vec::new();
+    let mut push = |value_type: AttributeValueType, id: &Id, tmpl_id: &Option<Id>| {
+        if let Some(attr) = item.attribute(&value_type, id) {
+            out.push((attr, tmpl_id.clone()));
+        }
+    };
+    for a in &item.text_attributes {
+        push(AttributeValueType::Text, &a.id, &a.tmpl_id);
+    }
+    for a in &item.numeric_attributes {
+        push(AttributeValueType::Numeric, &a.id, &a.tmpl_id);
+    }
+    for a in &item.boolean_attributes {
+        push(AttributeValueType::Boolean, &a.id, &a.tmpl_id);
+    }
+    for a in &item.date_attributes {
+        push(AttributeValueType::Date, &a.id, &a.tmpl_id);
+    }
+    for a in &item.datetime_attributes {
+        push(AttributeValueType::DateTime, &a.id, &a.tmpl_id);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AttrViolation, AttrViolationKind, validate_item};
+    use crate::domain::{
+        logic::test_fixtures::{attr_tmpl, required_attr_tmpl, server_tmpl},
+        model::{
+            Id,
+            meta::{Attr, AttributeValueType, Item, ItemTemplate},
+        },
+    };
+
+    fn template() -> ItemTemplate {
+        server_tmpl(vec![
+            required_attr_tmpl("at-1", "name", AttributeValueType::Text),
+            attr_tmpl("at-2", "cores", AttributeValueType::Numeric),
+        ])
+    }
+
+    fn valid_item() -> Item {
+        let mut item = Item::from(&template());
+        let name = item
+            .attributes()
+            .into_iter()
+            .find(|a| a.name == "name")
+            .expect("the attribute exists");
+        item.update_attribute(&Attr {
+            value: "srv-01".to_owned(),
+            ..name
+        })
+        .expect("the value is valid");
+        item
+    }
+
+    #[test]
+    fn item_created_from_its_template_is_valid() {
+        assert_eq!(
+            validate_item(&valid_item(), Some(&template())),
+            Vec::<AttrViolation>::new()
+        );
+    }
+
+    #[test]
+    fn missing_required_attribute_is_reported() {
+        let mut item = valid_item();
+        item.text_attributes.clear();
+
+        let violations = validate_item(&item, Some(&template()));
+
+        assert!(violations.contains(&AttrViolation::new(
+            "name",
+            AttrViolationKind::MissingRequired
+        )));
+    }
+
+    #[test]
+    fn type_mismatch_is_reported() {
+        let mut item = valid_item();
+        let cores = item
+            .attributes()
+            .into_iter()
+            .find(|a| a.name == "cores")
+            .expect("the attribute exists");
+        item.change_attr_value_type(&cores, &AttributeValueType::Text);
+
+        let violations = validate_item(&item, Some(&template()));
+
+        assert_eq!(
+            violations,
+            vec![AttrViolation::new(
+                "cores",
+                AttrViolationKind::TypeMismatch {
+                    expected: AttributeValueType::Numeric,
+                    actual: AttributeValueType::Text,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn unknown_attributes_and_wrong_listing_attr_are_reported() {
+        let mut item = valid_item();
+        item.add_attribute(Attr {
+            name: "color".to_owned(),
+            value_type: Some(AttributeValueType::Text),
+            value: "red".to_owned(),
+            ..Default::default()
+        });
+        item.add_attribute_from_template(&attr_tmpl("at-9", "rack", AttributeValueType::Text));
+        item.listing_attr_tmpl_id = Id::from("at-2");
+
+        let violations = validate_item(&item, Some(&template()));
+
+        assert!(violations.contains(&AttrViolation::new("color", AttrViolationKind::Unknown)));
+        assert!(violations.contains(&AttrViolation::new("rack", AttrViolationKind::Unknown)));
+        assert!(violations.contains(&AttrViolation::new(
+            "name",
+            AttrViolationKind::WrongListingAttr
+        )));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    domain::model::meta::{AttributeValueType, Item, ItemTemplate},
+    dtos::ExportFormat,
+};
+use serde_json::{Map, Number, Value};
+
+/// Writes the items of an item template in an export format, a chunk at a time.
+///
+/// Each item is flattened to an id column, followed by a column per attribute of the item template,
+/// in show order. An attribute that an item does not have is left empty (or `null`, in JSON).
+pub struct ItemsExporter {
+    format: ExportFormat,
+    columns: Vec<(String, AttributeValueType)>,
+    started: bool,
+    written: usize,
+}
+
+impl ItemsExporter {
+    pub fn new(format: ExportFormat, tmpl: &ItemTemplate) -> Self {
+        Self {
+            format,
+            columns: tmpl
+                .attributes
+                .iter()
+                .map(|at| (at.name.clone(), at.value_type.clone()))
+                .collect(),
+            started: false,
+            written: 0,
+        }
+    }
+
+    /// Write the items. The first call also writes the start of the export (such as the CSV header row).
+    pub fn write(&mut self, items: &[Item]) -> String {
+        let mut out = self.start();
+        for item in items {
+            let values = self.values(item);
+            match self.format {
+                ExportFormat::Csv => {
+                    let mut fields = vec![item.id.to_string()];
+                    fields.extend(values.into_iter().map(|v| v.unwrap_or_default()));
+                    out.push_str(&csv_row(&fields));
+                }
+                ExportFormat::Json | ExportFormat::Ndjson => {
+                    if self.format == ExportFormat::Json && self.written > 0 {
+                        out.push_str(",\n");
+                    }
+                    out.push_str(&self.json_object(item, values).to_string());
+                    if self.format == ExportFormat::Ndjson {
+                        out.push('\n');
+                    }
+                }
+            }
+            self.written += 1;
+        }
+        out
+    }
+
+    /// Write the end of the export (such as the closing bracket of the JSON array).
+    pub fn finish(&mut self) -> String {
+        let mut out = self.start();
+        if self.format == ExportFormat::Json {
+            out.push_str(if self.written > 0 { "\n]\n" } else { "]\n" });
+        }
+        out
+    }
+
+    fn start(&mut self) -> String {
+        if self.started {
+            return String::new();
+        }
+        self.started = true;
+        match self.format {
+            ExportFormat::Csv => {
+                let mut header = vec![String::from("id")];
+                header.extend(self.columns.iter().map(|(name, _)| name.clone()));
+                csv_row(&header)
+            }
+            ExportFormat::Json => String::from("[\n"),
+            ExportFormat::Ndjson => String::new(),
+        }
+    }
+
+    /// Get the values of the item's attributes, by the columns.
+    fn values(&self, item: &Item) -> Vec<Option<String>> {
+        let attrs = item.attributes();
+        self.columns
+            .iter()
+            .map(|(name, _)| {
+                attrs
+                    .iter()
+                    .find(|a| &a.name == name)
+                    .map(|a| a.value.clone())
+            })
+            .collect()
+    }
+
+    /// Get the item as a JSON object, with its values typed as per the columns.
+    fn json_object(&self, item: &Item, values: Vec<Option<String>>) -> Value {
+        let mut obj = Map::new();
+        obj.insert(String::from("id"), Value::String(item.id.to_string()));
+        for ((name, value_type), value) in self.columns.iter().zip(values) {
+            let value = match (value_type, value) {
+                (_, None) => Value::Null,
+                (AttributeValueType::Numeric, Some(v)) => {
+                    v.parse::<Number>().map_or(Value::String(v), Value::Number)
+                }
+                (AttributeValueType::Boolean, Some(v)) => Value::Bool(v == "true"),
+                (_, Some(v)) => Value::String(v),
+            };
+            obj.insert(name.clone(), value);
+        }
+        Value::Object(obj)
+    }
+}
+
+/// Write a CSV row (as of RFC 4180), that ends with a line break.
+/// The fields that contain commas, double quotes or line breaks are enclosed in double quotes.
+pub fn csv_row(fields: &[String]) -> String {
+    let mut row = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ItemsExporter, csv_row};
+    use crate::{
+        domain::{
+            logic::{
+                parse_csv,
+                test_fixtures::{attr_tmpl, server_tmpl},
+            },
+            model::{
+                Id,
+                meta::{AttributeValueType, Item, ItemTemplate},
+            },
+        },
+        dtos::ExportFormat,
+    };
+
+    fn hostname_cores_tmpl() -> ItemTemplate {
+        server_tmpl(vec![
+            attr_tmpl("at-1", "Hostname", AttributeValueType::Text),
+            attr_tmpl("at-2", "Cores", AttributeValueType::Numeric),
+        ])
+    }
+
+    fn export(format: ExportFormat, tmpl: &ItemTemplate, items: &[Item]) -> String {
+        let mut exporter = ItemsExporter::new(format, tmpl);
+        let mut out = exporter.write(items);
+        out.push_str(&exporter.finish());
+        out
+    }
+
+    #[test]
+    fn csv_rows_are_read_back_as_written() {
+        let fields = vec![
+            "a, b".to_owned(),
+            "say \"hi\"".to_owned(),
+            "1\n2".to_owned(),
+            String::new(),
+        ];
+
+        let rows = parse_csv(&csv_row(&fields)).expect("the CSV is valid");
+
+        assert_eq!(rows, vec![(1, fields)]);
+    }
+
+    #[test]
+    fn items_are_flattened_in_the_template_attributes_order() {
+        let tmpl = hostname_cores_tmpl();
+        let mut item = Item::from(&tmpl);
+        item.id = Id::from("i-1");
+        item.text_attributes
+            .iter_mut()
+            .for_each(|a| a.value = "srv-1".to_owned());
+
+        let out = export(ExportFormat::Csv, &tmpl, std::slice::from_ref(&item));
+        assert_eq!(out, "id,Hostname,Cores\r\ni-1,srv-1,0\r\n");
+
+        let out = export(ExportFormat::Json, &tmpl, &[item.clone(), item.clone()]);
+        let value: serde_json::Value = serde_json::from_str(&out).expect("the JSON is valid");
+        assert_eq!(
+            value.get(1),
+            Some(&serde_json::json!({ "id": "i-1", "Hostname": "srv-1", "Cores": 0 }))
+        );
+
+        let out = export(ExportFormat::Ndjson, &tmpl, &[item.clone(), item]);
+        assert_eq!(out.lines().count(), 2);
+
+        assert_eq!(export(ExportFormat::Json, &tmpl, &[]), "[\n]\n");
+    }
+}
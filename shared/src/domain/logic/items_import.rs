@@ -0,0 +1,259 @@
+use crate::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::validate_item,
+        model::{
+            Id,
+            meta::{Attr, AttributeValueType, Item, ItemTemplate},
+        },
+    },
+    dtos::ImportRowError,
+};
+use std::collections::HashMap;
+
+/// A row of a CSV text, along with the (1-based) line number where it starts.
+pub type CsvRow = (usize, Vec<String>);
+
+/// Read the rows of a CSV text (as of RFC 4180).
+///
+/// The fields are separated by commas, and a field that contains commas, double quotes or line breaks
+/// is enclosed in double quotes, with its double quotes written twice.
+/// Both `\n` and `\r\n` line breaks are accepted, and blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<CsvRow>, String> {
+    let mut rows = Vec::new();
+    let (mut row, mut field) = (Vec::new(), String::new());
+    let (mut line, mut row_line) = (1, 1);
+    let (mut quoted, mut was_quoted) = (false, false);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !was_quoted => (quoted, was_quoted) = (true, true),
+            '"' => {
+                return Err(format!(
+                    "line {line}: a double quote must be within a quoted field"
+                ));
+            }
+            ',' => {
+                row.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                if !row.is_empty() || !field.is_empty() || was_quoted {
+                    row.push(std::mem::take(&mut field));
+                    rows.push((row_line, std::mem::take(&mut row)));
+                }
+                was_quoted = false;
+                line += 1;
+                row_line = line;
+            }
+            _ if was_quoted => {
+                return Err(format!(
+                    "line {line}: nothing may follow the closing double quote of a field"
+                ));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("line {row_line}: a quoted field is not closed"));
+    }
+    if !row.is_empty() || !field.is_empty() || was_quoted {
+        row.push(field);
+        rows.push((row_line, row));
+    }
+    Ok(rows)
+}
+
+/// The items to be imported from the rows of a CSV text, and the errors of the rows that cannot be.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemsImport {
+    pub items: Vec<Item>,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Create an item (from the item template) for each row of a CSV text, that has a header row.
+///
+/// Each mapped column (by its name, to the id of an attribute template of the item template)
+/// sets the value of that attribute, while the other attributes keep their default values.
+/// An empty cell keeps the default value as well.
+///
+/// The (whole) import is refused if the CSV text is malformed or the column mapping is not valid.
+/// Otherwise, each row that is not valid against the item template is reported as an error.
+pub fn items_from_csv(
+    tmpl: &ItemTemplate,
+    csv: &str,
+    columns: &HashMap<String, Id>,
+) -> AppResult<ItemsImport> {
+    let mut rows = parse_csv(csv).map_err(AppError::InvalidInput)?.into_iter();
+    let Some((_, header)) = rows.next() else {
+        return Err(AppError::InvalidInput(
+            "the CSV text has no header row".to_owned(),
+        ));
+    };
+
+    let mut columns = columns.iter().collect::<Vec<_>>();
+    columns.sort_by_key(|(column, _)| *column);
+    let mut mapped = Vec::new();
+    for (column, attr_tmpl_id) in columns {
+        let Some(idx) = header.iter().position(|name| name.trim() == column.trim()) else {
+            return Err(AppError::InvalidInput(format!(
+                "there is no column named '{column}'"
+            )));
+        };
+        let Some(attr_tmpl) = tmpl.attributes.iter().find(|at| &at.id == attr_tmpl_id) else {
+            return Err(AppError::InvalidInput(format!(
+                "column '{column}' is mapped to an attribute that the item template '{}' does not have",
+                tmpl.name
+            )));
+        };
+        mapped.push((idx, attr_tmpl));
+    }
+    mapped.sort_by_key(|(idx, _)| *idx);
+
+    let mut import = ItemsImport::default();
+    for (line, cells) in rows {
+        if cells.len() != header.len() {
+            import.errors.push(ImportRowError {
+                line,
+                message: format!("it has {} fields, instead of {}", cells.len(), header.len()),
+            });
+            continue;
+        }
+        let mut item = Item::from(tmpl);
+        let mut messages = Vec::new();
+        for (idx, attr_tmpl) in &mapped {
+            let cell = cells.get(*idx).map(String::as_str).unwrap_or_default();
+            let value = match attr_tmpl.value_type {
+                AttributeValueType::Text => cell.to_owned(),
+                _ => cell.trim().to_owned(),
+            };
+            if value.is_empty() {
+                continue;
+            }
+            if let Err(reason) = Attr::validate_value(&attr_tmpl.value_type, &value) {
+                messages.push(format!(
+                    "'{}' has an invalid value: {}",
+                    attr_tmpl.name,
+                    reason.replace('\n', " ")
+                ));
+                continue;
+            }
+            let attr = item
+                .attributes()
+                .into_iter()
+                .find(|a| a.name == attr_tmpl.name);
+            if let Some(attr) = attr
+                && let Err(err) = item.update_attribute(&Attr { value, ..attr })
+            {
+                messages.push(format!("'{}' has an invalid value: {err}", attr_tmpl.name));
+            }
+        }
+        if messages.is_empty() {
+            item.refresh_listing_attr();
+            messages.extend(
+                validate_item(&item, Some(tmpl))
+                    .iter()
+                    .map(ToString::to_string),
+            );
+        }
+        if messages.is_empty() {
+            import.items.push(item);
+        } else {
+            import.errors.push(ImportRowError {
+                line,
+                message: messages.join("; "),
+            });
+        }
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{items_from_csv, parse_csv};
+    use crate::domain::{
+        logic::test_fixtures::{attr_tmpl, required_attr_tmpl, server_tmpl},
+        model::{
+            Id,
+            meta::{AttributeValueType, ItemTemplate},
+        },
+    };
+    use std::collections::HashMap;
+
+    fn hostname_cores_tmpl() -> ItemTemplate {
+        server_tmpl(vec![
+            required_attr_tmpl("at-1", "Hostname", AttributeValueType::Text),
+            attr_tmpl("at-2", "Cores", AttributeValueType::Numeric),
+        ])
+    }
+
+    #[test]
+    fn quoted_fields_may_have_commas_quotes_and_line_breaks() {
+        let rows = parse_csv("a,b\r\n\"x, \"\"y\"\"\",\"1\n2\"\n\nz,\n").expect("the CSV is valid");
+
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["a".to_owned(), "b".to_owned()]),
+                (2, vec!["x, \"y\"".to_owned(), "1\n2".to_owned()]),
+                (5, vec!["z".to_owned(), String::new()]),
+            ]
+        );
+        assert!(parse_csv("a,\"b\n").is_err());
+    }
+
+    #[test]
+    fn rows_are_imported_or_reported_with_their_line() {
+        let columns = HashMap::from([
+            ("Host".to_owned(), Id::from("at-1")),
+            ("CPU".to_owned(), Id::from("at-2")),
+        ]);
+        let csv = "Host,CPU,Ignored\nsrv-1,8,x\n,4,x\nsrv-3,many,x\n";
+
+        let import =
+            items_from_csv(&hostname_cores_tmpl(), csv, &columns).expect("the mapping is valid");
+
+        let names = import
+            .items
+            .iter()
+            .map(|i| i.listing_attr_value.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["srv-1"]);
+        let cores = import
+            .items
+            .iter()
+            .flat_map(|i| &i.numeric_attributes)
+            .map(|a| a.value.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(cores, vec!["8"]);
+        let lines = import.errors.iter().map(|e| e.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![3, 4]);
+        assert!(
+            import
+                .errors
+                .iter()
+                .any(|e| e.line == 4 && e.message.contains("'Cores' has an invalid value"))
+        );
+
+        let unknown = HashMap::from([("Owner".to_owned(), Id::from("at-1"))]);
+        assert!(items_from_csv(&hostname_cores_tmpl(), csv, &unknown).is_err());
+    }
+}
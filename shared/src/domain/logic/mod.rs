@@ -0,0 +1,26 @@
+mod item_validation;
+pub use item_validation::*;
+
+mod item_tmpl_change;
+pub use item_tmpl_change::*;
+
+mod item_query;
+pub use item_query::*;
+
+mod revision_diff;
+pub use revision_diff::*;
+
+mod items_import;
+pub use items_import::*;
+
+mod items_export;
+pub use items_export::*;
+
+mod bundle_import;
+pub use bundle_import::*;
+
+mod user_check;
+pub use user_check::*;
+
+#[cfg(test)]
+mod test_fixtures;
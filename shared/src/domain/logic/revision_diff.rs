@@ -0,0 +1,272 @@
+use crate::{
+    app::{AppError, AppResult},
+    domain::model::{
+        AccessLevel, Revision,
+        meta::{AttrTemplate, Item, ItemTemplate, Kind},
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A field whose value differs between two revisions (or copies) of an element.
+/// A side is `None` if the field does not exist there (such as an added attribute of an item).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl Revision {
+    /// Get the (human readable) fields of the element, as it was in this revision, in show order.
+    pub fn fields(&self) -> AppResult<Vec<(String, String)>> {
+        snapshot_fields(&self.kind, &self.snapshot)
+    }
+}
+
+/// Get the (human readable) fields of an element of the provided kind, out of its JSON snapshot,
+/// in show order.
+pub fn snapshot_fields(kind: &Kind, snapshot: &Value) -> AppResult<Vec<(String, String)>> {
+    let invalid = |err: serde_json::Error| {
+        AppError::ErrDetails("Invalid revision snapshot".to_owned(), err.to_string())
+    };
+    let fields = match kind {
+        Kind::Item => {
+            let item: Item = serde_json::from_value(snapshot.clone()).map_err(invalid)?;
+            let mut fields = vec![
+                ("kind".to_owned(), item.kind.clone()),
+                (
+                    "listing attribute".to_owned(),
+                    item.listing_attr_name.clone(),
+                ),
+                (
+                    "access level".to_owned(),
+                    item.access_level_id
+                        .as_ref()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                ),
+            ];
+            fields.extend(
+                item.attributes()
+                    .into_iter()
+                    .map(|a| (format!("attribute '{}'", a.name), a.value)),
+            );
+            fields
+        }
+        Kind::ItemTemplate => {
+            let tmpl: ItemTemplate = serde_json::from_value(snapshot.clone()).map_err(invalid)?;
+            vec![
+                ("name".to_owned(), tmpl.name),
+                ("description".to_owned(), tmpl.description),
+                ("listing attribute".to_owned(), tmpl.listing_attr.name),
+                (
+                    "attributes".to_owned(),
+                    join(tmpl.attributes.iter().map(|a| &a.name)),
+                ),
+                ("links".to_owned(), join(tmpl.links.iter().map(|l| &l.name))),
+            ]
+        }
+        Kind::AttributeTemplate => {
+            let tmpl: AttrTemplate = serde_json::from_value(snapshot.clone()).map_err(invalid)?;
+            vec![
+                ("name".to_owned(), tmpl.name),
+                ("description".to_owned(), tmpl.description),
+                ("value type".to_owned(), tmpl.value_type.to_string()),
+                ("default value".to_owned(), tmpl.default_value),
+                ("required".to_owned(), tmpl.is_required.to_string()),
+            ]
+        }
+        Kind::AccessLevel => {
+            let level: AccessLevel = serde_json::from_value(snapshot.clone()).map_err(invalid)?;
+            vec![
+                ("name".to_owned(), level.name),
+                (
+                    "description".to_owned(),
+                    level.description.unwrap_or_default(),
+                ),
+                ("rank".to_owned(), level.rank.to_string()),
+            ]
+        }
+        Kind::LinkTemplate => {
+            return Err(AppError::InvalidInput(
+                "Link templates have no revisions".to_owned(),
+            ));
+        }
+        Kind::User => {
+            return Err(AppError::InvalidInput("Users have no revisions".to_owned()));
+        }
+    };
+    Ok(fields)
+}
+
+/// Compare two revisions of the same element, field by field.
+/// Only the fields with different values are returned, the ones of `old` first.
+pub fn diff_revisions(old: &Revision, new: &Revision) -> AppResult<Vec<FieldChange>> {
+    if old.kind != new.kind || old.element_id != new.element_id {
+        return Err(AppError::InvalidInput(
+            "Only the revisions of the same element can be compared".to_owned(),
+        ));
+    }
+    diff_snapshots(&old.kind, &old.snapshot, &new.snapshot)
+}
+
+/// Compare two (JSON snapshots of) copies of an element of the provided kind, field by field.
+/// Only the fields with different values are returned, the ones of `old` first.
+pub fn diff_snapshots(kind: &Kind, old: &Value, new: &Value) -> AppResult<Vec<FieldChange>> {
+    let old_fields = snapshot_fields(kind, old)?;
+    let new_fields = snapshot_fields(kind, new)?;
+    let value_of = |fields: &[(String, String)], field: &str| {
+        fields
+            .iter()
+            .find(|(f, _)| f == field)
+            .map(|(_, v)| v.clone())
+    };
+
+    let mut changes: Vec<FieldChange> = old_fields
+        .iter()
+        .map(|(field, value)| FieldChange {
+            field: field.clone(),
+            old: Some(value.clone()),
+            new: value_of(&new_fields, field),
+        })
+        .filter(|c| c.old != c.new)
+        .collect();
+    changes.extend(
+        new_fields
+            .iter()
+            .filter(|(field, _)| value_of(&old_fields, field).is_none())
+            .map(|(field, value)| FieldChange {
+                field: field.clone(),
+                old: None,
+                new: Some(value.clone()),
+            }),
+    );
+    Ok(changes)
+}
+
+fn join<'a>(names: impl Iterator<Item = &'a String>) -> String {
+    names.map(String::as_str).collect::<Vec<&str>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FieldChange, diff_revisions, diff_snapshots};
+    use crate::domain::model::{
+        AccessLevel, Id, Revision,
+        meta::{AttrTemplate, Item, Kind},
+    };
+
+    fn revision(number: i32, tmpl: &AttrTemplate) -> Revision {
+        Revision {
+            kind: Kind::AttributeTemplate,
+            element_id: tmpl.id.clone(),
+            number,
+            snapshot: serde_json::to_value(tmpl).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_the_changed_fields_are_reported() {
+        let old = AttrTemplate {
+            id: Id::from("at-1"),
+            name: String::from("host"),
+            ..Default::default()
+        };
+        let new = AttrTemplate {
+            name: String::from("hostname"),
+            is_required: true,
+            ..old.clone()
+        };
+
+        let changes = diff_revisions(&revision(1, &old), &revision(2, &new));
+        assert_eq!(
+            changes.ok(),
+            Some(vec![
+                FieldChange {
+                    field: String::from("name"),
+                    old: Some(String::from("host")),
+                    new: Some(String::from("hostname")),
+                },
+                FieldChange {
+                    field: String::from("required"),
+                    old: Some(String::from("false")),
+                    new: Some(String::from("true")),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn the_version_is_not_a_change() {
+        let old = AttrTemplate {
+            name: String::from("host"),
+            version: 1,
+            ..Default::default()
+        };
+        let new = AttrTemplate {
+            version: 2,
+            ..old.clone()
+        };
+        let to_value = |tmpl: &AttrTemplate| serde_json::to_value(tmpl).unwrap_or_default();
+
+        let changes = diff_snapshots(&Kind::AttributeTemplate, &to_value(&old), &to_value(&new));
+        assert_eq!(changes.ok(), Some(Vec::new()));
+    }
+
+    #[test]
+    fn a_changed_access_level_of_an_item_is_reported() {
+        let old = Item {
+            id: Id::from("i-1"),
+            ..Default::default()
+        };
+        let new = Item {
+            access_level_id: Some(Id::from("al-1")),
+            ..old.clone()
+        };
+        let to_value = |item: &Item| serde_json::to_value(item).unwrap_or_default();
+
+        let changes = diff_snapshots(&Kind::Item, &to_value(&old), &to_value(&new));
+        assert_eq!(
+            changes.ok(),
+            Some(vec![FieldChange {
+                field: "access level".to_owned(),
+                old: Some(String::new()),
+                new: Some("al-1".to_owned()),
+            }])
+        );
+    }
+
+    #[test]
+    fn a_changed_rank_of_an_access_level_is_reported() {
+        let old = AccessLevel {
+            name: "Private".to_owned(),
+            rank: 1,
+            ..Default::default()
+        };
+        let new = AccessLevel {
+            rank: 2,
+            ..old.clone()
+        };
+        let to_value = |level: &AccessLevel| serde_json::to_value(level).unwrap_or_default();
+
+        let changes = diff_snapshots(&Kind::AccessLevel, &to_value(&old), &to_value(&new));
+        assert_eq!(
+            changes.ok(),
+            Some(vec![FieldChange {
+                field: "rank".to_owned(),
+                old: Some("1".to_owned()),
+                new: Some("2".to_owned()),
+            }])
+        );
+    }
+
+    #[test]
+    fn revisions_of_different_elements_are_not_compared() {
+        let tmpl = AttrTemplate::default();
+        let mut other = revision(1, &tmpl);
+        other.element_id = Id::from("at-2");
+        assert!(diff_revisions(&revision(1, &tmpl), &other).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+//! The fixtures that are shared by the tests of the domain logic.
+
+use crate::domain::model::{
+    Id,
+    meta::{AttrTemplate, AttributeValueType, ItemTemplate},
+};
+
+/// An optional attribute template, without a default value.
+pub(super) fn attr_tmpl(id: &str, name: &str, value_type: AttributeValueType) -> AttrTemplate {
+    AttrTemplate {
+        id: Id::from(id),
+        name: name.to_owned(),
+        value_type,
+        ..Default::default()
+    }
+}
+
+/// A required attribute template, without a default value.
+pub(super) fn required_attr_tmpl(
+    id: &str,
+    name: &str,
+    value_type: AttributeValueType,
+) -> AttrTemplate {
+    AttrTemplate {
+        is_required: true,
+        ..attr_tmpl(id, name, value_type)
+    }
+}
+
+/// The "Server" item template (`it-1`) with the provided attributes, the first one being the listing one.
+pub(super) fn server_tmpl(attributes: Vec<AttrTemplate>) -> ItemTemplate {
+    ItemTemplate {
+        id: Id::from("it-1"),
+        name: "Server".to_owned(),
+        listing_attr: attributes.first().cloned().unwrap_or_default(),
+        attributes,
+        ..Default::default()
+    }
+}
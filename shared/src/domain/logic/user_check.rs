@@ -0,0 +1,193 @@
+use crate::{
+    app::{AppError, AppResult},
+    domain::model::{PERMISSIONS, UserAccount},
+    dtos::ApiTokenCreateRequest,
+};
+
+/// The min number of characters of a (new) password.
+pub const PASSWORD_MIN_LEN: usize = 8;
+
+/// The max number of days that an API token can be valid for.
+pub const API_TOKEN_MAX_DAYS: u32 = 365;
+
+/// Check a user account before saving it, along with its password.
+///
+/// A new account (one without an id) must have a password, while for an existing one it is optional
+/// (and it replaces the current one, if provided).
+pub fn check_user_account(user: &UserAccount, password: Option<&str>) -> AppResult<()> {
+    check_length("username", &user.username, 48)?;
+    check_user_profile(&user.name, &user.email)?;
+    if let Some(perm) = user
+        .permissions
+        .iter()
+        .find(|p| !PERMISSIONS.contains(&p.as_str()))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "unknown permission '{perm}'"
+        )));
+    }
+    match password {
+        Some(password) => check_new_password(password),
+        None if user.id.is_zero() => {
+            Err(AppError::InvalidInput("a password is required".to_owned()))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Check the profile attributes of a user account, that its user may edit as well.
+pub fn check_user_profile(name: &str, email: &str) -> AppResult<()> {
+    check_length("name", name, 128)?;
+    check_length("email", email, 64)?;
+    if !email.contains('@') {
+        return Err(AppError::InvalidInput(format!(
+            "'{email}' is not an email address"
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a password is strong enough to be set.
+pub fn check_new_password(password: &str) -> AppResult<()> {
+    if password.chars().count() < PASSWORD_MIN_LEN {
+        return Err(AppError::InvalidInput(format!(
+            "the password must have at least {PASSWORD_MIN_LEN} characters"
+        )));
+    }
+    Ok(())
+}
+
+/// Check the request of creating an API token for a user.
+///
+/// The token must be scoped to at least one permission, all of them being ones that the user has.
+pub fn check_api_token_request(req: &ApiTokenCreateRequest, user: &UserAccount) -> AppResult<()> {
+    check_length("token name", &req.name, 64)?;
+    if req.permissions.is_empty() {
+        return Err(AppError::InvalidInput(
+            "the token must have at least one permission".to_owned(),
+        ));
+    }
+    if let Some(perm) = req
+        .permissions
+        .iter()
+        .find(|p| !user.permissions.contains(p))
+    {
+        return Err(AppError::InvalidInput(format!(
+            "you do not have the '{perm}' permission"
+        )));
+    }
+    if req
+        .expires_in_days
+        .is_some_and(|days| days == 0 || days > API_TOKEN_MAX_DAYS)
+    {
+        return Err(AppError::InvalidInput(format!(
+            "the token must expire in 1 to {API_TOKEN_MAX_DAYS} days"
+        )));
+    }
+    Ok(())
+}
+
+fn check_length(field: &str, value: &str, max: usize) -> AppResult<()> {
+    let len = value.trim().chars().count();
+    if len == 0 || len > max {
+        return Err(AppError::InvalidInput(format!(
+            "the {field} must have 1 to {max} characters"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_api_token_request, check_user_account, check_user_profile};
+    use crate::{
+        app::AppError,
+        domain::model::{Id, PERM_ADMIN_READ, PERM_ADMIN_WRITE, UserAccount},
+        dtos::ApiTokenCreateRequest,
+    };
+
+    fn user(id: &str) -> UserAccount {
+        UserAccount {
+            id: Id::from(id),
+            username: "jdoe".to_owned(),
+            name: "John Doe".to_owned(),
+            email: "jdoe@example.com".to_owned(),
+            permissions: vec![PERM_ADMIN_READ.to_owned()],
+            is_anonymous: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn new_user_requires_a_password() {
+        assert!(matches!(
+            check_user_account(&user(""), None),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            check_user_account(&user(""), Some("short")),
+            Err(AppError::InvalidInput(_))
+        ));
+        assert_eq!(check_user_account(&user(""), Some("long enough")), Ok(()));
+        assert_eq!(check_user_account(&user("u-1"), None), Ok(()));
+    }
+
+    #[test]
+    fn profile_requires_a_name_and_an_email_address() {
+        assert_eq!(check_user_profile("John Doe", "jdoe@example.com"), Ok(()));
+        assert_eq!(
+            check_user_profile("John Doe", "jdoe"),
+            Err(AppError::InvalidInput(
+                "'jdoe' is not an email address".to_owned()
+            ))
+        );
+        assert!(matches!(
+            check_user_profile("  ", "jdoe@example.com"),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_permission_is_refused() {
+        let mut user = user("u-1");
+        user.permissions.push("Admin::Everything".to_owned());
+
+        assert_eq!(
+            check_user_account(&user, None),
+            Err(AppError::InvalidInput(
+                "unknown permission 'Admin::Everything'".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn api_token_is_scoped_to_the_user_permissions() {
+        let mut req = ApiTokenCreateRequest {
+            name: "backup script".to_owned(),
+            permissions: vec![PERM_ADMIN_READ.to_owned()],
+            expires_in_days: Some(30),
+        };
+        assert_eq!(check_api_token_request(&req, &user("u-1")), Ok(()));
+
+        req.permissions.push(PERM_ADMIN_WRITE.to_owned());
+        assert_eq!(
+            check_api_token_request(&req, &user("u-1")),
+            Err(AppError::InvalidInput(
+                "you do not have the 'Admin::Write' permission".to_owned()
+            ))
+        );
+
+        req.permissions.clear();
+        assert!(matches!(
+            check_api_token_request(&req, &user("u-1")),
+            Err(AppError::InvalidInput(_))
+        ));
+
+        req.permissions.push(PERM_ADMIN_READ.to_owned());
+        req.expires_in_days = Some(0);
+        assert!(matches!(
+            check_api_token_request(&req, &user("u-1")),
+            Err(AppError::InvalidInput(_))
+        ));
+    }
+}
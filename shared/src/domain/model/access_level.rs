@@ -6,6 +6,15 @@ pub struct AccessLevel {
     pub id: Id,
     pub name: String,
     pub description: Option<String>,
+
+    /// How restricted it is: the higher, the more restricted. A user sees only the items
+    /// whose access level has a rank up to the user's clearance.
+    #[serde(default)]
+    pub rank: i32,
+
+    /// The version, for optimistic concurrency.
+    #[serde(default)]
+    pub version: i32,
 }
 
 impl AccessLevel {
@@ -14,6 +23,8 @@ impl AccessLevel {
             id,
             name: name.into(),
             description: description.map(Into::into),
+            rank: 0,
+            version: 0,
         }
     }
 }
@@ -0,0 +1,45 @@
+use crate::domain::model::Id;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The prefix of the API tokens' secrets, that tells them apart from the session ids.
+pub const API_TOKEN_PREFIX: &str = "cogs_";
+
+/// A personal API token of a user, for scripts and integrations.
+///
+/// It grants (at most) the subset of its user's permissions that it is scoped to, until it expires
+/// or it is revoked. Its secret is not included, as only the hash of it is kept.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Id,
+    pub name: String,
+
+    /// The beginning of the secret, that helps to recognize the token.
+    pub hint: String,
+
+    /// The permissions that the token is scoped to.
+    pub permissions: Vec<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    /// When the token expires, if ever.
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// When the token was last used, if ever.
+    pub last_used_at: Option<DateTime<Utc>>,
+
+    /// The token has expired, thus it cannot be used anymore.
+    #[serde(default)]
+    pub expired: bool,
+}
+
+impl ApiToken {
+    /// The permissions that the token grants: the ones of its scope that its user (still) has.
+    pub fn granted_permissions(&self, user_permissions: &[String]) -> Vec<String> {
+        self.permissions
+            .iter()
+            .filter(|perm| user_permissions.contains(perm))
+            .cloned()
+            .collect()
+    }
+}
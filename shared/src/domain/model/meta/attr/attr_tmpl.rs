@@ -11,6 +11,10 @@ pub struct AttrTemplate {
     pub value_type: AttributeValueType,
     pub default_value: String,
     pub is_required: bool,
+
+    /// The version, for optimistic concurrency.
+    #[serde(default)]
+    pub version: i32,
 }
 
 impl Hash for AttrTemplate {
@@ -38,15 +38,14 @@ impl DateAttribute {
 
 impl From<AttrTemplate> for DateAttribute {
     fn from(at: AttrTemplate) -> Self {
-        let value = NaiveDate::from_ymd_opt(at.default_value.parse().unwrap(), 1, 1)
-            .unwrap_or_else(|| {
-                log::error!(
-                    "Failed to parse value '{}' for date attribute '{}'.",
-                    at.default_value,
-                    at.name,
-                );
-                Utc::now().date_naive()
-            });
+        let value = NaiveDate::parse_from_str(&at.default_value, "%Y-%m-%d").unwrap_or_else(|_| {
+            log::error!(
+                "Failed to parse value '{}' for date attribute '{}'.",
+                at.default_value,
+                at.name,
+            );
+            Utc::now().date_naive()
+        });
 
         Self::new(
             Id::default(), // its id
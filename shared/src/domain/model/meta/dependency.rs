@@ -0,0 +1,44 @@
+use crate::domain::model::{Id, meta::Kind};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// An element that refers another one, thus preventing its deletion.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// The kind of the referring element.
+    pub kind: Kind,
+
+    /// The id of the referring element.
+    pub id: Id,
+
+    /// The name of the referring element (the listing attribute value, in case of items).
+    pub name: String,
+
+    /// How the element refers the other one (such as "attribute" or "listing attribute").
+    pub relation: String,
+}
+
+impl Dependency {
+    pub fn new(kind: Kind, id: Id, name: impl Into<String>, relation: impl Into<String>) -> Self {
+        Self {
+            kind,
+            id,
+            name: name.into(),
+            relation: relation.into(),
+        }
+    }
+}
+
+impl Display for Dependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            Kind::Item => "item",
+            Kind::ItemTemplate => "item template",
+            Kind::AttributeTemplate => "attribute template",
+            Kind::LinkTemplate => "link template",
+            Kind::AccessLevel => "access level",
+            Kind::User => "user",
+        };
+        write!(f, "{kind} '{}' (as {})", self.name, self.relation)
+    }
+}
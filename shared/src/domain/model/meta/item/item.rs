@@ -1,8 +1,8 @@
 use crate::domain::model::{
     Id,
     meta::{
-        Attr, AttributeValueType, BooleanAttribute, DateAttribute, DateTimeAttribute,
-        NumericAttribute, TextAttribute,
+        Attr, AttrTemplate, AttributeValueType, BooleanAttribute, DateAttribute, DateTimeAttribute,
+        ItemTemplate, NumericAttribute, TextAttribute,
     },
 };
 use anyhow::Error;
@@ -27,6 +27,11 @@ pub struct Item {
     /// Its template id, if it was created from a template.
     pub tmpl_id: Option<Id>,
 
+    /// The access level that is needed to see it. Without one, it's seen by everyone.
+    /// On creation, it defaults to the one of its template.
+    #[serde(default)]
+    pub access_level_id: Option<Id>,
+
     /// The show order of the attributes.
     #[serde(default)]
     pub attributes_order: Vec<(AttributeValueType, Id)>,
@@ -45,6 +50,10 @@ pub struct Item {
 
     #[serde(default)]
     pub datetime_attributes: Vec<DateTimeAttribute>,
+
+    /// The version, for optimistic concurrency.
+    #[serde(default)]
+    pub version: i32,
 }
 
 impl Item {
@@ -119,6 +128,182 @@ impl Item {
         self.attributes_order.push((value_type, attr_id));
     }
 
+    /// Add an attribute based on its template, using its default value.
+    pub fn add_attribute_from_template(&mut self, attr_tmpl: &AttrTemplate) {
+        let id = Id::from(attr_tmpl.name.clone());
+        let owner_id = self.id.clone();
+        let value_type = attr_tmpl.value_type.clone();
+        match value_type {
+            AttributeValueType::Text => self.text_attributes.push(TextAttribute {
+                id: id.clone(),
+                owner_id,
+                ..TextAttribute::from(attr_tmpl.clone())
+            }),
+            AttributeValueType::Numeric => self.numeric_attributes.push(NumericAttribute {
+                id: id.clone(),
+                owner_id,
+                ..NumericAttribute::from(attr_tmpl.clone())
+            }),
+            AttributeValueType::Boolean => self.boolean_attributes.push(BooleanAttribute {
+                id: id.clone(),
+                owner_id,
+                ..BooleanAttribute::from(attr_tmpl.clone())
+            }),
+            AttributeValueType::Date => self.date_attributes.push(DateAttribute {
+                id: id.clone(),
+                owner_id,
+                ..DateAttribute::from(attr_tmpl.clone())
+            }),
+            AttributeValueType::DateTime => self.datetime_attributes.push(DateTimeAttribute {
+                id: id.clone(),
+                owner_id,
+                ..DateTimeAttribute::from(attr_tmpl.clone())
+            }),
+        }
+        self.attributes_order.push((value_type, id));
+    }
+
+    /// Remove an attribute by its value type and id.
+    pub fn remove_attribute(&mut self, value_type: &AttributeValueType, id: &Id) {
+        self.attributes_order
+            .retain(|(vt, oid)| !(vt == value_type && oid == id));
+        match value_type {
+            AttributeValueType::Text => self.text_attributes.retain(|a| &a.id != id),
+            AttributeValueType::Numeric => self.numeric_attributes.retain(|a| &a.id != id),
+            AttributeValueType::Boolean => self.boolean_attributes.retain(|a| &a.id != id),
+            AttributeValueType::Date => self.date_attributes.retain(|a| &a.id != id),
+            AttributeValueType::DateTime => self.datetime_attributes.retain(|a| &a.id != id),
+        }
+    }
+
+    /// Detach an attribute from its template, so that it becomes a free-form one.
+    pub fn detach_attribute(&mut self, value_type: &AttributeValueType, id: &Id) {
+        match value_type {
+            AttributeValueType::Text => self
+                .text_attributes
+                .iter_mut()
+                .filter(|a| &a.id == id)
+                .for_each(|a| a.tmpl_id = None),
+            AttributeValueType::Numeric => self
+                .numeric_attributes
+                .iter_mut()
+                .filter(|a| &a.id == id)
+                .for_each(|a| a.tmpl_id = None),
+            AttributeValueType::Boolean => self
+                .boolean_attributes
+                .iter_mut()
+                .filter(|a| &a.id == id)
+                .for_each(|a| a.tmpl_id = None),
+            AttributeValueType::Date => self
+                .date_attributes
+                .iter_mut()
+                .filter(|a| &a.id == id)
+                .for_each(|a| a.tmpl_id = None),
+            AttributeValueType::DateTime => self
+                .datetime_attributes
+                .iter_mut()
+                .filter(|a| &a.id == id)
+                .for_each(|a| a.tmpl_id = None),
+        }
+    }
+
+    /// Get all its attributes, in their show order.
+    pub fn attributes(&self) -> Vec<Attr> {
+        self.attributes_order
+            .iter()
+            .filter_map(|(value_type, id)| self.attribute(value_type, id))
+            .collect()
+    }
+
+    /// Get an attribute by its value type and id.
+    pub fn attribute(&self, value_type: &AttributeValueType, id: &Id) -> Option<Attr> {
+        let (name, value) = match value_type {
+            AttributeValueType::Text => self
+                .text_attributes
+                .iter()
+                .find(|a| &a.id == id)
+                .map(|a| (a.name.clone(), a.value.clone())),
+            AttributeValueType::Numeric => self
+                .numeric_attributes
+                .iter()
+                .find(|a| &a.id == id)
+                .map(|a| (a.name.clone(), a.value.to_string())),
+            AttributeValueType::Boolean => self
+                .boolean_attributes
+                .iter()
+                .find(|a| &a.id == id)
+                .map(|a| (a.name.clone(), a.value.to_string())),
+            AttributeValueType::Date => self
+                .date_attributes
+                .iter()
+                .find(|a| &a.id == id)
+                .map(|a| (a.name.clone(), a.value.to_string())),
+            AttributeValueType::DateTime => self
+                .datetime_attributes
+                .iter()
+                .find(|a| &a.id == id)
+                .map(|a| {
+                    (
+                        a.name.clone(),
+                        a.value.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+                    )
+                }),
+        }?;
+        Some(Attr {
+            id: id.clone(),
+            name,
+            value_type: Some(value_type.clone()),
+            value,
+        })
+    }
+
+    /// Give new ids to all its attributes (using the provided generator), and set their owner.
+    /// Any attribute that is missing from `attributes_order` is appended to it.
+    pub fn renew_attribute_ids(&mut self, mut new_id: impl FnMut() -> Id) {
+        let mut order = Vec::with_capacity(self.attributes_order.len());
+        let owner_id = self.id.clone();
+
+        macro_rules! renew {
+            ($attrs:expr, $value_type:expr) => {
+                for a in &mut $attrs {
+                    let id = new_id();
+                    let pos = self
+                        .attributes_order
+                        .iter()
+                        .position(|(vt, oid)| vt == &$value_type && oid == &a.id)
+                        .unwrap_or(usize::MAX);
+                    order.push((pos, $value_type, id.clone()));
+                    a.id = id;
+                    a.owner_id = owner_id.clone();
+                }
+            };
+        }
+        renew!(self.text_attributes, AttributeValueType::Text);
+        renew!(self.numeric_attributes, AttributeValueType::Numeric);
+        renew!(self.boolean_attributes, AttributeValueType::Boolean);
+        renew!(self.date_attributes, AttributeValueType::Date);
+        renew!(self.datetime_attributes, AttributeValueType::DateTime);
+
+        order.sort_by_key(|(pos, _, _)| *pos);
+        self.attributes_order = order.into_iter().map(|(_, vt, id)| (vt, id)).collect();
+    }
+
+    /// Refresh the listing attribute value based on the current value of the attribute
+    /// that has the listing attribute name. If no such name is set (as it happens for
+    /// items created from scratch), the first attribute is used as the listing one.
+    pub fn refresh_listing_attr(&mut self) {
+        let attrs = self.attributes();
+        let listing_attr = if self.listing_attr_name.is_empty() {
+            attrs.first()
+        } else {
+            attrs.iter().find(|a| a.name == self.listing_attr_name)
+        };
+        if let Some(attr) = listing_attr {
+            self.listing_attr_name = attr.name.clone();
+            self.listing_attr_value = attr.value.clone();
+        }
+    }
+
     pub fn update_attribute(&mut self, attr: &Attr) -> Result<(), Error> {
         let value_type = attr.value_type.clone().unwrap_or_default();
         match value_type {
@@ -158,7 +343,8 @@ impl Item {
                     .find(|a| a.id == attr.id)
                 {
                     a.name = attr.name.clone();
-                    a.value = attr.value.parse()?;
+                    // The same format as the one accepted by `Attr::validate_value`.
+                    a.value = NaiveDateTime::parse_from_str(&attr.value, "%Y-%m-%d %H:%M:%S%.3f")?;
                 }
                 Ok(())
             }
@@ -240,3 +426,21 @@ impl Item {
         );
     }
 }
+
+impl From<&ItemTemplate> for Item {
+    fn from(tmpl: &ItemTemplate) -> Self {
+        let mut item = Self {
+            kind: tmpl.name.clone(),
+            listing_attr_tmpl_id: tmpl.listing_attr.id.clone(),
+            listing_attr_name: tmpl.listing_attr.name.clone(),
+            tmpl_id: Some(tmpl.id.clone()),
+            access_level_id: tmpl.access_level_id.clone(),
+            ..Default::default()
+        };
+        for attr_tmpl in &tmpl.attributes {
+            item.add_attribute_from_template(attr_tmpl);
+        }
+        item.refresh_listing_attr();
+        item
+    }
+}
@@ -0,0 +1,23 @@
+use crate::domain::model::Id;
+use serde::{Deserialize, Serialize};
+
+/// A named (and directed) link between two items.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ItemLink {
+    pub id: Id,
+
+    /// Its name. For items created from a template, it is the name of an `ItemTemplateLink`.
+    pub name: String,
+
+    pub source_item_id: Id,
+
+    pub target_item_id: Id,
+
+    /// The kind (template name) of the target item, populated when reading the link.
+    #[serde(default)]
+    pub target_item_kind: String,
+
+    /// The listing attribute value of the target item, populated when reading the link.
+    #[serde(default)]
+    pub target_item_listing_attr_value: String,
+}
@@ -10,6 +10,14 @@ pub struct ItemTemplate {
     pub attributes: Vec<AttrTemplate>,
     pub listing_attr: AttrTemplate,
     pub links: Vec<ItemTemplateLink>,
+
+    /// The default access level of the items that are created from it.
+    #[serde(default)]
+    pub access_level_id: Option<Id>,
+
+    /// The version, for optimistic concurrency.
+    #[serde(default)]
+    pub version: i32,
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
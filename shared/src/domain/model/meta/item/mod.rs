@@ -3,3 +3,6 @@ pub use item_tmpl::*;
 
 mod item;
 pub use item::*;
+
+mod item_link;
+pub use item_link::*;
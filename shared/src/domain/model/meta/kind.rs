@@ -6,4 +6,5 @@ pub enum Kind {
     AttributeTemplate,
     LinkTemplate,
     AccessLevel,
+    User,
 }
@@ -6,3 +6,6 @@ pub use item::*;
 
 mod kind;
 pub use kind::*;
+
+mod dependency;
+pub use dependency::*;
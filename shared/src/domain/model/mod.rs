@@ -4,6 +4,9 @@ pub use id::*;
 mod user;
 pub use user::*;
 
+mod api_token;
+pub use api_token::*;
+
 mod access_level;
 pub use access_level::*;
 
@@ -11,3 +14,6 @@ pub mod meta;
 
 mod action;
 pub use action::*;
+
+mod revision;
+pub use revision::*;
@@ -0,0 +1,30 @@
+use crate::domain::model::{Id, meta::Kind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A revision of an element, that is a snapshot of it as it was saved by a change.
+/// Revisions are append-only: they are never updated or deleted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revision {
+    pub id: Id,
+
+    /// The kind of the element.
+    pub kind: Kind,
+
+    /// The id of the element.
+    pub element_id: Id,
+
+    /// The sequence number of the revision, starting from 1 for each element.
+    pub number: i32,
+
+    /// The id of the user that made the change, if known.
+    pub user_id: Option<Id>,
+
+    /// The username of the user that made the change, or empty if unknown.
+    pub user_name: String,
+
+    pub created_at: DateTime<Utc>,
+
+    /// The element, as it was saved, in its JSON representation.
+    pub snapshot: serde_json::Value,
+}
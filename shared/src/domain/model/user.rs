@@ -1,6 +1,14 @@
 use crate::domain::model::Id;
 use serde::{Deserialize, Serialize};
 
+/// The permission of reading (anything, through the API).
+pub const PERM_ADMIN_READ: &str = "Admin::Read";
+/// The permission of changing (anything, through the API).
+pub const PERM_ADMIN_WRITE: &str = "Admin::Write";
+
+/// All the permissions that can be granted to a user.
+pub const PERMISSIONS: [&str; 2] = [PERM_ADMIN_READ, PERM_ADMIN_WRITE];
+
 /// User account contains most of the details of a user (except password related ones).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserAccount {
@@ -11,6 +19,27 @@ pub struct UserAccount {
     pub bio: String,
     pub is_anonymous: bool,
     pub permissions: Vec<String>,
+
+    /// The rank of the access level that the user is cleared for. The user sees only the items
+    /// whose access level has a rank up to it (and the ones without an access level).
+    #[serde(default)]
+    pub clearance: i32,
+
+    /// The access level that the user is cleared for, if any. The `clearance` is its rank.
+    #[serde(default)]
+    pub access_level_id: Option<Id>,
+
+    /// A disabled user cannot log in anymore, but the account (and its history) is kept.
+    #[serde(default)]
+    pub disabled: bool,
+
+    /// The user must set a new password on the next login, as an admin requested it.
+    #[serde(default)]
+    pub must_reset_password: bool,
+
+    /// The account is temporarily locked, due to repeated failed logins.
+    #[serde(default)]
+    pub locked: bool,
 }
 
 impl Default for UserAccount {
@@ -23,13 +52,22 @@ impl Default for UserAccount {
             name: "".into(),
             bio: "".into(),
             permissions: Vec::new(),
+            clearance: 0,
+            access_level_id: None,
+            disabled: false,
+            must_reset_password: false,
+            locked: false,
         }
     }
 }
 
 impl UserAccount {
     pub fn is_admin_read(&self) -> bool {
-        self.permissions.contains(&"Admin::Read".into())
+        self.permissions.contains(&PERM_ADMIN_READ.into())
+    }
+
+    pub fn is_admin_write(&self) -> bool {
+        self.permissions.contains(&PERM_ADMIN_WRITE.into())
     }
 }
 
@@ -38,7 +76,10 @@ impl UserAccount {
 pub struct UserEntry {
     pub user: UserAccount,
     pub password: String,
-    pub salt: String,
+    /// Only the legacy (salted MD5) password hashes have a separate salt.
+    pub salt: Option<String>,
+    /// For how many more seconds the account is locked, if it is.
+    pub locked_secs: Option<u64>,
 }
 
 impl From<UserEntry> for UserAccount {
@@ -50,5 +91,6 @@ impl From<UserEntry> for UserAccount {
 /// It includes just the user's password and salt.
 pub struct UserPasswordSalt {
     pub password: String,
-    pub salt: String,
+    /// Only the legacy (salted MD5) password hashes have a separate salt.
+    pub salt: Option<String>,
 }
@@ -0,0 +1,104 @@
+use crate::domain::model::{
+    AccessLevel,
+    meta::{AttrTemplate, Item, ItemLink, ItemTemplate, Kind},
+};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The version of the workspace bundle format. It is increased on each incompatible change of the format,
+/// and a bundle is imported only if it has the same version.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// A portable copy of (the not deleted elements of) a workspace, used for backups
+/// and for moving data between instances.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceBundle {
+    pub format_version: u32,
+    pub access_levels: Vec<AccessLevel>,
+    pub attr_templates: Vec<AttrTemplate>,
+
+    /// The item templates, each one with its attributes in show order, and its links.
+    pub item_templates: Vec<ItemTemplate>,
+
+    /// The items, each one with its (typed) attributes.
+    pub items: Vec<Item>,
+
+    pub item_links: Vec<ItemLink>,
+}
+
+/// What happens to the ids of the elements of an imported bundle.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BundleIdsPolicy {
+    /// The elements keep their ids, so importing the bundle into the same workspace updates them.
+    #[default]
+    Preserve,
+
+    /// The elements get new ids, so they are copies of the ones in the bundle.
+    Remap,
+}
+
+/// What happens to an element of an imported bundle that clashes with an existing one,
+/// by having the same id (when they are preserved) or the same unique name.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum BundleClashPolicy {
+    /// The existing element is kept, and it's used wherever the bundle refers the clashing one.
+    #[default]
+    Skip,
+
+    /// The existing element is updated with the clashing one.
+    Overwrite,
+
+    /// The clashing one is imported as a new element, renamed (if needed) to have a unique name.
+    Rename,
+}
+
+/// A request of importing a workspace bundle, in a single transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleImportRequest {
+    pub bundle: WorkspaceBundle,
+
+    #[serde(default)]
+    pub ids: BundleIdsPolicy,
+
+    #[serde(default)]
+    pub clashes: BundleClashPolicy,
+}
+
+/// An element of an imported bundle that clashed with an existing one, and how that was resolved.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleClash {
+    pub kind: Kind,
+
+    /// The name of the element (the listing attribute value, in case of items).
+    pub name: String,
+
+    pub resolution: BundleClashPolicy,
+
+    /// The new name of the element, if it was renamed.
+    pub renamed_to: Option<String>,
+}
+
+/// The outcome of importing a workspace bundle.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleImportReport {
+    /// The number of elements (of any kind, except item links) that were created.
+    pub created: usize,
+
+    /// The number of existing elements that were updated.
+    pub overwritten: usize,
+
+    /// The number of elements that were not imported, since they clashed with existing ones.
+    pub skipped: usize,
+
+    pub item_links: usize,
+
+    pub clashes: Vec<BundleClash>,
+}
@@ -0,0 +1,139 @@
+use crate::domain::model::Id;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The max depth (number of links away from the starting item) of traversing the item links.
+pub const GRAPH_MAX_DEPTH: u32 = 5;
+
+/// The max number of nodes of an item graph. The farthest ones are left out, once it's reached.
+pub const GRAPH_MAX_NODES: u32 = 500;
+
+/// The direction in which the item links are followed.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum GraphDirection {
+    /// From the source item to the target item.
+    #[default]
+    Out,
+    /// From the target item to the source item.
+    In,
+    Both,
+}
+
+impl GraphDirection {
+    /// Whether a link between two items of a graph (at the provided depths) is followed in this direction,
+    /// that is it does not point back towards the starting item, against the direction.
+    pub const fn follows(self, source_depth: u32, target_depth: u32) -> bool {
+        match self {
+            Self::Out => source_depth <= target_depth,
+            Self::In => target_depth <= source_depth,
+            Self::Both => true,
+        }
+    }
+}
+
+/// The (query) parameters of getting the graph (neighborhood) of an item.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GraphParams {
+    /// How many links away from the item to go. It's 1 by default, and at most `GRAPH_MAX_DEPTH`.
+    #[serde(default)]
+    pub depth: Option<u32>,
+
+    /// The names of the links to follow, comma separated. All the links are followed by default.
+    #[serde(default)]
+    pub links: Option<String>,
+
+    #[serde(default)]
+    pub direction: GraphDirection,
+}
+
+impl GraphParams {
+    /// The depth, kept between 1 and `GRAPH_MAX_DEPTH`.
+    pub fn depth(&self) -> u32 {
+        self.depth.unwrap_or(1).clamp(1, GRAPH_MAX_DEPTH)
+    }
+
+    /// The names of the links to follow. None of them means all the links.
+    pub fn link_names(&self) -> Vec<String> {
+        self.links
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+}
+
+/// An item that is part of a graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: Id,
+    pub tmpl_id: Option<Id>,
+
+    /// The name of the item's template, if any.
+    pub tmpl_name: String,
+
+    pub listing_attr_value: String,
+
+    /// The (least) number of links between the starting item and this one.
+    pub depth: u32,
+}
+
+/// A link between two items of a graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub id: Id,
+    pub name: String,
+    pub source_item_id: Id,
+    pub target_item_id: Id,
+}
+
+/// The neighborhood of an item: the items that are reached by following its links, and the links between them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemGraph {
+    /// The nodes, the starting item first, and then by their depth.
+    pub nodes: Vec<GraphNode>,
+
+    pub edges: Vec<GraphEdge>,
+
+    /// Whether some nodes were left out, since there were more than `GRAPH_MAX_NODES`.
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GRAPH_MAX_DEPTH, GraphDirection, GraphParams};
+
+    #[test]
+    fn a_back_link_is_followed_only_in_both_directions() {
+        // A back-link, from an item that is one link away from the starting item, to the starting item.
+        let (source_depth, target_depth) = (1, 0);
+        assert!(!GraphDirection::Out.follows(source_depth, target_depth));
+        assert!(GraphDirection::In.follows(source_depth, target_depth));
+        assert!(GraphDirection::Both.follows(source_depth, target_depth));
+
+        // The link that the walk went through, from the starting item.
+        assert!(GraphDirection::Out.follows(0, 1));
+        assert!(!GraphDirection::In.follows(0, 1));
+    }
+
+    #[test]
+    fn depth_is_kept_in_range_and_link_names_are_trimmed() {
+        let params = GraphParams {
+            depth: Some(9),
+            links: Some("owns, runs on,,".to_owned()),
+            direction: GraphDirection::Both,
+        };
+        assert_eq!(params.depth(), GRAPH_MAX_DEPTH);
+        assert_eq!(params.link_names(), vec!["owns", "runs on"]);
+
+        let params = GraphParams::default();
+        assert_eq!(params.depth(), 1);
+        assert!(params.link_names().is_empty());
+    }
+}
@@ -0,0 +1,41 @@
+use crate::domain::model::Id;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A request of importing items, from the rows of a CSV text, into an item template.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemsImportRequest {
+    /// The item template that the items are created from.
+    pub tmpl_id: Id,
+
+    /// The CSV text, having a header row with the names of the columns.
+    pub csv: String,
+
+    /// The attribute template (of the item template) that each column is mapped to, by the column name.
+    /// The columns that are not mapped are ignored.
+    pub columns: HashMap<String, Id>,
+
+    /// If set, the rows are only checked, and nothing is imported.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// The error of a CSV row, that makes it (and thus the whole import) fail.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportRowError {
+    /// The (1-based) line number where the row starts, the header row being the first one.
+    pub line: usize,
+    pub message: String,
+}
+
+/// The outcome of importing items.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemsImportReport {
+    pub dry_run: bool,
+
+    /// The number of rows that are (or, on a dry run, would be) imported.
+    /// It's zero if there are any errors, since either all rows are imported or none.
+    pub imported: usize,
+
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,62 @@
+use crate::domain::model::Id;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The (query) parameters of getting the items.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ItemsParams {
+    /// An optional filter of the items, written in the `ItemQuery` syntax,
+    /// such as `Status = "open" AND Priority >= 3`.
+    #[serde(default)]
+    pub filter: Option<String>,
+
+    /// An optional item template, to get only the items that are created from it.
+    #[serde(default)]
+    pub tmpl_id: Option<Id>,
+}
+
+/// The formats that the items can be exported to.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// A header row with the names of the columns, then a row per item.
+    #[default]
+    Csv,
+    /// An array of objects, one per item.
+    Json,
+    /// An object per item, on its own line (newline delimited JSON).
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    /// The extension of the files in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// The (query) parameters of exporting the items of an item template.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ItemsExportParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+
+    /// An optional filter of the items, written in the `ItemQuery` syntax.
+    #[serde(default)]
+    pub filter: Option<String>,
+}
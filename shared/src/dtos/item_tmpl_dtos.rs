@@ -0,0 +1,10 @@
+use crate::domain::logic::OrphanAttrsPolicy;
+use serde::{Deserialize, Serialize};
+
+/// The (query) parameters of an item template upsert.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ItemTemplateUpsertParams {
+    /// What happens to the attributes of the existing items that are no longer declared by the template.
+    #[serde(default)]
+    pub orphans: OrphanAttrsPolicy,
+}
@@ -5,11 +5,19 @@ use serde::{Deserialize, Serialize};
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
+
+    /// The password to replace the current one with, when the user must reset it.
+    #[serde(default)]
+    pub new_password: Option<String>,
 }
 
 impl LoginRequest {
     pub fn new(username: String, password: String) -> Self {
-        Self { username, password }
+        Self {
+            username,
+            password,
+            new_password: None,
+        }
     }
 
     /// Get the JSON representation of it.
@@ -9,3 +9,33 @@ pub use curr_user_account::*;
 
 mod err_resp_dto;
 pub use err_resp_dto::*;
+
+mod item_dtos;
+pub use item_dtos::*;
+
+mod item_tmpl_dtos;
+pub use item_tmpl_dtos::*;
+
+mod search_dtos;
+pub use search_dtos::*;
+
+mod page_dtos;
+pub use page_dtos::*;
+
+mod revision_dtos;
+pub use revision_dtos::*;
+
+mod trash_dtos;
+pub use trash_dtos::*;
+
+mod import_dtos;
+pub use import_dtos::*;
+
+mod bundle_dtos;
+pub use bundle_dtos::*;
+
+mod graph_dtos;
+pub use graph_dtos::*;
+
+mod user_dtos;
+pub use user_dtos::*;
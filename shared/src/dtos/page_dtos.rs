@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// The number of elements of a page, if the request does not tell it.
+pub const PAGE_DEFAULT_LIMIT: u32 = 50;
+
+/// The max number of elements of a page. Larger limits are reduced to it.
+pub const PAGE_MAX_LIMIT: u32 = 500;
+
+/// The direction in which the elements of a list are sorted.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc,
+        }
+    }
+
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// The (query) parameters of getting a page of a list of elements.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PageRequest {
+    /// The number of elements to skip.
+    pub offset: u32,
+
+    /// The max number of elements to return.
+    pub limit: u32,
+
+    /// The field to sort by. Each list supports its own fields, and uses the first one
+    /// of them if this is missing or unknown.
+    pub sort: Option<String>,
+
+    pub dir: SortDirection,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: PAGE_DEFAULT_LIMIT,
+            sort: None,
+            dir: SortDirection::Asc,
+        }
+    }
+}
+
+impl PageRequest {
+    pub fn new(offset: u32, limit: u32) -> Self {
+        Self {
+            offset,
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn sorted_by(mut self, sort: &str, dir: SortDirection) -> Self {
+        self.sort = Some(sort.to_owned());
+        self.dir = dir;
+        self
+    }
+
+    /// The limit, kept between 1 and `PAGE_MAX_LIMIT`.
+    pub fn limit(&self) -> u32 {
+        self.limit.clamp(1, PAGE_MAX_LIMIT)
+    }
+
+    /// Get the column to sort by, out of the `(field, column)` pairs that are supported by a list.
+    /// The first one is used if the sort field is missing or not supported.
+    /// Since only these columns can be returned, it is safe to use the result in an SQL statement.
+    pub fn sort_column<'a>(&self, columns: &[(&str, &'a str)]) -> &'a str {
+        let requested = self
+            .sort
+            .as_deref()
+            .and_then(|sort| columns.iter().find(|(field, _)| *field == sort));
+        requested
+            .or(columns.first())
+            .map(|(_, column)| *column)
+            .unwrap_or_default()
+    }
+
+    /// Get the query string (without the leading `?`) that represents this request.
+    pub fn to_query_string(&self) -> String {
+        let mut qs = format!(
+            "offset={}&limit={}&dir={}",
+            self.offset,
+            self.limit(),
+            self.dir
+        );
+        if let Some(sort) = &self.sort {
+            qs.push_str(&format!("&sort={sort}"));
+        }
+        qs
+    }
+}
+
+/// A page of a list of elements.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// The position of the first element (of this page) in the whole list.
+    pub offset: u32,
+
+    /// The total number of elements of the whole list.
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, req: &PageRequest, total: u64) -> Self {
+        Self {
+            items,
+            offset: req.offset,
+            total,
+        }
+    }
+
+    /// The offset of the next page.
+    pub fn next_offset(&self) -> u32 {
+        self.offset + self.items.len() as u32
+    }
+
+    /// Tell if there are more elements after this page.
+    pub fn has_more(&self) -> bool {
+        u64::from(self.next_offset()) < self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PAGE_MAX_LIMIT, Page, PageRequest, SortDirection};
+
+    const COLUMNS: &[(&str, &str)] = &[("name", "i.listing_attr_value"), ("kind", "kind")];
+
+    #[test]
+    fn unknown_sort_field_falls_back_to_the_first_column() {
+        let req = PageRequest::default().sorted_by("kind; DROP TABLE items", SortDirection::Desc);
+        assert_eq!(req.sort_column(COLUMNS), "i.listing_attr_value");
+
+        let req = PageRequest::default().sorted_by("kind", SortDirection::Desc);
+        assert_eq!(req.sort_column(COLUMNS), "kind");
+        assert_eq!(
+            req.to_query_string(),
+            "offset=0&limit=50&dir=desc&sort=kind"
+        );
+    }
+
+    #[test]
+    fn page_tells_if_there_are_more_elements() {
+        let req = PageRequest::new(40, 20);
+        assert!(Page::new(vec![0; 20], &req, 61).has_more());
+        assert!(!Page::new(vec![0; 20], &req, 60).has_more());
+        assert_eq!(PageRequest::new(0, 10_000).limit(), PAGE_MAX_LIMIT);
+    }
+}
@@ -0,0 +1,12 @@
+use crate::domain::model::Id;
+use serde::{Deserialize, Serialize};
+
+/// The (query) parameters of comparing two revisions of an element.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct RevisionDiffParams {
+    /// The id of the older revision.
+    pub from: Id,
+
+    /// The id of the newer revision.
+    pub to: Id,
+}
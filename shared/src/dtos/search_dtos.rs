@@ -0,0 +1,135 @@
+use crate::domain::model::{Id, meta::Kind};
+use serde::{Deserialize, Serialize};
+
+/// The marker of the start of a highlighted (matching) part in a search hit's headline.
+pub const SEARCH_HL_START: &str = "<b>";
+
+/// The marker of the end of a highlighted (matching) part in a search hit's headline.
+pub const SEARCH_HL_STOP: &str = "</b>";
+
+/// The (query) parameters of a search.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SearchParams {
+    /// The words to search for. The elements having words starting with all of them are matched.
+    #[serde(default)]
+    pub q: String,
+}
+
+/// An element that matches a search.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: Kind,
+    pub id: Id,
+
+    /// The name of the element (the listing attribute value, in case of items).
+    pub name: String,
+
+    /// The matching text fragment, having the matching words between
+    /// `SEARCH_HL_START` and `SEARCH_HL_STOP` markers.
+    pub headline: String,
+
+    /// How relevant the element is. The higher, the better.
+    pub rank: f32,
+}
+
+impl SearchHit {
+    /// Split the headline into its parts, each one telling if it is highlighted or not.
+    pub fn headline_parts(&self) -> Vec<(&str, bool)> {
+        let mut parts = Vec::new();
+        let mut rest = self.headline.as_str();
+        while let Some((before, after)) = rest.split_once(SEARCH_HL_START) {
+            if !before.is_empty() {
+                parts.push((before, false));
+            }
+            let (hl, after) = after.split_once(SEARCH_HL_STOP).unwrap_or((after, ""));
+            if !hl.is_empty() {
+                parts.push((hl, true));
+            }
+            rest = after;
+        }
+        if !rest.is_empty() {
+            parts.push((rest, false));
+        }
+        parts
+    }
+}
+
+/// The hits of a search for one kind of elements.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchHitsGroup {
+    pub kind: Kind,
+    pub hits: Vec<SearchHit>,
+}
+
+impl SearchHitsGroup {
+    /// Group the hits by their kind. Both the groups (by their best hit)
+    /// and the hits within them are in descending rank order.
+    pub fn group(mut hits: Vec<SearchHit>) -> Vec<Self> {
+        hits.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+        let mut groups: Vec<Self> = Vec::new();
+        for hit in hits {
+            match groups.iter_mut().find(|g| g.kind == hit.kind) {
+                Some(group) => group.hits.push(hit),
+                None => groups.push(Self {
+                    kind: hit.kind.clone(),
+                    hits: vec![hit],
+                }),
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchHit, SearchHitsGroup};
+    use crate::domain::model::{Id, meta::Kind};
+
+    fn hit(kind: Kind, id: &str, rank: f32) -> SearchHit {
+        SearchHit {
+            kind,
+            id: Id::from(id),
+            rank,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hits_are_grouped_by_kind_in_rank_order() {
+        let groups = SearchHitsGroup::group(vec![
+            hit(Kind::ItemTemplate, "it-1", 0.2),
+            hit(Kind::Item, "i-1", 0.1),
+            hit(Kind::Item, "i-2", 0.5),
+        ]);
+
+        let ids: Vec<(Kind, Vec<Id>)> = groups
+            .into_iter()
+            .map(|g| (g.kind, g.hits.into_iter().map(|h| h.id).collect()))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                (Kind::Item, vec![Id::from("i-2"), Id::from("i-1")]),
+                (Kind::ItemTemplate, vec![Id::from("it-1")])
+            ]
+        );
+    }
+
+    #[test]
+    fn headline_is_split_into_highlighted_parts() {
+        let hit = SearchHit {
+            headline: String::from("the <b>web</b> server <b>srv</b>"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            hit.headline_parts(),
+            vec![
+                ("the ", false),
+                ("web", true),
+                (" server ", false),
+                ("srv", true)
+            ]
+        );
+    }
+}
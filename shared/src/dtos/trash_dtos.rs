@@ -0,0 +1,22 @@
+use crate::domain::model::{Id, meta::Kind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An element that is (soft) deleted, thus being in the Trash.
+/// It can be either restored or purged (deleted for good).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub kind: Kind,
+    pub id: Id,
+
+    /// The name of the element (the listing attribute value, in case of items).
+    pub name: String,
+
+    pub deleted_at: DateTime<Utc>,
+
+    /// The id of the user that deleted it, if known.
+    pub deleted_by: Option<Id>,
+
+    /// The username of the user that deleted it, or empty if unknown.
+    pub deleted_by_name: String,
+}
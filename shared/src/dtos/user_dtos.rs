@@ -0,0 +1,68 @@
+use crate::domain::model::{ApiToken, UserAccount};
+use serde::{Deserialize, Serialize};
+
+/// The request of creating or updating a user account (by an admin).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserUpsertRequest {
+    /// The account, with its permissions and access level. It's a new one if it has no id.
+    pub user: UserAccount,
+
+    /// The password, required for a new account. For an existing one, it's reset if provided.
+    pub password: Option<String>,
+}
+
+/// The request of editing the profile of the user that is logged in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileUpdateRequest {
+    pub name: String,
+    pub email: String,
+    pub bio: String,
+}
+
+impl From<&UserAccount> for ProfileUpdateRequest {
+    fn from(user: &UserAccount) -> Self {
+        Self {
+            name: user.name.clone(),
+            email: user.email.clone(),
+            bio: user.bio.clone(),
+        }
+    }
+}
+
+/// The request of changing the password of the user that is logged in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordChangeRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+/// The request of creating a personal API token for the user that is logged in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiTokenCreateRequest {
+    pub name: String,
+
+    /// The permissions that the token is scoped to, being a subset of the user's ones.
+    pub permissions: Vec<String>,
+
+    /// In how many days the token expires, or never if not provided.
+    pub expires_in_days: Option<u32>,
+}
+
+/// A personal API token that was just created, along with its secret.
+///
+/// The secret is provided only this once, as just the hash of it is kept.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiTokenCreated {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+/// The query parameter of an export's URL that carries a download token, as the downloads
+/// that are opened in the browser cannot include the session header.
+pub const DOWNLOAD_TOKEN_PARAM: &str = "download_token";
+
+/// A short-lived, single-use token that authenticates a download of an export (as its `DOWNLOAD_TOKEN_PARAM`).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadToken {
+    pub token: String,
+}
@@ -0,0 +1,19 @@
+-- Items created from scratch do not have a listing attribute template.
+ALTER TABLE items ALTER COLUMN listing_attr_tmpl_id DROP NOT NULL;
+
+
+
+-- The name and the show order of the attributes must be persisted, since
+-- attributes of items created from scratch do not have a template to get them from.
+
+ALTER TABLE text_attributes     ADD COLUMN name VARCHAR(128) NOT NULL DEFAULT '', ADD COLUMN show_index INT2 NOT NULL DEFAULT 1;
+ALTER TABLE numeric_attributes  ADD COLUMN name VARCHAR(128) NOT NULL DEFAULT '', ADD COLUMN show_index INT2 NOT NULL DEFAULT 1;
+ALTER TABLE boolean_attributes  ADD COLUMN name VARCHAR(128) NOT NULL DEFAULT '', ADD COLUMN show_index INT2 NOT NULL DEFAULT 1;
+ALTER TABLE date_attributes     ADD COLUMN name VARCHAR(128) NOT NULL DEFAULT '', ADD COLUMN show_index INT2 NOT NULL DEFAULT 1;
+ALTER TABLE datetime_attributes ADD COLUMN name VARCHAR(128) NOT NULL DEFAULT '', ADD COLUMN show_index INT2 NOT NULL DEFAULT 1;
+
+CREATE INDEX idx_text_attributes_owner     ON text_attributes(owner_id);
+CREATE INDEX idx_numeric_attributes_owner  ON numeric_attributes(owner_id);
+CREATE INDEX idx_boolean_attributes_owner  ON boolean_attributes(owner_id);
+CREATE INDEX idx_date_attributes_owner     ON date_attributes(owner_id);
+CREATE INDEX idx_datetime_attributes_owner ON datetime_attributes(owner_id);
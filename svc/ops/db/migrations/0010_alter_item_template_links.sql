@@ -0,0 +1,7 @@
+-- The links of an item template are now updated in place (instead of being deleted and re-inserted),
+-- since they may be referred by item links. Therefore, the show index uniqueness is checked at commit time.
+
+ALTER TABLE item_template_links DROP CONSTRAINT item_template_links_show_index_uniq;
+
+ALTER TABLE item_template_links ADD CONSTRAINT item_template_links_show_index_uniq
+    UNIQUE (source_item_tmpl_id, show_index) DEFERRABLE INITIALLY DEFERRED;
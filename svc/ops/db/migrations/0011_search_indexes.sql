@@ -0,0 +1,8 @@
+-- Full-text search indexes. The 'simple' configuration is used (no stemming, no stop words),
+-- since the searched values are mostly names and identifiers rather than natural language text.
+-- The expressions must be the same as the ones used by the search query, so that these indexes are used.
+
+CREATE INDEX idx_items_search           ON items           USING GIN (to_tsvector('simple', COALESCE(listing_attr_value, '')));
+CREATE INDEX idx_text_attributes_search ON text_attributes USING GIN (to_tsvector('simple', COALESCE(value, '')));
+CREATE INDEX idx_item_templates_search  ON item_templates  USING GIN (to_tsvector('simple', name || ' ' || COALESCE(description, '')));
+CREATE INDEX idx_attr_templates_search  ON attr_templates  USING GIN (to_tsvector('simple', name || ' ' || COALESCE(description, '')));
@@ -0,0 +1,29 @@
+-- The revisions (snapshots) of the items, item templates, attribute templates and access levels.
+-- A revision is appended on each change of an element, and it is never updated or deleted.
+-- The kind is one of: 'i' (item), 'it' (item template), 'at' (attribute template), 'al' (access level).
+-- The user is not a foreign key, so that the history is kept even if the user account is removed.
+
+CREATE TABLE revisions
+(
+    id              UUID               PRIMARY KEY  DEFAULT uuidv7(),
+    kind            VARCHAR(2)         NOT NULL,
+    element_id      UUID               NOT NULL,
+    number          INT                NOT NULL,
+    user_id         UUID,
+    user_name       VARCHAR(48)        NOT NULL     DEFAULT '',
+    created_at      TIMESTAMPTZ        NOT NULL     DEFAULT now(),
+    snapshot        JSONB              NOT NULL,
+    UNIQUE (kind, element_id, number)
+);
+
+CREATE INDEX idx_revisions_element ON revisions (element_id);
+
+CREATE FUNCTION revisions_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'revisions are append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_revisions_append_only
+    BEFORE UPDATE OR DELETE ON revisions
+    FOR EACH ROW EXECUTE FUNCTION revisions_append_only();
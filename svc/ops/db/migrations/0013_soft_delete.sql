@@ -0,0 +1,16 @@
+-- The items and the templates are soft deleted: they get moved to the Trash (by setting `deleted_at`),
+-- from where they can be either restored or purged (deleted for good).
+-- The user is not a foreign key, so that the Trash is kept even if the user account is removed.
+
+ALTER TABLE attr_templates ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+ALTER TABLE item_templates ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+ALTER TABLE items          ADD COLUMN deleted_at TIMESTAMPTZ, ADD COLUMN deleted_by UUID;
+
+-- The deleted attribute templates must not prevent reusing their name and description.
+ALTER TABLE attr_templates DROP CONSTRAINT name_desc_unique;
+CREATE UNIQUE INDEX attr_templates_name_desc_unique ON attr_templates (name, description) NULLS NOT DISTINCT
+    WHERE deleted_at IS NULL;
+
+CREATE INDEX idx_attr_templates_deleted ON attr_templates (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_item_templates_deleted ON item_templates (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX idx_items_deleted          ON items (deleted_at)          WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,7 @@
+-- The version of the elements, increased on each save, for the optimistic concurrency control:
+-- an update must provide the current version, otherwise it is refused as a conflict.
+
+ALTER TABLE attr_templates ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE item_templates ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE access_levels  ADD COLUMN version INT NOT NULL DEFAULT 1;
+ALTER TABLE items          ADD COLUMN version INT NOT NULL DEFAULT 1;
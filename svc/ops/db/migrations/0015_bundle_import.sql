@@ -0,0 +1,4 @@
+-- A workspace bundle may have item templates that link to each other, thus (when importing it in a single transaction)
+-- the targets of the item template links are checked at commit time.
+
+ALTER TABLE item_template_links ALTER CONSTRAINT item_template_links_target_fk DEFERRABLE INITIALLY IMMEDIATE;
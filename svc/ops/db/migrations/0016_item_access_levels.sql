@@ -0,0 +1,13 @@
+-- The items are restricted by access levels: a user sees only the items whose access level's rank is
+-- at most the user's clearance (the rank of the user's access level). A higher rank is a more restricted access.
+-- The items (and the item templates, as a default for their new items) without an access level are visible to all.
+
+ALTER TABLE access_levels ADD COLUMN rank INT NOT NULL DEFAULT 0;
+UPDATE access_levels SET rank = 1 WHERE name = 'Private';
+UPDATE access_levels SET rank = 2 WHERE name = 'Confidential';
+
+ALTER TABLE item_templates ADD COLUMN access_level_id UUID REFERENCES access_levels(id);
+ALTER TABLE items          ADD COLUMN access_level_id UUID REFERENCES access_levels(id);
+ALTER TABLE user_accounts  ADD COLUMN access_level_id UUID REFERENCES access_levels(id);
+
+CREATE INDEX idx_items_access_level ON items (access_level_id) WHERE access_level_id IS NOT NULL;
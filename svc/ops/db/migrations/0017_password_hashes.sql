@@ -0,0 +1,7 @@
+-- The passwords are hashed with Argon2id, stored as PHC strings (that include the parameters and the salt).
+-- The legacy (salted MD5) hashes keep their separate salt until they are upgraded, on the next successful login.
+
+ALTER TABLE user_accounts ALTER COLUMN salt DROP NOT NULL;
+
+-- An admin may require a user to set a new password on the next login.
+ALTER TABLE user_accounts ADD COLUMN must_reset_password BOOLEAN NOT NULL DEFAULT FALSE;
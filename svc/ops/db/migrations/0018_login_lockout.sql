@@ -0,0 +1,4 @@
+-- A user account is temporarily locked (its `state` being 'L') after repeated failed logins, until `locked_until`.
+-- The other states are 'A' (active) and 'D' (disabled). An expired lock is the same as being active.
+
+ALTER TABLE user_accounts ADD COLUMN locked_until TIMESTAMPTZ;
@@ -0,0 +1,18 @@
+-- The personal API tokens of the users, for scripts and integrations. Only the (SHA-256) hash of a token's
+-- secret is kept, along with its beginning (`hint`) that helps to recognize it. A token is scoped to a subset
+-- of its user's permissions. A revoked one is kept (with its `revoked_at`), as it may be referred to by the logs.
+
+CREATE TABLE api_tokens (
+    id           UUID         PRIMARY KEY,
+    user_id      UUID         NOT NULL REFERENCES user_accounts(id) ON DELETE CASCADE,
+    name         VARCHAR(64)  NOT NULL,
+    token_hash   CHAR(64)     NOT NULL UNIQUE,
+    hint         VARCHAR(16)  NOT NULL,
+    permissions  TEXT[]       NOT NULL DEFAULT '{}',
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+    expires_at   TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    revoked_at   TIMESTAMPTZ
+);
+
+CREATE INDEX idx_api_tokens_user ON api_tokens (user_id) WHERE revoked_at IS NULL;
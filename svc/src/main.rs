@@ -2,7 +2,7 @@ use cogs_shared::app::AppError;
 use cogs_svc::server::{self, ServerState, SvcConfig, init_logging, init_router};
 use config::{Config, Environment};
 use sqlx::{Pool, Postgres};
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 use tokio::signal;
 
 #[tokio::main]
@@ -39,14 +39,15 @@ async fn main() {
         }
     }
 
-    let web_api_router = init_router(&dbcp).await.with_state(state);
+    let web_api_router = init_router(&dbcp, &state).await.with_state(state);
 
     log::info!("Listening on http://{}", cfg.listenaddress);
     let listener = tokio::net::TcpListener::bind(&cfg.listenaddress)
         .await
         .expect(format!("Failed to bind to address {}", cfg.listenaddress).as_str());
 
-    axum::serve(listener, web_api_router.into_make_service())
+    // The client's address is needed for throttling the failed logins.
+    axum::serve(listener, web_api_router.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal(dbcp))
         .await
         .unwrap();
@@ -65,3 +65,61 @@ where
         })),
     )
 }
+
+/// Utility function for responding with `409 Conflict` code, an error description,
+/// and the dependencies (the elements that refer the one to be deleted).
+pub fn respond_conflict<E>(err: E, dependencies: Value) -> (StatusCode, Json<Value>)
+where
+    E: std::error::Error,
+{
+    (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": err.to_string(),
+            "dependencies": dependencies
+        })),
+    )
+}
+
+/// Utility function for responding with `409 Conflict` code, an error description,
+/// and the current copy of the element that was saved by someone else meanwhile.
+pub fn respond_stale_version<E>(err: E, current: Value) -> (StatusCode, Json<Value>)
+where
+    E: std::error::Error,
+{
+    (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": err.to_string(),
+            "current": current
+        })),
+    )
+}
+
+/// Utility function for responding with `422 Unprocessable Entity` code, an error description,
+/// and the details (such as the list of violations) of why the input could not be processed.
+pub fn respond_unprocessable_entity<E>(err: E, details: Value) -> (StatusCode, Json<Value>)
+where
+    E: std::error::Error,
+{
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Json(json!({
+            "error": err.to_string(),
+            "violations": details
+        })),
+    )
+}
+
+/// Utility function for responding with `429 Too Many Requests` code and an error description.
+pub fn respond_too_many_requests<E>(err: E) -> (StatusCode, Json<Value>)
+where
+    E: std::error::Error,
+{
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "error": err.to_string()
+        })),
+    )
+}
@@ -1,13 +1,18 @@
-use crate::server::UserAccountsRepo;
+use crate::server::{ServerState, UserAccountsRepo};
 use async_trait::async_trait;
-use axum::response::{IntoResponse, Response};
-use axum_session::{SessionConfig, SessionLayer, SessionMode};
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    response::{IntoResponse, Response},
+};
+use axum_session::{Session, SessionConfig, SessionLayer, SessionMode};
 use axum_session_auth::*;
 use axum_session_sqlx::{SessionPgPool, SessionPgSessionStore};
 use chrono::Duration;
 use cogs_shared::domain::model::{Id, UserAccount};
+use http::request::Parts;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::convert::Infallible;
 
 pub const SESSION_NAME: &str = "Authorization";
 pub const SESSION_TABLE: &str = "user_sessions";
@@ -34,6 +39,65 @@ pub async fn init_session_layer(pg_pool: &PgPool) -> SessionLayer<SessionPgPool>
     SessionLayer::new(session_store)
 }
 
+/// Get the user that is logged in within the session, if any, as it was at the login.
+/// Use `SessionUser` (or `CurrentUser`) to get the account as currently saved.
+pub fn session_user(session: &Session<SessionPgPool>) -> Option<UserAccount> {
+    session
+        .get::<AuthUserAccount>(SESSION_CURRENT_USER_KEY)
+        .map(|user| user.0)
+        .filter(|user| !user.is_anonymous)
+}
+
+/// The user of the token that a request was authenticated by, with just the permissions that the token grants.
+/// It is provided as an extension of the request, by the `api_token_auth` middleware (for an API token)
+/// or by the `download_token_auth` one (for a download token, that grants all the user's permissions).
+#[derive(Clone, Debug)]
+pub struct ApiTokenUser(pub UserAccount);
+
+/// The user that a request is made by: the one of its (API or download) token, if it has one,
+/// or else the one that is logged in within its session (if any, as in `SessionUser`).
+#[derive(Clone, Debug)]
+pub struct CurrentUser(pub Option<UserAccount>);
+
+impl<S> FromRequestParts<S> for CurrentUser
+where
+    ServerState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(ApiTokenUser(user)) = parts.extensions.get::<ApiTokenUser>() {
+            return Ok(Self(Some(user.clone())));
+        }
+        let SessionUser(user) = SessionUser::from_request_parts(parts, state).await?;
+        Ok(Self(user))
+    }
+}
+
+/// The user that is logged in within the session of a request, if any. Its account is reloaded on each request,
+/// so that the changes of its state, permissions and clearance apply to the live sessions as well.
+/// It's none if the user cannot act anymore (see `UserMgmt::get_active_account`). An API token is not accepted.
+#[derive(Clone, Debug)]
+pub struct SessionUser(pub Option<UserAccount>);
+
+impl<S> FromRequestParts<S> for SessionUser
+where
+    ServerState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session = Session::<SessionPgPool>::from_request_parts(parts, state).await.ok();
+        let Some(user) = session.as_ref().and_then(session_user) else {
+            return Ok(Self(None));
+        };
+        let state = ServerState::from_ref(state);
+        Ok(Self(state.user_mgmt.get_active_account(&user.id).await))
+    }
+}
+
 // ---------------------------------
 //          AuthUserAccount
 // ---------------------------------
@@ -1,37 +1,70 @@
 use crate::server::{AuthUserAccount, SESSION_CURRENT_USER_KEY, SESSION_MAX_LIFESPAN, ServerState};
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    extract::{ConnectInfo, State},
+};
 use axum_session::Session;
 use axum_session_sqlx::SessionPgPool;
 use cogs_shared::{
     app::AppError,
     dtos::{ErrorResponse, LoginRequest, LoginResponse},
 };
-use http::StatusCode;
+use http::{HeaderMap, HeaderValue, StatusCode, header};
 use log::debug;
+use std::net::SocketAddr;
 
+/// Log in a user. The failed logins are throttled (per username and per client IP), the responses of the refused
+/// ones including a `Retry-After` header (in seconds) when the next attempt has to wait.
 pub async fn login(
     State(state): State<ServerState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
     session: Session<SessionPgPool>,
     Json(payload): Json<LoginRequest>,
-) -> Result<(StatusCode, Json<LoginResponse>), (StatusCode, Json<ErrorResponse>)> {
+) -> Result<(StatusCode, Json<LoginResponse>), (StatusCode, HeaderMap, Json<ErrorResponse>)> {
     //
-    debug!("[login] Received '{:?}'.", payload);
+    // The payload is not logged, since it includes the password.
+    debug!(
+        "[login] Received a login request for '{}' from {}.",
+        payload.username,
+        client.ip()
+    );
 
+    let username = payload.username.clone();
+    let client_ip = Some(client.ip());
     let user_account = state
         .user_mgmt
-        .authenticate_user(payload.username, payload.password)
+        .login(payload.username, payload.password, payload.new_password, client_ip)
         .await
-        .map_err(|err| match err {
-            AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, Json(msg.into())),
-            _ => {
-                debug!("Login error: {:?}", err);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: "Internal server error".to_owned(),
-                    }),
-                )
+        .map_err(|err| {
+            let (status, retry_after) = match &err {
+                AppError::Unauthorized(_) => (
+                    StatusCode::UNAUTHORIZED,
+                    state.user_mgmt.login_retry_after(&username, client_ip),
+                ),
+                AppError::LoginThrottled(secs) => (StatusCode::TOO_MANY_REQUESTS, Some(*secs)),
+                AppError::LoginLocked(secs) => (StatusCode::LOCKED, Some(*secs)),
+                AppError::LoginPasswordResetRequired => (StatusCode::FORBIDDEN, None),
+                AppError::InvalidInput(_) => (StatusCode::BAD_REQUEST, None),
+                _ => {
+                    debug!("Login error: {:?}", err);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        HeaderMap::new(),
+                        Json(ErrorResponse {
+                            error: "Internal server error".to_owned(),
+                        }),
+                    );
+                }
+            };
+            let mut headers = HeaderMap::new();
+            if let Some(secs) = retry_after {
+                headers.insert(header::RETRY_AFTER, HeaderValue::from(secs));
             }
+            let msg = match err {
+                AppError::Unauthorized(msg) | AppError::InvalidInput(msg) => msg,
+                _ => err.to_string(),
+            };
+            (status, headers, Json(msg.into()))
         })?;
 
     session.set_store(true);
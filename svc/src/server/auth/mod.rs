@@ -3,3 +3,6 @@ pub use auth::*;
 
 mod auth_api;
 pub use auth_api::*;
+
+mod permissions;
+pub use permissions::*;
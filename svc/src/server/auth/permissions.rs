@@ -0,0 +1,101 @@
+use crate::server::{
+    ApiTokenUser, AuthUserAccount, CurrentUser, ServerState, respond_forbidden, respond_internal_server_error,
+    respond_unauthorized,
+};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_session_auth::HasPermission;
+use cogs_shared::{
+    app::AppError,
+    domain::model::{PERM_ADMIN_READ, PERM_ADMIN_WRITE},
+    dtos::DOWNLOAD_TOKEN_PARAM,
+};
+use http::{Method, header};
+use log::debug;
+
+/// The scheme of the `Authorization` header that carries an API token (instead of a session id).
+pub const BEARER_PREFIX: &str = "Bearer ";
+
+/// Middleware that requires an authenticated session (or API token) and the permission that is needed by
+/// the request's method: `Admin::Read` for the reads (`GET` and `HEAD`) and `Admin::Write` for anything else.
+pub async fn require_permissions(CurrentUser(user): CurrentUser, req: Request, next: Next) -> Response {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into())).into_response();
+    };
+    let perm = if req.method() == Method::GET || req.method() == Method::HEAD {
+        PERM_ADMIN_READ
+    } else {
+        PERM_ADMIN_WRITE
+    };
+    if !AuthUserAccount(user).has(perm, &None).await {
+        debug!(
+            "[require_permissions] Denied {} {} due to missing '{}'.",
+            req.method(),
+            req.uri().path(),
+            perm
+        );
+        return respond_forbidden(AppError::Forbidden(format!("'{perm}' permission is required"))).into_response();
+    }
+    next.run(req).await
+}
+
+/// Middleware that authenticates a request by the API token that it carries (as `Authorization: Bearer <token>`)
+/// instead of by a session. The token's user is provided as the request's `ApiTokenUser` extension, and the header
+/// is removed, so that it is not taken as a session id. A request with an unusable token is refused.
+pub async fn api_token_auth(State(state): State<ServerState>, mut req: Request, next: Next) -> Response {
+    //
+    let Some(secret) = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix(BEARER_PREFIX))
+        .map(|secret| secret.trim().to_string())
+    else {
+        return next.run(req).await;
+    };
+    req.headers_mut().remove(header::AUTHORIZATION);
+    match state.user_mgmt.authenticate_api_token(&secret).await {
+        Ok(user) => {
+            req.extensions_mut().insert(ApiTokenUser(user));
+            next.run(req).await
+        }
+        Err(err @ AppError::Unauthorized(_)) => {
+            debug!("[api_token_auth] Denied {} {}: {err}", req.method(), req.uri().path());
+            respond_unauthorized(err).into_response()
+        }
+        Err(err) => respond_internal_server_error(err).into_response(),
+    }
+}
+
+/// Middleware that authenticates a download (of an export) by the download token that its URL carries
+/// (as the `DOWNLOAD_TOKEN_PARAM` query parameter), since the downloads that are opened in the browser
+/// cannot include the session header. The token's user is provided as the request's `ApiTokenUser` extension.
+/// A request with an unusable token is refused.
+pub async fn download_token_auth(State(state): State<ServerState>, mut req: Request, next: Next) -> Response {
+    //
+    let token = req.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == DOWNLOAD_TOKEN_PARAM)
+            .map(|(_, value)| value.to_string())
+    });
+    let Some(token) = token else {
+        return next.run(req).await;
+    };
+    match state.user_mgmt.authenticate_download_token(&token).await {
+        Ok(user) => {
+            req.extensions_mut().insert(ApiTokenUser(user));
+            next.run(req).await
+        }
+        Err(err @ AppError::Unauthorized(_)) => {
+            debug!("[download_token_auth] Denied {} {}: {err}", req.method(), req.uri().path());
+            respond_unauthorized(err).into_response()
+        }
+        Err(err) => respond_internal_server_error(err).into_response(),
+    }
+}
@@ -1,12 +1,17 @@
+use super::{NewRevision, RevisionRepo, dependency_from_row, item_name_sql};
 use crate::utils::{new_app_error_from_sqlx, uuid_from};
 use cogs_shared::{
     app::{AppError, AppResult},
-    domain::model::{AccessLevel, Id},
+    domain::model::{AccessLevel, Id, meta::Dependency},
+    dtos::{Page, PageRequest},
 };
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// The fields that the access levels can be sorted by, mapped to their columns.
+const ACCESS_LEVELS_SORT_COLUMNS: &[(&str, &str)] = &[("name", "name")];
+
 #[derive(Clone, Debug)]
 pub struct AccessLevelRepo {
     dbcp: Arc<PgPool>,
@@ -17,73 +22,153 @@ impl AccessLevelRepo {
         Self { dbcp }
     }
 
-    pub async fn get_all(&self) -> AppResult<Vec<AccessLevel>> {
-        let rows = sqlx::query(
+    pub async fn get_page(&self, page: &PageRequest) -> AppResult<Page<AccessLevel>> {
+        let sort_column = page.sort_column(ACCESS_LEVELS_SORT_COLUMNS);
+        let sql = format!(
             r#"
-            SELECT id, name, description
+            SELECT id, name, description, rank, version
             FROM access_levels
-            ORDER BY name ASC
+            ORDER BY {sort_column} {}, id ASC
+            LIMIT $1 OFFSET $2
+            "#,
+            page.dir.as_sql()
+        );
+        let rows = sqlx::query(&sql)
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get access levels".to_string())))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM access_levels")
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to count access levels".to_string())))?;
+
+        let data = rows.iter().map(access_level_from_row).collect();
+
+        Ok(Page::new(data, page, total as u64))
+    }
+
+    pub async fn get_by_id(&self, id: &Id) -> AppResult<AccessLevel> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, description, rank, version
+            FROM access_levels
+            WHERE id = $1
             "#,
         )
-        .fetch_all(self.dbcp.as_ref())
+        .bind(uuid_from(id))
+        .fetch_optional(self.dbcp.as_ref())
         .await
-        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get access levels".to_string())))?;
-
-        Ok(rows
-            .into_iter()
-            .map(|row| AccessLevel {
-                id: Id::from(row.get::<Uuid, _>("id").to_string()),
-                name: row.get("name"),
-                description: row.get("description"),
-            })
-            .collect())
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get access level".to_string())))?;
+
+        row.as_ref().map(access_level_from_row).ok_or(AppError::NotFound)
     }
 
-    pub async fn insert(&self, access_level: &AccessLevel) -> AppResult<()> {
+    /// Insert an access level, along with its revision.
+    pub async fn insert(&self, access_level: &AccessLevel, revision: &NewRevision) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+
         sqlx::query(
             r#"
-            INSERT INTO access_levels (id, name, description)
-            VALUES ($1, $2, $3)
+            INSERT INTO access_levels (id, name, description, rank)
+            VALUES ($1, $2, $3, $4)
             "#,
         )
         .bind(uuid_from(&access_level.id))
         .bind(&access_level.name)
         .bind(&access_level.description)
-        .execute(self.dbcp.as_ref())
+        .bind(access_level.rank)
+        .execute(&mut *txn)
         .await
         .map_err(|err| new_app_error_from_sqlx(err, Some(access_level.name.clone())))?;
 
+        RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+
         Ok(())
     }
 
-    pub async fn update(&self, access_level: &AccessLevel) -> AppResult<()> {
+    /// Update an access level, along with its revision. It is refused with `AppError::StaleVersion`
+    /// (without the current copy) if the access level does not have the provided version anymore.
+    pub async fn update(&self, access_level: &AccessLevel, revision: &NewRevision) -> AppResult<()> {
         self.ensure_not_read_only(&access_level.id).await?;
 
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+
         let result = sqlx::query(
             r#"
             UPDATE access_levels
             SET name = $2,
-                description = $3
-            WHERE id = $1
+                description = $3,
+                rank = $5,
+                version = version + 1
+            WHERE id = $1 AND version = $4
             "#,
         )
         .bind(uuid_from(&access_level.id))
         .bind(&access_level.name)
         .bind(&access_level.description)
-        .execute(self.dbcp.as_ref())
+        .bind(access_level.version)
+        .bind(access_level.rank)
+        .execute(&mut *txn)
         .await
         .map_err(|err| new_app_error_from_sqlx(err, Some(access_level.name.clone())))?;
 
+        // It exists (as checked above), thus it has another version.
         if result.rows_affected() == 0 {
-            return Err(AppError::NotFound);
+            return Err(AppError::StaleVersion(serde_json::Value::Null));
+        }
+
+        RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Insert or update an access level as part of an existing transaction.
+    /// An update is refused with `AppError::StaleVersion` (without the current copy) if the access level
+    /// does not have the provided version anymore, or if it's read-only.
+    pub(crate) async fn upsert_in_txn(txn: &mut Transaction<'_, Postgres>, access_level: &AccessLevel) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO access_levels (id, name, description, rank)
+            VALUES ($1, $2, $3, $5)
+            ON CONFLICT (id) DO UPDATE
+                SET name = EXCLUDED.name,
+                    description = EXCLUDED.description,
+                    rank = EXCLUDED.rank,
+                    version = access_levels.version + 1
+                WHERE access_levels.version = $4 AND NOT access_levels.read_only
+            "#,
+        )
+        .bind(uuid_from(&access_level.id))
+        .bind(&access_level.name)
+        .bind(&access_level.description)
+        .bind(access_level.version)
+        .bind(access_level.rank)
+        .execute(&mut **txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some(access_level.name.clone())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::StaleVersion(serde_json::Value::Null));
         }
 
         Ok(())
     }
 
-    pub async fn delete(&self, id: Id) -> AppResult<()> {
+    /// Delete an access level, if it is not used. Otherwise, `AppError::DependenciesExist`
+    /// with the elements that use it is returned (see `where_used`).
+    pub async fn delete(&self, id: Id, clearance: i32) -> AppResult<()> {
         self.ensure_not_read_only(&id).await?;
 
+        let deps = self.where_used(&id, clearance).await?;
+        if !deps.is_empty() {
+            return Err(AppError::DependenciesExist(deps));
+        }
+
         let result = sqlx::query(r#"DELETE FROM access_levels WHERE id = $1"#)
             .bind(uuid_from(&id))
             .execute(self.dbcp.as_ref())
@@ -97,6 +182,37 @@ impl AccessLevelRepo {
         Ok(())
     }
 
+    /// Get the elements that use an access level: the items, the item templates and the users,
+    /// including the items and item templates that are in the Trash.
+    /// The names of the items that are not visible with the provided clearance are redacted.
+    pub async fn where_used(&self, id: &Id, clearance: i32) -> AppResult<Vec<Dependency>> {
+        let sql = format!(
+            r#"
+            SELECT 'i' AS kind, i.id, {} AS name, 'access level' AS relation
+            FROM items i
+            WHERE i.access_level_id = $1
+            UNION ALL
+            SELECT 'it', it.id, it.name, 'access level'
+            FROM item_templates it
+            WHERE it.access_level_id = $1
+            UNION ALL
+            SELECT 'u', u.id, u.username, 'access level'
+            FROM user_accounts u
+            WHERE u.access_level_id = $1
+            ORDER BY 1, 3
+            "#,
+            item_name_sql("i", 2)
+        );
+        let rows = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .bind(clearance)
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get access level usages".to_string())))?;
+
+        Ok(rows.iter().map(dependency_from_row).collect())
+    }
+
     async fn ensure_not_read_only(&self, id: &Id) -> AppResult<()> {
         let row = sqlx::query(
             r#"
@@ -121,3 +237,13 @@ impl AccessLevelRepo {
         Ok(())
     }
 }
+
+fn access_level_from_row(row: &PgRow) -> AccessLevel {
+    AccessLevel {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        description: row.get("description"),
+        rank: row.get("rank"),
+        version: row.get("version"),
+    }
+}
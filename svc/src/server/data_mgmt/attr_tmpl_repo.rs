@@ -1,11 +1,19 @@
-use crate::utils::uuid_from;
+use super::{NewRevision, RevisionRepo, item_name_sql};
+use crate::utils::{new_app_error_from_sqlx, uuid_from};
 use cogs_shared::{
     app::{AppError, AppResult},
-    domain::model::{Id, meta::AttrTemplate},
+    domain::model::{
+        Id,
+        meta::{AttrTemplate, Dependency, Kind},
+    },
+    dtos::{Page, PageRequest},
 };
-use sqlx::{PgPool, types::Uuid};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow, types::Uuid};
 use std::sync::Arc;
 
+/// The fields that the attribute templates can be sorted by, mapped to their columns.
+const ATTR_TEMPLATES_SORT_COLUMNS: &[(&str, &str)] = &[("name", "name"), ("value_type", "value_type")];
+
 #[derive(Clone, Debug)]
 pub struct AttrTemplateRepo {
     dbcp: Arc<PgPool>,
@@ -16,46 +24,51 @@ impl AttrTemplateRepo {
         Self { dbcp }
     }
 
-    /// Retrieve all attribute templates.
-    pub async fn get_all(&self) -> AppResult<Vec<AttrTemplate>> {
-        let rows = sqlx::query_as!(
-            AttrTemplateRow,
+    /// Retrieve a page of the attribute templates (the deleted ones are excluded).
+    pub async fn get_page(&self, page: &PageRequest) -> AppResult<Page<AttrTemplate>> {
+        let sort_column = page.sort_column(ATTR_TEMPLATES_SORT_COLUMNS);
+        let sql = format!(
             r#"
-            SELECT
-                id,
-                name,
-                description,
-                value_type,
-                default_value,
-                required
+            SELECT id, name, description, value_type, default_value, required, version
             FROM attr_templates
-            ORDER BY name ASC
-            "#
-        )
-        .fetch_all(self.dbcp.as_ref())
-        .await
-        .map_err(|err| AppError::from(err.to_string()))?;
+            WHERE deleted_at IS NULL
+            ORDER BY {sort_column} {}, name ASC, id ASC
+            LIMIT $1 OFFSET $2
+            "#,
+            page.dir.as_sql()
+        );
+        let rows = sqlx::query(&sql)
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get attribute templates".to_string())))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attr_templates WHERE deleted_at IS NULL")
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to count attribute templates".to_string())))?;
 
-        let data = rows
-            .into_iter()
-            .map(|r| AttrTemplate {
-                id: Id::from(r.id.to_string()),
-                name: r.name,
-                description: r.description.unwrap_or_default(),
-                value_type: r.value_type.into(),
-                default_value: r.default_value.unwrap_or_default(), // model is String
-                is_required: r.required.unwrap_or(false),
-            })
-            .collect::<Vec<_>>();
-
-        Ok(data)
+        let data = rows.iter().map(attr_template_from_row).collect::<Vec<_>>();
+
+        Ok(Page::new(data, page, total as u64))
+    }
+
+    /// Insert or update an attribute template, along with its revision. An update is refused with `AppError::StaleVersion`
+    /// (without the current copy) if the attribute template does not have the provided version anymore.
+    pub async fn upsert(&self, attr_templ: &AttrTemplate, revision: &NewRevision) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+        Self::upsert_in_txn(&mut txn, attr_templ).await?;
+        RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+        Ok(())
     }
 
-    /// Insert or update an attribute template.
-    pub async fn upsert(&self, attr_templ: &AttrTemplate) -> AppResult<()> {
+    /// Insert or update an attribute template as part of an existing transaction.
+    pub(crate) async fn upsert_in_txn(txn: &mut Transaction<'_, Postgres>, attr_templ: &AttrTemplate) -> AppResult<()> {
         log::debug!("upsert_attr_templ: {attr_templ:?}");
 
-        sqlx::query!(
+        let result = sqlx::query!(
             r#"
             INSERT INTO attr_templates (id, name, description, value_type, default_value, required)
             VALUES ($1, $2, $3, $4, $5, $6)
@@ -64,7 +77,9 @@ impl AttrTemplateRepo {
                     description = EXCLUDED.description,
                     value_type = EXCLUDED.value_type,
                     default_value = EXCLUDED.default_value,
-                    required = EXCLUDED.required
+                    required = EXCLUDED.required,
+                    version = attr_templates.version + 1
+                WHERE attr_templates.version = $7
             "#,
             uuid_from(&attr_templ.id),
             &attr_templ.name,
@@ -72,31 +87,116 @@ impl AttrTemplateRepo {
             attr_templ.value_type.to_string(),
             &attr_templ.default_value, // String in model
             attr_templ.is_required,
+            attr_templ.version,
         )
-        .execute(self.dbcp.as_ref())
+        .execute(&mut **txn)
         .await
         .map_err(|err| AppError::from(err.to_string()))?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::StaleVersion(serde_json::Value::Null));
+        }
         Ok(())
     }
 
-    /// Delete an attribute template.
-    pub async fn delete(&self, id: Id) -> AppResult<()> {
+    /// Get a (not deleted) attribute template.
+    pub async fn get_by_id(&self, id: &Id) -> AppResult<AttrTemplate> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, name, description, value_type, default_value, required, version
+            FROM attr_templates
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(uuid_from(id))
+        .fetch_optional(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get attribute template".to_string())))?;
+
+        row.as_ref().map(attr_template_from_row).ok_or(AppError::NotFound)
+    }
+
+    /// Delete an attribute template for good.
+    pub async fn purge(&self, id: Id) -> AppResult<()> {
         sqlx::query!(r#"DELETE FROM attr_templates WHERE id = $1"#, uuid_from(&id),)
             .execute(self.dbcp.as_ref())
             .await
-            .map_err(|err| AppError::from(err.to_string()))?;
+            .map_err(|err| new_app_error_from_sqlx(err, None))?;
 
         Ok(())
     }
+
+    /// Get the elements that use an attribute template: the item templates (as attribute or
+    /// as listing attribute) and the items (through their attributes or listing attribute).
+    /// The deleted elements (that are in the Trash) are included only if `include_deleted` is set.
+    /// The names of the items that are not visible with the provided clearance are redacted.
+    pub async fn where_used(&self, id: &Id, include_deleted: bool, clearance: i32) -> AppResult<Vec<Dependency>> {
+        let sql = format!(
+            r#"
+            SELECT 'it' AS kind, it.id, it.name, 'attribute' AS relation
+            FROM item_templates it
+            INNER JOIN item_templates_attr_templates_xref x ON x.item_tmpl_id = it.id
+            WHERE x.attr_tmpl_id = $1 AND ($2 OR it.deleted_at IS NULL)
+            UNION ALL
+            SELECT 'it', it.id, it.name, 'listing attribute'
+            FROM item_templates it
+            WHERE it.listing_attr_tmpl_id = $1 AND ($2 OR it.deleted_at IS NULL)
+            UNION ALL
+            SELECT DISTINCT 'i', i.id, {name}, 'attribute'
+            FROM items i
+            INNER JOIN (
+                SELECT owner_id, tmpl_id FROM text_attributes
+                UNION ALL SELECT owner_id, tmpl_id FROM numeric_attributes
+                UNION ALL SELECT owner_id, tmpl_id FROM boolean_attributes
+                UNION ALL SELECT owner_id, tmpl_id FROM date_attributes
+                UNION ALL SELECT owner_id, tmpl_id FROM datetime_attributes
+            ) a ON a.owner_id = i.id
+            WHERE a.tmpl_id = $1 AND ($2 OR i.deleted_at IS NULL)
+            UNION ALL
+            SELECT 'i', i.id, {name}, 'listing attribute'
+            FROM items i
+            WHERE i.listing_attr_tmpl_id = $1 AND ($2 OR i.deleted_at IS NULL)
+            ORDER BY 1, 3
+            "#,
+            name = item_name_sql("i", 3)
+        );
+        let rows = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .bind(include_deleted)
+            .bind(clearance)
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get attribute template usages".to_string())))?;
+
+        Ok(rows.iter().map(dependency_from_row).collect())
+    }
+}
+
+fn attr_template_from_row(row: &PgRow) -> AttrTemplate {
+    AttrTemplate {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        description: row.get::<Option<String>, _>("description").unwrap_or_default(),
+        value_type: row.get::<String, _>("value_type").into(),
+        default_value: row.get::<Option<String>, _>("default_value").unwrap_or_default(), // model is String
+        is_required: row.get::<Option<bool>, _>("required").unwrap_or(false),
+        version: row.get("version"),
+    }
 }
 
-#[derive(Debug)]
-struct AttrTemplateRow {
-    id: Uuid,
-    name: String,
-    description: Option<String>,
-    value_type: String,
-    default_value: Option<String>,
-    required: Option<bool>,
+/// Map a `(kind, id, name, relation)` row to a `Dependency`,
+/// where kind is either `it` (item template), `at` (attribute template), `u` (user) or `i` (item).
+pub(crate) fn dependency_from_row(row: &PgRow) -> Dependency {
+    let kind = match row.get::<&str, _>("kind") {
+        "it" => Kind::ItemTemplate,
+        "at" => Kind::AttributeTemplate,
+        "u" => Kind::User,
+        _ => Kind::Item,
+    };
+    Dependency::new(
+        kind,
+        Id::from(row.get::<Uuid, _>("id").to_string()),
+        row.get::<String, _>("name"),
+        row.get::<String, _>("relation"),
+    )
 }
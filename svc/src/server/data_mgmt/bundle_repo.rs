@@ -0,0 +1,110 @@
+use crate::{
+    server::{AccessLevelRepo, AttrTemplateRepo, ItemLinkRepo, ItemRepo, ItemTemplateRepo, NewRevision, RevisionRepo},
+    utils::new_app_error_from_sqlx,
+};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::{BundleImportPlan, WorkspaceElement},
+        model::{Id, meta::Kind},
+    },
+};
+use sqlx::{PgPool, Row, types::Uuid};
+use std::{collections::HashMap, sync::Arc};
+
+/// The workspace bundles: the elements that an imported bundle may clash with, and the import itself.
+#[derive(Clone, Debug)]
+pub struct BundleRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl BundleRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Get the elements of the workspace (including the deleted ones) that an imported bundle may clash with.
+    pub async fn get_workspace_elements(&self) -> AppResult<Vec<WorkspaceElement>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 'al' AS kind, id, name, '' AS description, version, read_only, false AS deleted
+            FROM access_levels
+            UNION ALL
+            SELECT 'at', id, name, COALESCE(description, ''), version, false, deleted_at IS NOT NULL
+            FROM attr_templates
+            UNION ALL
+            SELECT 'it', id, name, '', version, false, deleted_at IS NOT NULL
+            FROM item_templates
+            UNION ALL
+            SELECT 'i', id, COALESCE(listing_attr_value, ''), '', version, false, deleted_at IS NOT NULL
+            FROM items
+            "#,
+        )
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the workspace elements".to_string())))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WorkspaceElement {
+                kind: match row.get::<&str, _>("kind") {
+                    "al" => Kind::AccessLevel,
+                    "at" => Kind::AttributeTemplate,
+                    "it" => Kind::ItemTemplate,
+                    _ => Kind::Item,
+                },
+                id: Id::from(row.get::<Uuid, _>("id").to_string()),
+                name: row.get("name"),
+                description: row.get("description"),
+                version: row.get("version"),
+                read_only: row.get("read_only"),
+                deleted: row.get("deleted"),
+            })
+            .collect())
+    }
+
+    /// Write the elements of an imported bundle, along with their revisions, either all of them or none.
+    pub async fn import(&self, plan: &BundleImportPlan, revisions: &[NewRevision]) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        // The item templates may link to each other.
+        sqlx::query("SET CONSTRAINTS item_template_links_target_fk DEFERRED")
+            .execute(&mut *txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to defer the constraints".to_string())))?;
+
+        for access_level in &plan.access_levels {
+            AccessLevelRepo::upsert_in_txn(&mut txn, access_level).await?;
+        }
+        for attr_tmpl in &plan.attr_templates {
+            AttrTemplateRepo::upsert_in_txn(&mut txn, attr_tmpl).await?;
+        }
+        for item_tmpl in &plan.item_templates {
+            ItemTemplateRepo::upsert_in_txn(&mut txn, item_tmpl).await?;
+        }
+        for item in &plan.items {
+            ItemRepo::upsert_in_txn(&mut txn, item).await?;
+        }
+
+        // The item links refer the item template links by the template of their source item.
+        let tmpl_ids: HashMap<&Id, &Option<Id>> = plan.items.iter().map(|i| (&i.id, &i.tmpl_id)).collect();
+        let item_ids: Vec<Id> = plan.items.iter().map(|i| i.id.clone()).collect();
+        let links: Vec<_> = plan
+            .item_links
+            .iter()
+            .map(|link| {
+                let tmpl_id = tmpl_ids.get(&link.source_item_id).and_then(|id| (*id).clone());
+                (link.clone(), tmpl_id)
+            })
+            .collect();
+        ItemLinkRepo::replace_in_txn(&mut txn, &item_ids, &links).await?;
+
+        for revision in revisions {
+            RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        }
+
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -1,22 +1,52 @@
 use std::sync::Arc;
 
 use crate::{
-    server::{AccessLevelRepo, AttrTemplateRepo, ItemTemplateRepo},
+    server::{
+        AccessLevelRepo, AttrTemplateRepo, BundleRepo, ItemLinkRepo, ItemRepo, ItemTemplateRepo, NewRevision, RevisionRepo,
+        SearchRepo, TrashRepo,
+    },
     utils::new_id,
 };
 use cogs_shared::{
-    app::AppResult,
-    domain::model::{
-        AccessLevel, Id,
-        meta::{AttrTemplate, ItemTemplate},
+    app::{AppError, AppResult},
+    domain::{
+        logic::{
+            BundleImportPlan, FieldChange, ItemQuery, ItemTemplateChangePlan, ItemsExporter, OrphanAttrsPolicy, diff_revisions,
+            items_from_csv, plan_bundle_import, validate_item,
+        },
+        model::{
+            AccessLevel, Id, Revision, UserAccount,
+            meta::{AttrTemplate, Dependency, Item, ItemLink, ItemTemplate, Kind},
+        },
+    },
+    dtos::{
+        BUNDLE_FORMAT_VERSION, BundleImportReport, BundleImportRequest, GRAPH_MAX_NODES, GraphParams, ItemGraph,
+        ItemsExportParams, ItemsImportReport, ItemsImportRequest, ItemsParams, PAGE_MAX_LIMIT, Page, PageRequest,
+        SearchHitsGroup, TrashEntry, WorkspaceBundle,
     },
 };
+use futures::{Stream, stream};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// The max length of `items.listing_attr_value` column.
+const LISTING_ATTR_VALUE_MAX_LEN: usize = 64;
+
+/// The max length of `item_links.name` column.
+const LINK_NAME_MAX_LEN: usize = 64;
 
 #[derive(Clone, Debug)]
 pub struct DataMgmt {
     attr_templ_repo: Arc<AttrTemplateRepo>,
     item_templ_repo: Arc<ItemTemplateRepo>,
     access_level_repo: Arc<AccessLevelRepo>,
+    item_repo: Arc<ItemRepo>,
+    item_link_repo: Arc<ItemLinkRepo>,
+    search_repo: Arc<SearchRepo>,
+    revision_repo: Arc<RevisionRepo>,
+    trash_repo: Arc<TrashRepo>,
+    bundle_repo: Arc<BundleRepo>,
 }
 
 impl DataMgmt {
@@ -25,11 +55,23 @@ impl DataMgmt {
         attr_templ_repo: Arc<AttrTemplateRepo>,
         item_templ_repo: Arc<ItemTemplateRepo>,
         access_level_repo: Arc<AccessLevelRepo>,
+        item_repo: Arc<ItemRepo>,
+        item_link_repo: Arc<ItemLinkRepo>,
+        search_repo: Arc<SearchRepo>,
+        revision_repo: Arc<RevisionRepo>,
+        trash_repo: Arc<TrashRepo>,
+        bundle_repo: Arc<BundleRepo>,
     ) -> Self {
         Self {
             attr_templ_repo,
             item_templ_repo,
             access_level_repo,
+            item_repo,
+            item_link_repo,
+            search_repo,
+            revision_repo,
+            trash_repo,
+            bundle_repo,
         }
     }
 
@@ -37,62 +79,598 @@ impl DataMgmt {
     // Attribute Templates
     // -------------------
 
-    pub async fn upsert_attr_template(&self, mut attr_templ: AttrTemplate) -> AppResult<Id> {
+    /// Create or update an attribute template. On update, it must have the current version,
+    /// otherwise `AppError::StaleVersion` with the current copy is returned.
+    pub async fn upsert_attr_template(&self, mut attr_templ: AttrTemplate, user: Option<&UserAccount>) -> AppResult<Id> {
         if attr_templ.id.clone().is_zero() {
             attr_templ.id = new_id();
         }
-        self.attr_templ_repo.upsert(&attr_templ).await?;
+        let revision = NewRevision::new(Kind::AttributeTemplate, &attr_templ.id, &attr_templ, user)?;
+        let res = self.attr_templ_repo.upsert(&attr_templ, &revision).await;
+        self.with_current_copy(res, Kind::AttributeTemplate, &attr_templ.id, user)
+            .await?;
         Ok(attr_templ.id)
     }
 
-    pub async fn get_all_attr_templates(&self) -> AppResult<Vec<AttrTemplate>> {
-        self.attr_templ_repo.get_all().await
+    pub async fn get_attr_templates(&self, page: PageRequest) -> AppResult<Page<AttrTemplate>> {
+        self.attr_templ_repo.get_page(&page).await
+    }
+
+    /// Delete (move to the Trash) an attribute template, if it is not used.
+    /// Otherwise, `AppError::DependenciesExist` with the elements that use it is returned.
+    pub async fn delete_attr_template(&self, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        let deps = self.attr_templ_repo.where_used(&id, false, clearance_of(user)).await?;
+        if !deps.is_empty() {
+            return Err(AppError::DependenciesExist(deps));
+        }
+        self.trash_repo.delete(&Kind::AttributeTemplate, &id, user).await
     }
 
-    pub async fn delete_attr_template(&self, id: Id) -> AppResult<()> {
-        self.attr_templ_repo.delete(id).await
+    /// Get the elements that use an attribute template, with the names of the items that the user is not cleared for redacted.
+    pub async fn get_attr_template_usages(&self, id: Id, user: Option<&UserAccount>) -> AppResult<Vec<Dependency>> {
+        self.attr_templ_repo.where_used(&id, false, clearance_of(user)).await
     }
 
     // --------------
     // Item Templates
     // --------------
 
-    pub async fn upsert_item_template(&self, mut item_templ: ItemTemplate) -> AppResult<Id> {
+    /// Create or update an item template. On update, the existing items that were created
+    /// from it are migrated to its new shape, as described by `ItemTemplateChangePlan`.
+    /// A revision is recorded for the item template and for each migrated item.
+    /// On update, it must have the current version, otherwise `AppError::StaleVersion` with the current copy is returned.
+    pub async fn upsert_item_template(
+        &self,
+        mut item_templ: ItemTemplate,
+        orphans: OrphanAttrsPolicy,
+        user: Option<&UserAccount>,
+    ) -> AppResult<Id> {
         if item_templ.id.clone().is_zero() {
             item_templ.id = new_id();
+            let revision = NewRevision::new(Kind::ItemTemplate, &item_templ.id, &item_templ, user)?;
+            self.item_templ_repo.upsert(&item_templ, &[], &[revision]).await?;
+            return Ok(item_templ.id);
         }
-        self.item_templ_repo.upsert(&item_templ).await?;
+        let plan = self.plan_item_template_change(&item_templ).await?;
+        let mut items = Vec::new();
+        if !plan.is_empty() {
+            items = self.item_repo.get_by_tmpl_id(&item_templ.id).await?;
+            for item in &mut items {
+                plan.apply_to(item, &item_templ, orphans);
+                item.renew_attribute_ids(new_id);
+                item.listing_attr_value = item.listing_attr_value.chars().take(LISTING_ATTR_VALUE_MAX_LEN).collect();
+            }
+        }
+        let mut revisions = vec![NewRevision::new(Kind::ItemTemplate, &item_templ.id, &item_templ, user)?];
+        for item in &items {
+            revisions.push(NewRevision::new(Kind::Item, &item.id, item, user)?);
+        }
+        let res = self.item_templ_repo.upsert(&item_templ, &items, &revisions).await;
+        self.with_current_copy(res, Kind::ItemTemplate, &item_templ.id, user).await?;
         Ok(item_templ.id)
     }
 
-    pub async fn get_all_item_templates(&self) -> AppResult<Vec<ItemTemplate>> {
-        self.item_templ_repo.get_all().await
+    /// Compute (as a dry-run) the plan of changing an item template to the provided shape.
+    /// For a new item template, the plan is empty.
+    pub async fn plan_item_template_change(&self, item_templ: &ItemTemplate) -> AppResult<ItemTemplateChangePlan> {
+        if item_templ.id.is_zero() {
+            return Ok(ItemTemplateChangePlan::default());
+        }
+        let old = match self.item_templ_repo.get_by_id(&item_templ.id).await {
+            Ok(old) => old,
+            Err(AppError::NotFound) => return Ok(ItemTemplateChangePlan::default()),
+            Err(err) => return Err(err),
+        };
+        let mut plan = ItemTemplateChangePlan::new(&old, item_templ);
+        plan.affected_items = self.item_repo.get_by_tmpl_id(&item_templ.id).await?.len();
+        Ok(plan)
+    }
+
+    pub async fn get_item_templates(&self, page: PageRequest) -> AppResult<Page<ItemTemplate>> {
+        self.item_templ_repo.get_page(&page).await
+    }
+
+    /// Delete (move to the Trash) an item template, if it is not used.
+    /// Otherwise, `AppError::DependenciesExist` with the elements that use it is returned.
+    pub async fn delete_item_template(&self, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        let deps = self.item_templ_repo.where_used(&id, false, clearance_of(user)).await?;
+        if !deps.is_empty() {
+            return Err(AppError::DependenciesExist(deps));
+        }
+        self.trash_repo.delete(&Kind::ItemTemplate, &id, user).await
+    }
+
+    /// Get the elements that use an item template, with the names of the items that the user is not cleared for redacted.
+    pub async fn get_item_template_usages(&self, id: Id, user: Option<&UserAccount>) -> AppResult<Vec<Dependency>> {
+        self.item_templ_repo.where_used(&id, false, clearance_of(user)).await
+    }
+
+    // -----
+    // Items
+    // -----
+
+    /// Create or update an item. The item is validated against its template (if any) and,
+    /// in case of violations, `AppError::ValidationFailed` is returned.
+    /// On update, it must have the current version, otherwise `AppError::StaleVersion` with the current copy is returned.
+    /// An existing item that the user is not cleared for is `AppError::NotFound`.
+    pub async fn upsert_item(&self, mut item: Item, user: Option<&UserAccount>) -> AppResult<Id> {
+        if !item.id.is_zero() {
+            self.ensure_not_hidden(&Kind::Item, &item.id, user).await?;
+        }
+        let tmpl = match &item.tmpl_id {
+            Some(tmpl_id) => Some(self.item_templ_repo.get_by_id(tmpl_id).await?),
+            None => None,
+        };
+        let violations = validate_item(&item, tmpl.as_ref());
+        if !violations.is_empty() {
+            return Err(AppError::ValidationFailed(violations));
+        }
+        if item.id.is_zero() {
+            item.id = new_id();
+            // A new item gets the access level of its template, unless it has its own.
+            if item.access_level_id.is_none() {
+                item.access_level_id = tmpl.as_ref().and_then(|t| t.access_level_id.clone());
+            }
+        }
+        // The attributes are replaced on each save, thus they always get new ids.
+        item.renew_attribute_ids(new_id);
+        item.refresh_listing_attr();
+        item.listing_attr_value = item.listing_attr_value.chars().take(LISTING_ATTR_VALUE_MAX_LEN).collect();
+        let revision = NewRevision::new(Kind::Item, &item.id, &item, user)?;
+        let res = self.item_repo.upsert(&item, &revision).await;
+        self.with_current_copy(res, Kind::Item, &item.id, user).await?;
+        Ok(item.id)
+    }
+
+    /// Import items, from the rows of a CSV text, into an item template.
+    /// Either all the rows are imported or none (if any of them is not valid).
+    /// On a dry run, the rows are only checked.
+    pub async fn import_items(&self, req: ItemsImportRequest, user: Option<&UserAccount>) -> AppResult<ItemsImportReport> {
+        let tmpl = self.item_templ_repo.get_by_id(&req.tmpl_id).await?;
+        let import = items_from_csv(&tmpl, &req.csv, &req.columns)?;
+        let mut report = ItemsImportReport {
+            dry_run: req.dry_run,
+            imported: 0,
+            errors: import.errors,
+        };
+        if req.dry_run || !report.errors.is_empty() {
+            if report.errors.is_empty() {
+                report.imported = import.items.len();
+            }
+            return Ok(report);
+        }
+        let mut items = import.items;
+        for item in &mut items {
+            item.id = new_id();
+            item.renew_attribute_ids(new_id);
+            item.listing_attr_value = item.listing_attr_value.chars().take(LISTING_ATTR_VALUE_MAX_LEN).collect();
+        }
+        let revisions = items
+            .iter()
+            .map(|item| NewRevision::new(Kind::Item, &item.id, item, user))
+            .collect::<AppResult<Vec<_>>>()?;
+        self.item_repo.upsert_all(&items, &revisions).await?;
+        report.imported = items.len();
+        Ok(report)
+    }
+
+    /// Get a page of the items, optionally only the ones that match the filter (in the `ItemQuery` syntax)
+    /// and that are created from the item template, as provided in `params`. Only the items that the user
+    /// is cleared for are included.
+    pub async fn get_items(&self, params: ItemsParams, page: PageRequest, user: Option<&UserAccount>) -> AppResult<Page<Item>> {
+        let filter = parse_items_filter(params.filter.as_deref())?;
+        if let Some(tmpl_id) = &params.tmpl_id
+            && Uuid::parse_str(&tmpl_id.0).is_err()
+        {
+            return Err(AppError::InvalidInput(format!("invalid item template id '{tmpl_id}'")));
+        }
+        self.item_repo
+            .get_page(filter.as_ref(), params.tmpl_id.as_ref(), clearance_of(user), &page)
+            .await
+    }
+
+    /// Export the items of an item template, optionally only the ones that match the filter (in the `ItemQuery` syntax).
+    /// The items are read a page at a time, so the export is streamed as chunks of text in the requested format.
+    /// Only the items that the user is cleared for are exported.
+    pub async fn export_items(
+        &self,
+        tmpl_id: Id,
+        params: ItemsExportParams,
+        user: Option<&UserAccount>,
+    ) -> AppResult<impl Stream<Item = AppResult<String>> + Send + use<>> {
+        let filter = parse_items_filter(params.filter.as_deref())?;
+        if Uuid::parse_str(&tmpl_id.0).is_err() {
+            return Err(AppError::InvalidInput(format!("invalid item template id '{tmpl_id}'")));
+        }
+        let tmpl = self.item_templ_repo.get_by_id(&tmpl_id).await?;
+        let exporter = ItemsExporter::new(params.format, &tmpl);
+        let item_repo = self.item_repo.clone();
+        let clearance = clearance_of(user);
+
+        // The state is the exporter and the offset of the next page, and it's gone once the export is finished.
+        Ok(stream::unfold(Some((exporter, 0)), move |state| {
+            let (item_repo, filter, tmpl_id) = (item_repo.clone(), filter.clone(), tmpl_id.clone());
+            async move {
+                let (mut exporter, offset) = state?;
+                let page = PageRequest::new(offset, PAGE_MAX_LIMIT);
+                match item_repo.get_page(filter.as_ref(), Some(&tmpl_id), clearance, &page).await {
+                    Ok(page) if page.items.is_empty() => Some((Ok(exporter.finish()), None)),
+                    Ok(page) => {
+                        let chunk = exporter.write(&page.items);
+                        let offset = offset + page.items.len() as u32;
+                        Some((Ok(chunk), Some((exporter, offset))))
+                    }
+                    Err(err) => {
+                        log::error!("Failed to export the items of item template '{tmpl_id}' at offset {offset}: {err}");
+                        Some((Err(err), None))
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Get an item. It's `AppError::NotFound` if the user is not cleared for it.
+    pub async fn get_item(&self, id: Id, user: Option<&UserAccount>) -> AppResult<Item> {
+        self.item_repo.get_by_id(&id, clearance_of(user)).await
+    }
+
+    /// Delete (move to the Trash) an item. It's `AppError::NotFound` if the user is not cleared for it.
+    pub async fn delete_item(&self, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        self.ensure_not_hidden(&Kind::Item, &id, user).await?;
+        self.trash_repo.delete(&Kind::Item, &id, user).await
+    }
+
+    // ----------
+    // Item Links
+    // ----------
+
+    /// Create an outgoing link of an item. For items created from a template, the link must be one
+    /// declared by the template (both as name and target item template). Items created from scratch
+    /// can have any (free-form) links. The user must be cleared for both items.
+    pub async fn create_item_link(&self, source_item_id: Id, mut link: ItemLink, user: Option<&UserAccount>) -> AppResult<Id> {
+        let clearance = clearance_of(user);
+        let source = self.item_repo.get_by_id(&source_item_id, clearance).await?;
+        let target = match self.item_repo.get_by_id(&link.target_item_id, clearance).await {
+            Ok(target) => target,
+            Err(AppError::NotFound) => return Err(AppError::InvalidInput("the target item does not exist".into())),
+            Err(err) => return Err(err),
+        };
+        if source.id == target.id {
+            return Err(AppError::InvalidInput("an item cannot be linked to itself".into()));
+        }
+        link.name = link.name.trim().to_string();
+        if link.name.is_empty() || link.name.chars().count() > LINK_NAME_MAX_LEN {
+            return Err(AppError::InvalidInput(format!(
+                "the link name must have between 1 and {LINK_NAME_MAX_LEN} characters"
+            )));
+        }
+
+        if let Some(tmpl_id) = &source.tmpl_id {
+            let tmpl = self.item_templ_repo.get_by_id(tmpl_id).await?;
+            let Some(tmpl_link) = tmpl.links.iter().find(|l| l.name == link.name) else {
+                return Err(AppError::InvalidInput(format!(
+                    "link '{}' is not declared by item template '{}'",
+                    link.name, tmpl.name
+                )));
+            };
+            if target.tmpl_id.as_ref() != Some(&tmpl_link.item_template_id) {
+                return Err(AppError::InvalidInput(format!(
+                    "the target of link '{}' must be an item of the template declared by '{}'",
+                    link.name, tmpl.name
+                )));
+            }
+        }
+
+        link.id = new_id();
+        link.source_item_id = source.id;
+        self.item_link_repo.insert(&link, source.tmpl_id.as_ref()).await?;
+        Ok(link.id)
+    }
+
+    /// Get the outgoing links of an item, to the items that the user is cleared for.
+    pub async fn get_item_links(&self, source_item_id: Id, user: Option<&UserAccount>) -> AppResult<Vec<ItemLink>> {
+        let clearance = clearance_of(user);
+        // To distinguish between an unknown item and an item without links.
+        self.item_repo.get_by_id(&source_item_id, clearance).await?;
+        self.item_link_repo.get_by_source(&source_item_id, clearance).await
+    }
+
+    /// Delete an outgoing link of an item. It's `AppError::NotFound` if the user is not cleared for both items.
+    pub async fn delete_item_link(&self, source_item_id: Id, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        // The visible links are the ones from and to the items that the user is cleared for.
+        let links = self.get_item_links(source_item_id.clone(), user).await?;
+        if !links.iter().any(|link| link.id == id) {
+            return Err(AppError::NotFound);
+        }
+        self.item_link_repo.delete(&source_item_id, &id).await
     }
 
-    pub async fn delete_item_template(&self, id: Id) -> AppResult<()> {
-        self.item_templ_repo.delete(id).await
+    /// Get the graph (neighborhood) of an item, as reached by following its links (as per the params).
+    /// Only the items that the user is cleared for are included (and walked through).
+    /// It's `AppError::NotFound` if the item does not exist, it is deleted, or the user is not cleared for it.
+    pub async fn get_item_graph(&self, id: Id, params: &GraphParams, user: Option<&UserAccount>) -> AppResult<ItemGraph> {
+        self.item_link_repo
+            .get_graph(
+                &id,
+                params.depth(),
+                &params.link_names(),
+                params.direction,
+                GRAPH_MAX_NODES,
+                clearance_of(user),
+            )
+            .await
     }
 
     // -------------
     // Access Levels
     // -------------
 
-    pub async fn upsert_access_level(&self, mut access_level: AccessLevel) -> AppResult<Id> {
+    /// Create or update an access level. On update, it must have the current version,
+    /// otherwise `AppError::StaleVersion` with the current copy is returned.
+    pub async fn upsert_access_level(&self, mut access_level: AccessLevel, user: Option<&UserAccount>) -> AppResult<Id> {
         if access_level.id.is_zero() {
             access_level.id = new_id();
-            self.access_level_repo.insert(&access_level).await?;
+            let revision = NewRevision::new(Kind::AccessLevel, &access_level.id, &access_level, user)?;
+            self.access_level_repo.insert(&access_level, &revision).await?;
         } else {
-            self.access_level_repo.update(&access_level).await?;
+            let revision = NewRevision::new(Kind::AccessLevel, &access_level.id, &access_level, user)?;
+            let res = self.access_level_repo.update(&access_level, &revision).await;
+            self.with_current_copy(res, Kind::AccessLevel, &access_level.id, user).await?;
         }
 
         Ok(access_level.id)
     }
 
-    pub async fn get_all_access_levels(&self) -> AppResult<Vec<AccessLevel>> {
-        self.access_level_repo.get_all().await
+    pub async fn get_access_levels(&self, page: PageRequest) -> AppResult<Page<AccessLevel>> {
+        self.access_level_repo.get_page(&page).await
     }
 
-    pub async fn delete_access_level(&self, id: Id) -> AppResult<()> {
-        self.access_level_repo.delete(id).await
+    /// Delete an access level, if it is not used by any item, item template or user.
+    /// Otherwise, `AppError::DependenciesExist` with the elements that use it is returned.
+    pub async fn delete_access_level(&self, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        self.access_level_repo.delete(id, clearance_of(user)).await
+    }
+
+    // -----------------
+    // Workspace Bundles
+    // -----------------
+
+    /// Export (the not deleted elements of) the workspace as a bundle,
+    /// with the items (and the links between them) that the user is cleared for.
+    pub async fn export_bundle(&self, user: Option<&UserAccount>) -> AppResult<WorkspaceBundle> {
+        let clearance = clearance_of(user);
+        // Get all the pages of the elements, as read by the provided call (of the provided page request).
+        macro_rules! all_pages {
+            (|$page:ident| $get_page:expr) => {{
+                let mut all = Vec::new();
+                loop {
+                    let $page = &PageRequest::new(all.len() as u32, PAGE_MAX_LIMIT);
+                    let fetched = $get_page.await?;
+                    let done = fetched.items.is_empty() || all.len() + fetched.items.len() >= fetched.total as usize;
+                    all.extend(fetched.items);
+                    if done {
+                        break all;
+                    }
+                }
+            }};
+        }
+
+        Ok(WorkspaceBundle {
+            format_version: BUNDLE_FORMAT_VERSION,
+            access_levels: all_pages!(|page| self.access_level_repo.get_page(page)),
+            attr_templates: all_pages!(|page| self.attr_templ_repo.get_page(page)),
+            item_templates: all_pages!(|page| self.item_templ_repo.get_page(page)),
+            items: all_pages!(|page| self.item_repo.get_page(None, None, clearance, page)),
+            item_links: self.item_link_repo.get_all(clearance).await?,
+        })
+    }
+
+    /// Import a workspace bundle in a single transaction, as planned by `plan_bundle_import`.
+    /// The items are validated against their templates (as on save) and, in case of violations,
+    /// `AppError::ValidationFailed` is returned, without importing anything.
+    /// A revision is recorded for each element that is created or overwritten.
+    pub async fn import_bundle(&self, req: BundleImportRequest, user: Option<&UserAccount>) -> AppResult<BundleImportReport> {
+        let existing = self.bundle_repo.get_workspace_elements().await?;
+        let mut plan: BundleImportPlan = plan_bundle_import(&req, &existing, new_id)?;
+        for item in &mut plan.items {
+            item.listing_attr_value = item.listing_attr_value.chars().take(LISTING_ATTR_VALUE_MAX_LEN).collect();
+        }
+        // The items are validated (as on save) before anything is written.
+        let mut workspace_tmpls = Vec::new();
+        for tmpl_id in plan.kept_item_tmpl_ids() {
+            workspace_tmpls.push(self.item_templ_repo.get_by_id(&tmpl_id).await?);
+        }
+        let violations = plan.validate_items(&workspace_tmpls);
+        if !violations.is_empty() {
+            return Err(AppError::ValidationFailed(violations));
+        }
+        let mut revisions = Vec::new();
+        for access_level in &plan.access_levels {
+            revisions.push(NewRevision::new(Kind::AccessLevel, &access_level.id, access_level, user)?);
+        }
+        for attr_tmpl in &plan.attr_templates {
+            revisions.push(NewRevision::new(Kind::AttributeTemplate, &attr_tmpl.id, attr_tmpl, user)?);
+        }
+        for item_tmpl in &plan.item_templates {
+            revisions.push(NewRevision::new(Kind::ItemTemplate, &item_tmpl.id, item_tmpl, user)?);
+        }
+        for item in &plan.items {
+            revisions.push(NewRevision::new(Kind::Item, &item.id, item, user)?);
+        }
+        self.bundle_repo.import(&plan, &revisions).await?;
+        Ok(plan.report)
+    }
+
+    // ---------
+    // Revisions
+    // ---------
+
+    /// Get the revisions of an element, the newest first.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    pub async fn get_revisions(&self, kind: Kind, element_id: Id, user: Option<&UserAccount>) -> AppResult<Vec<Revision>> {
+        self.ensure_not_hidden(&kind, &element_id, user).await?;
+        self.revision_repo.get_all(&kind, &element_id).await
+    }
+
+    /// Compare two revisions of the same element, field by field.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    pub async fn diff_revisions(&self, from: Id, to: Id, user: Option<&UserAccount>) -> AppResult<Vec<FieldChange>> {
+        let from = self.revision_repo.get_by_id(&from).await?;
+        let to = self.revision_repo.get_by_id(&to).await?;
+        self.ensure_not_hidden(&from.kind, &from.element_id, user).await?;
+        diff_revisions(&from, &to)
+    }
+
+    /// Refuse (as `AppError::NotFound`, so it cannot be told apart from a missing one) an item
+    /// that the user is not cleared for.
+    async fn ensure_not_hidden(&self, kind: &Kind, id: &Id, user: Option<&UserAccount>) -> AppResult<()> {
+        if *kind == Kind::Item && self.item_repo.is_hidden(id, clearance_of(user)).await? {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Restore an element as it was in a revision. This is a change as well,
+    /// thus it gets recorded as a new (the latest) revision of the element.
+    /// It returns the kind and the id of the restored element.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    pub async fn restore_revision(&self, id: Id, user: Option<&UserAccount>) -> AppResult<(Kind, Id)> {
+        let mut revision = self.revision_repo.get_by_id(&id).await?;
+        self.ensure_not_hidden(&revision.kind, &revision.element_id, user).await?;
+        // It replaces the current copy (if any), whatever its version is.
+        let version = match self.current_copy(&revision.kind, &revision.element_id, user).await {
+            Ok(current) => current.get("version").cloned().unwrap_or_default(),
+            Err(AppError::NotFound) => Value::from(0),
+            Err(err) => return Err(err),
+        };
+        if let Some(snapshot) = revision.snapshot.as_object_mut() {
+            snapshot.insert("version".to_string(), version);
+        }
+        let element_id = match revision.kind {
+            Kind::Item => self.upsert_item(snapshot_of(&revision)?, user).await?,
+            Kind::ItemTemplate => {
+                self.upsert_item_template(snapshot_of(&revision)?, OrphanAttrsPolicy::default(), user)
+                    .await?
+            }
+            Kind::AttributeTemplate => self.upsert_attr_template(snapshot_of(&revision)?, user).await?,
+            Kind::AccessLevel => {
+                let access_level: AccessLevel = snapshot_of(&revision)?;
+                let new_revision = NewRevision::new(Kind::AccessLevel, &access_level.id, &access_level, user)?;
+                // It gets recreated, in case it was deleted in the meantime.
+                match self.access_level_repo.update(&access_level, &new_revision).await {
+                    Err(AppError::NotFound) => self.access_level_repo.insert(&access_level, &new_revision).await?,
+                    res => res?,
+                }
+                access_level.id
+            }
+            Kind::LinkTemplate => return Err(AppError::InvalidInput("link templates have no revisions".into())),
+            Kind::User => return Err(AppError::InvalidInput("users have no revisions".into())),
+        };
+        Ok((revision.kind, element_id))
+    }
+
+    /// Get the current (saved) copy of an element, as JSON.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    async fn current_copy(&self, kind: &Kind, id: &Id, user: Option<&UserAccount>) -> AppResult<Value> {
+        let current = match kind {
+            Kind::Item => serde_json::to_value(self.item_repo.get_by_id(id, clearance_of(user)).await?),
+            Kind::ItemTemplate => serde_json::to_value(self.item_templ_repo.get_by_id(id).await?),
+            Kind::AttributeTemplate => serde_json::to_value(self.attr_templ_repo.get_by_id(id).await?),
+            Kind::AccessLevel => serde_json::to_value(self.access_level_repo.get_by_id(id).await?),
+            Kind::LinkTemplate | Kind::User => return Err(AppError::NotFound),
+        };
+        current.map_err(|err| {
+            log::error!("Failed to serialize the current copy of {kind:?} '{id}': {err}");
+            AppError::InternalErr
+        })
+    }
+
+    /// Include the current copy of the element (as the user is cleared to see it) in an `AppError::StaleVersion`
+    /// result of saving it.
+    async fn with_current_copy(&self, res: AppResult<()>, kind: Kind, id: &Id, user: Option<&UserAccount>) -> AppResult<()> {
+        match res {
+            Err(AppError::StaleVersion(_)) => Err(AppError::StaleVersion(self.current_copy(&kind, id, user).await?)),
+            res => res,
+        }
+    }
+
+    // -----
+    // Trash
+    // -----
+
+    /// Get a page of the (soft) deleted items (that the user is cleared for) and templates.
+    pub async fn get_trash(&self, page: PageRequest, user: Option<&UserAccount>) -> AppResult<Page<TrashEntry>> {
+        self.trash_repo.get_page(&page, clearance_of(user)).await
+    }
+
+    /// Take an element out of the Trash. The elements that it refers to and that are
+    /// in the Trash as well (such as the template of an item) must be restored first.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    pub async fn restore_from_trash(&self, kind: Kind, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        self.ensure_not_hidden(&kind, &id, user).await?;
+        let refs = self.trash_repo.deleted_references(&kind, &id).await?;
+        if !refs.is_empty() {
+            let refs = refs.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+            return Err(AppError::InvalidInput(format!(
+                "it refers to deleted elements that must be restored first: {refs}"
+            )));
+        }
+        self.trash_repo.restore(&kind, &id).await
+    }
+
+    /// Delete for good an element that is in the Trash, if it is not used by any other element,
+    /// including the ones in the Trash. Otherwise, `AppError::DependenciesExist` is returned.
+    /// It's `AppError::NotFound` for an item that the user is not cleared for.
+    pub async fn purge(&self, kind: Kind, id: Id, user: Option<&UserAccount>) -> AppResult<()> {
+        self.ensure_not_hidden(&kind, &id, user).await?;
+        if !self.trash_repo.is_deleted(&kind, &id).await? {
+            return Err(AppError::InvalidInput("only the elements in the trash can be purged".into()));
+        }
+        let deps = match kind {
+            Kind::AttributeTemplate => self.attr_templ_repo.where_used(&id, true, clearance_of(user)).await?,
+            Kind::ItemTemplate => self.item_templ_repo.where_used(&id, true, clearance_of(user)).await?,
+            _ => Vec::new(),
+        };
+        if !deps.is_empty() {
+            return Err(AppError::DependenciesExist(deps));
+        }
+        match kind {
+            Kind::AttributeTemplate => self.attr_templ_repo.purge(id).await,
+            Kind::ItemTemplate => self.item_templ_repo.purge(id).await,
+            _ => self.item_repo.purge(&id).await,
+        }
+    }
+
+    // ------
+    // Search
+    // ------
+
+    /// Search the items (that the user is cleared for) and the templates, getting the hits grouped by their kind.
+    pub async fn search(&self, query: &str, user: Option<&UserAccount>) -> AppResult<Vec<SearchHitsGroup>> {
+        let hits = self.search_repo.search(query, clearance_of(user)).await?;
+        Ok(SearchHitsGroup::group(hits))
+    }
+}
+
+/// The clearance of a user: the rank of its access level, whatever its permissions are
+/// (as reading the data at all requires `Admin::Read`). The anonymous ones see only the not restricted items.
+fn clearance_of(user: Option<&UserAccount>) -> i32 {
+    user.map_or(0, |user| user.clearance)
+}
+
+/// Get the element out of the snapshot of a revision.
+fn snapshot_of<T: DeserializeOwned>(revision: &Revision) -> AppResult<T> {
+    serde_json::from_value(revision.snapshot.clone())
+        .map_err(|err| AppError::ErrDetails("Invalid revision snapshot".into(), err.to_string()))
+}
+
+/// Parse the items filter (in the `ItemQuery` syntax), if it's not blank.
+fn parse_items_filter(filter: Option<&str>) -> AppResult<Option<ItemQuery>> {
+    match filter.map(str::trim) {
+        Some(filter) if !filter.is_empty() => filter
+            .parse::<ItemQuery>()
+            .map(Some)
+            .map_err(|err| AppError::InvalidInput(err.to_string())),
+        _ => Ok(None),
     }
 }
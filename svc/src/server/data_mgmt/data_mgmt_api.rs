@@ -1,111 +1,388 @@
-use crate::server::{ServerState, respond_forbidden, respond_internal_server_error, respond_not_found};
+use crate::server::{
+    CurrentUser, ServerState, respond_bad_request, respond_conflict, respond_forbidden, respond_internal_server_error,
+    respond_not_found, respond_stale_version, respond_unprocessable_entity,
+};
 use axum::{
     Json,
-    extract::{self, Path, State},
-    response::IntoResponse,
+    body::Body,
+    extract::{self, Path, Query, State},
+    response::{IntoResponse, Response},
 };
 use cogs_shared::{
     app::AppError,
     domain::model::{
-        AccessLevel, Id,
-        meta::{AttrTemplate, ItemTemplate},
+        AccessLevel, Id, UserAccount,
+        meta::{AttrTemplate, Item, ItemLink, ItemTemplate, Kind},
+    },
+    dtos::{
+        BundleImportRequest, GraphParams, ItemTemplateUpsertParams, ItemsExportParams, ItemsImportRequest, ItemsParams,
+        PageRequest, RevisionDiffParams, SearchParams,
     },
 };
-use http::StatusCode;
-use serde_json::json;
+use http::{StatusCode, header};
+use serde_json::{Value, json};
 
 pub async fn upsert_attr_template(
     State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
     extract::Json(input): extract::Json<AttrTemplate>,
 ) -> impl IntoResponse {
     //
     log::debug!("Upserting attr template {input:?} ...");
-    match state.data_mgmt.upsert_attr_template(input).await {
+    match state.data_mgmt.upsert_attr_template(input, user.as_ref()).await {
         Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
-        Err(err) => {
+        Err(err) => match err {
+            AppError::StaleVersion(ref current) => {
+                let current = current.clone();
+                respond_stale_version(err, current)
+            }
             // TODO: We should return a more specific error:
             // e.g. Either not found (if the id is non-zero) or internal server error.
-            respond_internal_server_error(err)
-        }
+            _ => respond_internal_server_error(err),
+        },
     }
 }
 
-pub async fn get_all_attr_templates(State(state): State<ServerState>) -> impl IntoResponse {
+pub async fn get_all_attr_templates(State(state): State<ServerState>, Query(page): Query<PageRequest>) -> impl IntoResponse {
     //
-    match state.data_mgmt.get_all_attr_templates().await {
-        Ok(attr_templs) => {
-            log::debug!("Got {} attr templates.", attr_templs.len());
-            (StatusCode::OK, Json(json!(attr_templs)))
+    match state.data_mgmt.get_attr_templates(page).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} attr templates.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
         }
         Err(err) => respond_not_found(err),
     }
 }
 
-pub async fn delete_attr_template(State(state): State<ServerState>, Path(id): Path<Id>) -> impl IntoResponse {
+pub async fn delete_attr_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
     //
-    match state.data_mgmt.delete_attr_template(id).await {
+    match state.data_mgmt.delete_attr_template(id, user.as_ref()).await {
         Ok(()) => (StatusCode::OK, Json::default()),
-        Err(err) => respond_not_found(err),
+        Err(err) => match err {
+            AppError::DependenciesExist(ref deps) => {
+                let details = json!(deps);
+                respond_conflict(err, details)
+            }
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_attr_template_usages(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_attr_template_usages(id, user.as_ref()).await {
+        Ok(deps) => (StatusCode::OK, Json(json!(deps))),
+        Err(err) => respond_internal_server_error(err),
     }
 }
 
 pub async fn upsert_item_template(
     State(state): State<ServerState>,
+    Query(params): Query<ItemTemplateUpsertParams>,
+    CurrentUser(user): CurrentUser,
     extract::Json(input): extract::Json<ItemTemplate>,
 ) -> impl IntoResponse {
     //
-    log::debug!("Upserting item template {input:?} ...");
-    match state.data_mgmt.upsert_item_template(input).await {
+    log::debug!("Upserting item template {input:?} (orphans: {}) ...", params.orphans);
+    match state
+        .data_mgmt
+        .upsert_item_template(input, params.orphans, user.as_ref())
+        .await
+    {
         Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
         Err(err) => match err {
+            AppError::StaleVersion(ref current) => {
+                let current = current.clone();
+                respond_stale_version(err, current)
+            }
             cogs_shared::app::AppError::NotFound => respond_not_found(err),
             _ => respond_internal_server_error(err),
         },
     }
 }
 
-pub async fn get_all_item_templates(State(state): State<ServerState>) -> impl IntoResponse {
+/// Get (as a dry-run) the plan of changing an item template to the provided shape,
+/// that is how the existing items created from it would be migrated.
+pub async fn plan_item_template_change(
+    State(state): State<ServerState>,
+    extract::Json(input): extract::Json<ItemTemplate>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.plan_item_template_change(&input).await {
+        Ok(plan) => (StatusCode::OK, Json(json!(plan))),
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+pub async fn get_all_item_templates(State(state): State<ServerState>, Query(page): Query<PageRequest>) -> impl IntoResponse {
     //
-    match state.data_mgmt.get_all_item_templates().await {
-        Ok(attr_templs) => {
-            log::debug!("Got {} item templates.", attr_templs.len());
-            (StatusCode::OK, Json(json!(attr_templs)))
+    match state.data_mgmt.get_item_templates(page).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} item templates.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
         }
         Err(err) => respond_not_found(err),
     }
 }
 
-pub async fn delete_item_template(State(state): State<ServerState>, Path(id): Path<Id>) -> impl IntoResponse {
+pub async fn delete_item_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
     //
     log::debug!("Delete item_template w/ id {id} ...",);
-    match state.data_mgmt.delete_item_template(id).await {
+    match state.data_mgmt.delete_item_template(id, user.as_ref()).await {
         Ok(()) => (StatusCode::OK, Json::default()),
-        Err(err) => respond_not_found(err),
+        Err(err) => match err {
+            AppError::DependenciesExist(ref deps) => {
+                let details = json!(deps);
+                respond_conflict(err, details)
+            }
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_item_template_usages(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_item_template_usages(id, user.as_ref()).await {
+        Ok(deps) => (StatusCode::OK, Json(json!(deps))),
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+pub async fn upsert_item(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    extract::Json(input): extract::Json<Item>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Upserting item {input:?} ...");
+    match state.data_mgmt.upsert_item(input, user.as_ref()).await {
+        Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::ValidationFailed(ref violations) => {
+                let details = json!(violations);
+                respond_unprocessable_entity(err, details)
+            }
+            AppError::StaleVersion(ref current) => {
+                let current = current.clone();
+                respond_stale_version(err, current)
+            }
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn import_items(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    extract::Json(input): extract::Json<ItemsImportRequest>,
+) -> impl IntoResponse {
+    //
+    log::debug!(
+        "Importing items into item template '{}' (dry run: {}) ...",
+        input.tmpl_id,
+        input.dry_run
+    );
+    match state.data_mgmt.import_items(input, user.as_ref()).await {
+        Ok(report) => (StatusCode::OK, Json(json!(report))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn export_items(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+    Query(params): Query<ItemsExportParams>,
+) -> Response {
+    //
+    log::debug!("Exporting the items of item template '{id}' as {} ...", params.format);
+    let format = params.format;
+    match state.data_mgmt.export_items(id, params, user.as_ref()).await {
+        Ok(chunks) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"items.{}\"", format.extension()),
+                ),
+            ],
+            Body::from_stream(chunks),
+        )
+            .into_response(),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        }
+        .into_response(),
+    }
+}
+
+pub async fn get_all_items(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<ItemsParams>,
+    Query(page): Query<PageRequest>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_items(params, page, user.as_ref()).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} items.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
+        }
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_item(State(state): State<ServerState>, CurrentUser(user): CurrentUser, Path(id): Path<Id>) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_item(id, user.as_ref()).await {
+        Ok(item) => (StatusCode::OK, Json(json!(item))),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_item_graph(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+    Query(params): Query<GraphParams>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_item_graph(id, &params, user.as_ref()).await {
+        Ok(graph) => (StatusCode::OK, Json(json!(graph))),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn delete_item(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Delete item w/ id {id} ...",);
+    match state.data_mgmt.delete_item(id, user.as_ref()).await {
+        Ok(()) => (StatusCode::OK, Json::default()),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn create_item_link(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+    extract::Json(input): extract::Json<ItemLink>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Creating item link {input:?} from item w/ id {id} ...");
+    match state.data_mgmt.create_item_link(id, input, user.as_ref()).await {
+        Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) | AppError::AlreadyExists(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_item_links(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_item_links(id, user.as_ref()).await {
+        Ok(links) => {
+            log::debug!("Got {} item links.", links.len());
+            (StatusCode::OK, Json(json!(links)))
+        }
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn delete_item_link(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path((id, link_id)): Path<(Id, Id)>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Delete item link w/ id {link_id} of item w/ id {id} ...",);
+    match state.data_mgmt.delete_item_link(id, link_id, user.as_ref()).await {
+        Ok(()) => (StatusCode::OK, Json::default()),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
     }
 }
 
 pub async fn upsert_access_level(
     State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
     extract::Json(input): extract::Json<AccessLevel>,
 ) -> impl IntoResponse {
     //
     log::debug!("Upserting access level {input:?} ...");
-    match state.data_mgmt.upsert_access_level(input).await {
+    match state.data_mgmt.upsert_access_level(input, user.as_ref()).await {
         Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
         Err(err) => match err {
             AppError::ReadOnly(_) => respond_forbidden(err),
+            AppError::StaleVersion(ref current) => {
+                let current = current.clone();
+                respond_stale_version(err, current)
+            }
             AppError::NotFound => respond_not_found(err),
             _ => respond_internal_server_error(err),
         },
     }
 }
 
-pub async fn get_all_access_levels(State(state): State<ServerState>) -> impl IntoResponse {
+pub async fn get_all_access_levels(State(state): State<ServerState>, Query(page): Query<PageRequest>) -> impl IntoResponse {
     //
-    match state.data_mgmt.get_all_access_levels().await {
-        Ok(access_levels) => {
-            log::debug!("Got {} access levels.", access_levels.len());
-            (StatusCode::OK, Json(json!(access_levels)))
+    match state.data_mgmt.get_access_levels(page).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} access levels.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
         }
         Err(err) => match err {
             AppError::NotFound => respond_not_found(err),
@@ -114,15 +391,262 @@ pub async fn get_all_access_levels(State(state): State<ServerState>) -> impl Int
     }
 }
 
-pub async fn delete_access_level(State(state): State<ServerState>, Path(id): Path<Id>) -> impl IntoResponse {
+pub async fn delete_access_level(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
     //
     log::debug!("Delete access_level w/ id {id} ...",);
-    match state.data_mgmt.delete_access_level(id).await {
+    match state.data_mgmt.delete_access_level(id, user.as_ref()).await {
         Ok(()) => (StatusCode::OK, Json::default()),
         Err(err) => match err {
+            AppError::DependenciesExist(ref deps) => {
+                let details = json!(deps);
+                respond_conflict(err, details)
+            }
+            AppError::ReadOnly(_) => respond_forbidden(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn export_bundle(State(state): State<ServerState>, CurrentUser(user): CurrentUser) -> Response {
+    //
+    match state.data_mgmt.export_bundle(user.as_ref()).await {
+        Ok(bundle) => {
+            log::debug!(
+                "Exported a bundle of {} item templates and {} items.",
+                bundle.item_templates.len(),
+                bundle.items.len()
+            );
+            (
+                StatusCode::OK,
+                [(header::CONTENT_DISPOSITION, "attachment; filename=\"cogs-bundle.json\"")],
+                Json(json!(bundle)),
+            )
+                .into_response()
+        }
+        Err(err) => respond_internal_server_error(err).into_response(),
+    }
+}
+
+pub async fn import_bundle(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    extract::Json(input): extract::Json<BundleImportRequest>,
+) -> impl IntoResponse {
+    //
+    log::debug!(
+        "Importing a bundle of {} item templates and {} items (ids: {}, clashes: {}) ...",
+        input.bundle.item_templates.len(),
+        input.bundle.items.len(),
+        input.ids,
+        input.clashes
+    );
+    match state.data_mgmt.import_bundle(input, user.as_ref()).await {
+        Ok(report) => (StatusCode::OK, Json(json!(report))),
+        Err(err) => match err {
+            AppError::ValidationFailed(ref violations) => {
+                let details = json!(violations);
+                respond_unprocessable_entity(err, details)
+            }
+            AppError::InvalidInput(_) | AppError::AlreadyExists(_) => respond_bad_request(err),
+            AppError::StaleVersion(_) => respond_stale_version(err, Value::Null),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn get_item_revisions(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    get_revisions(&state, Kind::Item, id, user.as_ref()).await
+}
+
+pub async fn get_item_template_revisions(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    get_revisions(&state, Kind::ItemTemplate, id, user.as_ref()).await
+}
+
+pub async fn get_attr_template_revisions(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    get_revisions(&state, Kind::AttributeTemplate, id, user.as_ref()).await
+}
+
+pub async fn get_access_level_revisions(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    get_revisions(&state, Kind::AccessLevel, id, user.as_ref()).await
+}
+
+async fn get_revisions(state: &ServerState, kind: Kind, id: Id, user: Option<&UserAccount>) -> (StatusCode, Json<Value>) {
+    //
+    match state.data_mgmt.get_revisions(kind, id, user).await {
+        Ok(revisions) => {
+            log::debug!("Got {} revisions.", revisions.len());
+            (StatusCode::OK, Json(json!(revisions)))
+        }
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Compare two revisions (of the same element) field by field, getting the changed fields.
+pub async fn diff_revisions(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<RevisionDiffParams>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.diff_revisions(params.from, params.to, user.as_ref()).await {
+        Ok(changes) => (StatusCode::OK, Json(json!(changes))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Restore an element as it was in a revision, responding with its kind and id.
+pub async fn restore_revision(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Restoring revision w/ id {id} ...");
+    match state.data_mgmt.restore_revision(id, user.as_ref()).await {
+        Ok((kind, id)) => (StatusCode::OK, Json(json!({ "kind": kind, "id": id }))),
+        Err(err) => match err {
+            AppError::ValidationFailed(ref violations) => {
+                let details = json!(violations);
+                respond_unprocessable_entity(err, details)
+            }
+            AppError::InvalidInput(_) | AppError::AlreadyExists(_) => respond_bad_request(err),
             AppError::ReadOnly(_) => respond_forbidden(err),
             AppError::NotFound => respond_not_found(err),
             _ => respond_internal_server_error(err),
         },
     }
 }
+
+pub async fn search(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Query(params): Query<SearchParams>,
+) -> impl IntoResponse {
+    //
+    log::debug!("Searching for '{}' ...", params.q);
+    match state.data_mgmt.search(&params.q, user.as_ref()).await {
+        Ok(groups) => (StatusCode::OK, Json(json!(groups))),
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+pub async fn get_trash(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Query(page): Query<PageRequest>,
+) -> impl IntoResponse {
+    //
+    match state.data_mgmt.get_trash(page, user.as_ref()).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} trash entries.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
+        }
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+pub async fn restore_item(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    restore_from_trash(&state, Kind::Item, id, user.as_ref()).await
+}
+
+pub async fn restore_item_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    restore_from_trash(&state, Kind::ItemTemplate, id, user.as_ref()).await
+}
+
+pub async fn restore_attr_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    restore_from_trash(&state, Kind::AttributeTemplate, id, user.as_ref()).await
+}
+
+async fn restore_from_trash(state: &ServerState, kind: Kind, id: Id, user: Option<&UserAccount>) -> (StatusCode, Json<Value>) {
+    //
+    log::debug!("Restoring {kind:?} w/ id {id} from trash ...");
+    match state.data_mgmt.restore_from_trash(kind, id.clone(), user).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn purge_item(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    purge(&state, Kind::Item, id, user.as_ref()).await
+}
+
+pub async fn purge_item_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    purge(&state, Kind::ItemTemplate, id, user.as_ref()).await
+}
+
+pub async fn purge_attr_template(
+    State(state): State<ServerState>,
+    CurrentUser(user): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    purge(&state, Kind::AttributeTemplate, id, user.as_ref()).await
+}
+
+async fn purge(state: &ServerState, kind: Kind, id: Id, user: Option<&UserAccount>) -> (StatusCode, Json<Value>) {
+    //
+    log::debug!("Purging {kind:?} w/ id {id} ...");
+    match state.data_mgmt.purge(kind, id.clone(), user).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::DependenciesExist(ref deps) => {
+                let details = json!(deps);
+                respond_conflict(err, details)
+            }
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
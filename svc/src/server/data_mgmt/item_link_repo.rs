@@ -0,0 +1,269 @@
+use crate::{
+    server::item_visible_sql,
+    utils::{new_app_error_from_sqlx, uuid_from},
+};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::{Id, meta::ItemLink},
+    dtos::{GraphDirection, GraphEdge, GraphNode, ItemGraph},
+};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+#[derive(Clone, Debug)]
+pub struct ItemLinkRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl ItemLinkRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Insert an item link. The `source_item_tmpl_id` must be provided if the source item has a template.
+    pub async fn insert(&self, link: &ItemLink, source_item_tmpl_id: Option<&Id>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO item_links (id, name, source_item_tmpl_id, source_item_id, target_item_id)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(uuid_from(&link.id))
+        .bind(&link.name)
+        .bind(source_item_tmpl_id.map(uuid_from))
+        .bind(uuid_from(&link.source_item_id))
+        .bind(uuid_from(&link.target_item_id))
+        .execute(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some(link.name.clone())))?;
+
+        Ok(())
+    }
+
+    /// Replace the outgoing links of the provided items with the provided ones, as part of an existing transaction.
+    /// Each link must have the (optional) template id of its source item.
+    pub(crate) async fn replace_in_txn(
+        txn: &mut Transaction<'_, Postgres>,
+        source_item_ids: &[Id],
+        links: &[(ItemLink, Option<Id>)],
+    ) -> AppResult<()> {
+        let source_item_ids: Vec<Uuid> = source_item_ids.iter().map(uuid_from).collect();
+        sqlx::query(r#"DELETE FROM item_links WHERE source_item_id = ANY($1)"#)
+            .bind(&source_item_ids)
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item links".to_string())))?;
+
+        for (link, source_item_tmpl_id) in links {
+            sqlx::query(
+                r#"
+                INSERT INTO item_links (id, name, source_item_tmpl_id, source_item_id, target_item_id)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(uuid_from(&link.id))
+            .bind(&link.name)
+            .bind(source_item_tmpl_id.as_ref().map(uuid_from))
+            .bind(uuid_from(&link.source_item_id))
+            .bind(uuid_from(&link.target_item_id))
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(link.name.clone())))?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the links between (not deleted) items that are visible with the provided clearance,
+    /// ordered by their source item and name.
+    pub async fn get_all(&self, clearance: i32) -> AppResult<Vec<ItemLink>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT l.id, l.name, l.source_item_id, l.target_item_id,
+                   COALESCE(it.name, '') AS target_item_kind,
+                   COALESCE(t.listing_attr_value, '') AS target_item_listing_attr_value
+            FROM item_links l
+            INNER JOIN items s ON s.id = l.source_item_id
+            INNER JOIN items t ON t.id = l.target_item_id
+            LEFT JOIN item_templates it ON it.id = t.tmpl_id
+            WHERE s.deleted_at IS NULL AND t.deleted_at IS NULL AND {} AND {}
+            ORDER BY l.source_item_id ASC, l.name ASC, l.id ASC
+            "#,
+            item_visible_sql("s", 1),
+            item_visible_sql("t", 1)
+        ))
+        .bind(clearance)
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item links".to_string())))?;
+
+        Ok(rows.iter().map(item_link_from_row).collect())
+    }
+
+    /// Get the outgoing links of an item to the items that are visible with the provided clearance,
+    /// ordered by their name and target's listing attribute value.
+    pub async fn get_by_source(&self, source_item_id: &Id, clearance: i32) -> AppResult<Vec<ItemLink>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT l.id, l.name, l.source_item_id, l.target_item_id,
+                   COALESCE(it.name, '') AS target_item_kind,
+                   COALESCE(t.listing_attr_value, '') AS target_item_listing_attr_value
+            FROM item_links l
+            INNER JOIN items t ON t.id = l.target_item_id
+            LEFT JOIN item_templates it ON it.id = t.tmpl_id
+            WHERE l.source_item_id = $1 AND t.deleted_at IS NULL AND {}
+            ORDER BY l.name ASC, target_item_listing_attr_value ASC
+            "#,
+            item_visible_sql("t", 2)
+        ))
+        .bind(uuid_from(source_item_id))
+        .bind(clearance)
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item links".to_string())))?;
+
+        Ok(rows.iter().map(item_link_from_row).collect())
+    }
+
+    /// Get the graph (neighborhood) of an item, by following (in the provided direction) its links
+    /// and the ones of the reached items, up to the provided depth. Only the links with the provided names
+    /// are followed, unless none is provided. The items are walked breadth first, level by level, so that
+    /// each one is included once (at its least depth), and at most `max_nodes` items (the nearest ones) are included.
+    /// The items that are not visible with the provided clearance are neither included, nor walked through.
+    pub async fn get_graph(
+        &self,
+        item_id: &Id,
+        depth: u32,
+        link_names: &[String],
+        direction: GraphDirection,
+        max_nodes: u32,
+        clearance: i32,
+    ) -> AppResult<ItemGraph> {
+        // The walk goes level by level: each level is the (not yet reached) neighbors of the previous one,
+        // thus each item is reached once, at its least depth. It stops once `max_nodes` items are reached,
+        // keeping the nearest ones (and, among them, the first ones by their listing attribute value).
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH RECURSIVE edges (from_id, to_id) AS (
+                SELECT source_item_id, target_item_id FROM item_links
+                WHERE $3 <> 'in' AND (cardinality($2::VARCHAR[]) = 0 OR name = ANY($2))
+                UNION ALL
+                SELECT target_item_id, source_item_id FROM item_links
+                WHERE $3 <> 'out' AND (cardinality($2::VARCHAR[]) = 0 OR name = ANY($2))
+            ),
+            walk (depth, level, reached) AS (
+                SELECT 0, ARRAY[i.id], ARRAY[i.id] FROM items i WHERE i.id = $1 AND i.deleted_at IS NULL AND {visible}
+                UNION ALL
+                SELECT w.depth + 1, n.ids, w.reached || n.ids
+                FROM walk w
+                CROSS JOIN LATERAL (
+                    SELECT ARRAY(
+                        SELECT DISTINCT e.to_id
+                        FROM edges e
+                        INNER JOIN items i ON i.id = e.to_id AND i.deleted_at IS NULL AND {visible}
+                        WHERE e.from_id = ANY(w.level) AND NOT e.to_id = ANY(w.reached)
+                    ) AS ids
+                ) n
+                WHERE w.depth < $4 AND cardinality(w.reached) <= $5 AND cardinality(n.ids) > 0
+            )
+            SELECT i.id, i.tmpl_id, COALESCE(it.name, '') AS tmpl_name,
+                   COALESCE(i.listing_attr_value, '') AS listing_attr_value, w.depth
+            FROM walk w
+            CROSS JOIN LATERAL unnest(w.level) AS l (item_id)
+            INNER JOIN items i ON i.id = l.item_id
+            LEFT JOIN item_templates it ON it.id = i.tmpl_id
+            ORDER BY w.depth ASC, listing_attr_value ASC, i.id ASC
+            LIMIT $5 + 1
+            "#,
+            visible = item_visible_sql("i", 6)
+        ))
+        .bind(uuid_from(item_id))
+        .bind(link_names)
+        .bind(direction.to_string())
+        .bind(depth as i32)
+        .bind(i64::from(max_nodes))
+        .bind(clearance)
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the item graph".to_string())))?;
+
+        if rows.is_empty() {
+            return Err(AppError::NotFound);
+        }
+        let truncated = rows.len() > max_nodes as usize;
+        let nodes: Vec<GraphNode> = rows
+            .iter()
+            .take(max_nodes as usize)
+            .map(|row| GraphNode {
+                id: Id::from(row.get::<Uuid, _>("id").to_string()),
+                tmpl_id: row.get::<Option<Uuid>, _>("tmpl_id").map(|id| Id::from(id.to_string())),
+                tmpl_name: row.get("tmpl_name"),
+                listing_attr_value: row.get("listing_attr_value"),
+                depth: row.get::<i32, _>("depth") as u32,
+            })
+            .collect();
+
+        // The edges are the (followed kind of) links between the nodes, in the followed direction.
+        let node_ids: Vec<Uuid> = nodes.iter().map(|n| uuid_from(&n.id)).collect();
+        let depths: HashMap<&Id, u32> = nodes.iter().map(|n| (&n.id, n.depth)).collect();
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, source_item_id, target_item_id FROM item_links
+            WHERE source_item_id = ANY($1) AND target_item_id = ANY($1)
+                  AND (cardinality($2::VARCHAR[]) = 0 OR name = ANY($2))
+            ORDER BY name ASC, id ASC
+            "#,
+        )
+        .bind(&node_ids)
+        .bind(link_names)
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the item graph links".to_string())))?;
+
+        let edges = rows
+            .iter()
+            .map(|row| GraphEdge {
+                id: Id::from(row.get::<Uuid, _>("id").to_string()),
+                name: row.get("name"),
+                source_item_id: Id::from(row.get::<Uuid, _>("source_item_id").to_string()),
+                target_item_id: Id::from(row.get::<Uuid, _>("target_item_id").to_string()),
+            })
+            .filter(
+                |edge| match (depths.get(&edge.source_item_id), depths.get(&edge.target_item_id)) {
+                    (Some(source_depth), Some(target_depth)) => direction.follows(*source_depth, *target_depth),
+                    _ => false,
+                },
+            )
+            .collect();
+
+        Ok(ItemGraph { nodes, edges, truncated })
+    }
+
+    /// Delete an outgoing link of an item.
+    pub async fn delete(&self, source_item_id: &Id, id: &Id) -> AppResult<()> {
+        let result = sqlx::query(r#"DELETE FROM item_links WHERE id = $1 AND source_item_id = $2"#)
+            .bind(uuid_from(id))
+            .bind(uuid_from(source_item_id))
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item link".to_string())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+fn item_link_from_row(row: &PgRow) -> ItemLink {
+    ItemLink {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        source_item_id: Id::from(row.get::<Uuid, _>("source_item_id").to_string()),
+        target_item_id: Id::from(row.get::<Uuid, _>("target_item_id").to_string()),
+        target_item_kind: row.get("target_item_kind"),
+        target_item_listing_attr_value: row.get("target_item_listing_attr_value"),
+    }
+}
@@ -0,0 +1,524 @@
+use super::{NewRevision, RevisionRepo};
+use crate::utils::{new_app_error_from_sqlx, uuid_from};
+use chrono::{NaiveDate, NaiveDateTime};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::ItemQuery,
+        model::{
+            Id,
+            meta::{
+                AttributeValueType, BooleanAttribute, DateAttribute, DateTimeAttribute, Item, NumericAttribute, TextAttribute,
+            },
+        },
+    },
+    dtos::{Page, PageRequest},
+};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use uuid::Uuid;
+
+/// The fields that the items can be sorted by, mapped to their columns.
+const ITEMS_SORT_COLUMNS: &[(&str, &str)] = &[("kind", "kind"), ("name", "i.listing_attr_value")];
+
+/// The name that is reported (such as in the usages of a template) instead of the one of an item that the user is not cleared for.
+const HIDDEN_ITEM_NAME: &str = "(hidden)";
+
+#[derive(Clone, Debug)]
+pub struct ItemRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl ItemRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Insert or update an item (+ replace all its attributes), along with its revision.
+    pub async fn upsert(&self, item: &Item, revision: &NewRevision) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+        Self::upsert_in_txn(&mut txn, item).await?;
+        RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Insert or update items (+ replace all their attributes), along with their revisions, either all of them or none.
+    pub async fn upsert_all(&self, items: &[Item], revisions: &[NewRevision]) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+        for item in items {
+            Self::upsert_in_txn(&mut txn, item).await?;
+        }
+        for revision in revisions {
+            RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        }
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Insert or update an item (+ replace all its attributes) as part of an existing transaction.
+    /// An update is refused with `AppError::StaleVersion` (without the current copy) if the item
+    /// does not have the provided version anymore.
+    pub(crate) async fn upsert_in_txn(txn: &mut Transaction<'_, Postgres>, item: &Item) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO items (id, tmpl_id, listing_attr_tmpl_id, listing_attr_name, listing_attr_value, access_level_id)
+            VALUES ($1, $2, $3, $4, $5, $7)
+            ON CONFLICT (id) DO UPDATE
+                SET tmpl_id = EXCLUDED.tmpl_id,
+                    listing_attr_tmpl_id = EXCLUDED.listing_attr_tmpl_id,
+                    listing_attr_name = EXCLUDED.listing_attr_name,
+                    listing_attr_value = EXCLUDED.listing_attr_value,
+                    access_level_id = EXCLUDED.access_level_id,
+                    version = items.version + 1
+                WHERE items.version = $6
+            "#,
+        )
+        .bind(uuid_from(&item.id))
+        .bind(item.tmpl_id.as_ref().map(uuid_from))
+        .bind(opt_uuid_from(&item.listing_attr_tmpl_id))
+        .bind(&item.listing_attr_name)
+        .bind(&item.listing_attr_value)
+        .bind(item.version)
+        .bind(item.access_level_id.as_ref().map(uuid_from))
+        .execute(&mut **txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some(item.listing_attr_value.clone())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::StaleVersion(serde_json::Value::Null));
+        }
+
+        Self::delete_attributes(txn, &item.id).await?;
+        Self::insert_attributes(txn, item).await?;
+
+        Ok(())
+    }
+
+    /// Get a page of the (not deleted) items that are visible with the provided clearance, optionally only
+    /// the ones that match the provided filter and that are created from the provided item template.
+    pub async fn get_page(
+        &self,
+        filter: Option<&ItemQuery>,
+        tmpl_id: Option<&Id>,
+        clearance: i32,
+        page: &PageRequest,
+    ) -> AppResult<Page<Item>> {
+        let mut params = vec![clearance.to_string()];
+        let mut conditions = vec!["i.deleted_at IS NULL".to_string(), item_visible_sql("i", 1)];
+        if let Some(filter) = filter {
+            conditions.push(filter.to_sql(&mut params));
+        }
+        if let Some(tmpl_id) = tmpl_id {
+            params.push(tmpl_id.to_string());
+            conditions.push(format!("i.tmpl_id = ${}::uuid", params.len()));
+        }
+        let where_clause = format!("WHERE {}", conditions.join(" AND "));
+
+        let sort_column = page.sort_column(ITEMS_SORT_COLUMNS);
+        let sql = format!(
+            r#"
+            SELECT i.id, i.tmpl_id, i.listing_attr_tmpl_id, i.listing_attr_name, i.listing_attr_value, i.version,
+                   i.access_level_id, COALESCE(it.name, '') AS kind
+            FROM items i
+            LEFT JOIN item_templates it ON it.id = i.tmpl_id
+            {where_clause}
+            ORDER BY {sort_column} {}, kind ASC, i.listing_attr_value ASC, i.id ASC
+            LIMIT ${} OFFSET ${}
+            "#,
+            page.dir.as_sql(),
+            params.len() + 1,
+            params.len() + 2
+        );
+        let mut query = sqlx::query(&sql);
+        for param in &params {
+            query = query.bind(param);
+        }
+        let rows = query
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get items".to_string())))?;
+
+        let count_sql = format!("SELECT COUNT(*) FROM items i {where_clause}");
+        let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+        for param in &params {
+            count_query = count_query.bind(param);
+        }
+        let total = count_query
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to count items".to_string())))?;
+
+        let mut items: Vec<Item> = rows.iter().map(item_from_row).collect();
+        self.load_attributes(&mut items).await?;
+        Ok(Page::new(items, page, total as u64))
+    }
+
+    /// Get the items that are created from an item template, including the deleted ones,
+    /// so that they are also migrated when the item template changes.
+    pub async fn get_by_tmpl_id(&self, tmpl_id: &Id) -> AppResult<Vec<Item>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT i.id, i.tmpl_id, i.listing_attr_tmpl_id, i.listing_attr_name, i.listing_attr_value, i.version,
+                   i.access_level_id, COALESCE(it.name, '') AS kind
+            FROM items i
+            LEFT JOIN item_templates it ON it.id = i.tmpl_id
+            WHERE i.tmpl_id = $1
+            ORDER BY i.listing_attr_value ASC
+            "#,
+        )
+        .bind(uuid_from(tmpl_id))
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get items".to_string())))?;
+
+        let mut items: Vec<Item> = rows.iter().map(item_from_row).collect();
+        self.load_attributes(&mut items).await?;
+        Ok(items)
+    }
+
+    /// Get a (not deleted) item, if it's visible with the provided clearance.
+    /// Otherwise, it's `AppError::NotFound`, so that the hidden items cannot be told apart from the missing ones.
+    pub async fn get_by_id(&self, id: &Id, clearance: i32) -> AppResult<Item> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT i.id, i.tmpl_id, i.listing_attr_tmpl_id, i.listing_attr_name, i.listing_attr_value, i.version,
+                   i.access_level_id, COALESCE(it.name, '') AS kind
+            FROM items i
+            LEFT JOIN item_templates it ON it.id = i.tmpl_id
+            WHERE i.id = $1 AND i.deleted_at IS NULL AND {}
+            "#,
+            item_visible_sql("i", 2)
+        ))
+        .bind(uuid_from(id))
+        .bind(clearance)
+        .fetch_optional(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item".to_string())))?;
+
+        let Some(row) = row else {
+            return Err(AppError::NotFound);
+        };
+
+        let mut items = vec![item_from_row(&row)];
+        self.load_attributes(&mut items).await?;
+        Ok(items.remove(0))
+    }
+
+    /// Check if an item (including a deleted one) exists, but it's not visible with the provided clearance.
+    pub async fn is_hidden(&self, id: &Id, clearance: i32) -> AppResult<bool> {
+        let sql = format!(
+            "SELECT EXISTS(SELECT 1 FROM items i WHERE i.id = $1 AND NOT {})",
+            item_visible_sql("i", 2)
+        );
+        sqlx::query_scalar(&sql)
+            .bind(uuid_from(id))
+            .bind(clearance)
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to check the item access level".to_string())))
+    }
+
+    /// Delete an item for good, including its attributes and the links from and to it.
+    pub async fn purge(&self, id: &Id) -> AppResult<()> {
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        Self::delete_attributes(&mut txn, id).await?;
+
+        sqlx::query(r#"DELETE FROM item_links WHERE source_item_id = $1 OR target_item_id = $1"#)
+            .bind(uuid_from(id))
+            .execute(&mut *txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item links".to_string())))?;
+
+        let result = sqlx::query(r#"DELETE FROM items WHERE id = $1"#)
+            .bind(uuid_from(id))
+            .execute(&mut *txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item".to_string())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_attributes(txn: &mut Transaction<'_, Postgres>, owner_id: &Id) -> AppResult<()> {
+        for table in ATTR_TABLES {
+            sqlx::query(&format!("DELETE FROM {table} WHERE owner_id = $1"))
+                .bind(uuid_from(owner_id))
+                .execute(&mut **txn)
+                .await
+                .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item attributes".to_string())))?;
+        }
+        Ok(())
+    }
+
+    async fn insert_attributes(txn: &mut Transaction<'_, Postgres>, item: &Item) -> AppResult<()> {
+        let show_index = |value_type: AttributeValueType, id: &Id| -> i16 {
+            item.attributes_order
+                .iter()
+                .position(|(vt, oid)| *vt == value_type && oid == id)
+                .map(|pos| pos as i16 + 1)
+                .unwrap_or(i16::MAX)
+        };
+
+        for a in &item.text_attributes {
+            insert_attr_query(
+                INSERT_TEXT_ATTR,
+                item,
+                &a.id,
+                &a.name,
+                &a.tmpl_id,
+                show_index(AttributeValueType::Text, &a.id),
+            )
+            .bind(&a.value)
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(a.name.clone())))?;
+        }
+        for a in &item.numeric_attributes {
+            insert_attr_query(
+                INSERT_NUMERIC_ATTR,
+                item,
+                &a.id,
+                &a.name,
+                &a.tmpl_id,
+                show_index(AttributeValueType::Numeric, &a.id),
+            )
+            .bind(a.value.to_string())
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(a.name.clone())))?;
+        }
+        for a in &item.boolean_attributes {
+            insert_attr_query(
+                INSERT_BOOLEAN_ATTR,
+                item,
+                &a.id,
+                &a.name,
+                &a.tmpl_id,
+                show_index(AttributeValueType::Boolean, &a.id),
+            )
+            .bind(a.value)
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(a.name.clone())))?;
+        }
+        for a in &item.date_attributes {
+            insert_attr_query(
+                INSERT_DATE_ATTR,
+                item,
+                &a.id,
+                &a.name,
+                &a.tmpl_id,
+                show_index(AttributeValueType::Date, &a.id),
+            )
+            .bind(a.value)
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(a.name.clone())))?;
+        }
+        for a in &item.datetime_attributes {
+            insert_attr_query(
+                INSERT_DATETIME_ATTR,
+                item,
+                &a.id,
+                &a.name,
+                &a.tmpl_id,
+                show_index(AttributeValueType::DateTime, &a.id),
+            )
+            .bind(a.value)
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(a.name.clone())))?;
+        }
+        Ok(())
+    }
+
+    /// Load the attributes of the provided items, in their show order.
+    async fn load_attributes(&self, items: &mut [Item]) -> AppResult<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+        let owner_ids: Vec<Uuid> = items.iter().map(|i| uuid_from(&i.id)).collect();
+        let mut by_owner: HashMap<Id, &mut Item> = items.iter_mut().map(|i| (i.id.clone(), i)).collect();
+        // The (show index, value type, id) of each attribute, per owner.
+        let mut orders: HashMap<Id, Vec<(i16, AttributeValueType, Id)>> = HashMap::new();
+
+        for table in ATTR_TABLES {
+            let rows = sqlx::query(&format!(
+                r#"
+                SELECT id, owner_id, tmpl_id, name, value, show_index
+                FROM {table}
+                WHERE owner_id = ANY($1)
+                "#
+            ))
+            .bind(&owner_ids)
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item attributes".to_string())))?;
+
+            for row in rows {
+                let id = Id::from(row.get::<Uuid, _>("id").to_string());
+                let owner_id = Id::from(row.get::<Uuid, _>("owner_id").to_string());
+                let tmpl_id = row.get::<Option<Uuid>, _>("tmpl_id").map(|id| Id::from(id.to_string()));
+                let name: String = row.get("name");
+                let Some(item) = by_owner.get_mut(&owner_id) else {
+                    continue;
+                };
+                let value_type = match table {
+                    "text_attributes" => {
+                        let value: Option<String> = row.get("value");
+                        item.text_attributes.push(TextAttribute::new(
+                            id.clone(),
+                            name,
+                            value.unwrap_or_default(),
+                            tmpl_id,
+                            owner_id.clone(),
+                        ));
+                        AttributeValueType::Text
+                    }
+                    "numeric_attributes" => {
+                        let value: Option<String> = row.get("value");
+                        let value = Decimal::from_str(&value.unwrap_or_default()).unwrap_or_default();
+                        item.numeric_attributes
+                            .push(NumericAttribute::new(id.clone(), name, value, tmpl_id, owner_id.clone()));
+                        AttributeValueType::Numeric
+                    }
+                    "boolean_attributes" => {
+                        let value: Option<bool> = row.get("value");
+                        item.boolean_attributes.push(BooleanAttribute::new(
+                            id.clone(),
+                            name,
+                            value.unwrap_or_default(),
+                            tmpl_id,
+                            owner_id.clone(),
+                        ));
+                        AttributeValueType::Boolean
+                    }
+                    "date_attributes" => {
+                        let value: Option<NaiveDate> = row.get("value");
+                        item.date_attributes.push(DateAttribute::new(
+                            id.clone(),
+                            name,
+                            value.unwrap_or_default(),
+                            tmpl_id,
+                            owner_id.clone(),
+                        ));
+                        AttributeValueType::Date
+                    }
+                    _ => {
+                        let value: Option<NaiveDateTime> = row.get("value");
+                        item.datetime_attributes.push(DateTimeAttribute::new(
+                            id.clone(),
+                            name,
+                            value.unwrap_or_default(),
+                            tmpl_id,
+                            owner_id.clone(),
+                        ));
+                        AttributeValueType::DateTime
+                    }
+                };
+                orders
+                    .entry(owner_id)
+                    .or_default()
+                    .push((row.get::<i16, _>("show_index"), value_type, id));
+            }
+        }
+
+        for (owner_id, mut order) in orders {
+            order.sort_by_key(|(show_index, _, _)| *show_index);
+            if let Some(item) = by_owner.get_mut(&owner_id) {
+                item.attributes_order = order.into_iter().map(|(_, vt, id)| (vt, id)).collect();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The tables that store the attributes of the items, one per attribute value type.
+const ATTR_TABLES: [&str; 5] = [
+    "text_attributes",
+    "numeric_attributes",
+    "boolean_attributes",
+    "date_attributes",
+    "datetime_attributes",
+];
+
+/// Get the insert query of an attribute, having all the binds except the value one.
+fn insert_attr_query<'q>(
+    sql: &'static str,
+    item: &Item,
+    id: &Id,
+    name: &'q str,
+    tmpl_id: &Option<Id>,
+    show_index: i16,
+) -> sqlx::query::Query<'q, Postgres, sqlx::postgres::PgArguments> {
+    sqlx::query(sql)
+        .bind(uuid_from(id))
+        .bind(uuid_from(&item.id))
+        .bind(tmpl_id.as_ref().map(uuid_from))
+        .bind(name)
+        .bind(show_index)
+}
+
+const INSERT_TEXT_ATTR: &str =
+    "INSERT INTO text_attributes (id, owner_id, tmpl_id, name, show_index, value) VALUES ($1, $2, $3, $4, $5, $6)";
+const INSERT_NUMERIC_ATTR: &str =
+    "INSERT INTO numeric_attributes (id, owner_id, tmpl_id, name, show_index, value) VALUES ($1, $2, $3, $4, $5, $6)";
+const INSERT_BOOLEAN_ATTR: &str =
+    "INSERT INTO boolean_attributes (id, owner_id, tmpl_id, name, show_index, value) VALUES ($1, $2, $3, $4, $5, $6)";
+const INSERT_DATE_ATTR: &str =
+    "INSERT INTO date_attributes (id, owner_id, tmpl_id, name, show_index, value) VALUES ($1, $2, $3, $4, $5, $6)";
+const INSERT_DATETIME_ATTR: &str =
+    "INSERT INTO datetime_attributes (id, owner_id, tmpl_id, name, show_index, value) VALUES ($1, $2, $3, $4, $5, $6)";
+
+fn item_from_row(row: &PgRow) -> Item {
+    Item {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        kind: row.get("kind"),
+        listing_attr_tmpl_id: row
+            .get::<Option<Uuid>, _>("listing_attr_tmpl_id")
+            .map(|id| Id::from(id.to_string()))
+            .unwrap_or_default(),
+        listing_attr_name: row.get("listing_attr_name"),
+        listing_attr_value: row.get::<Option<String>, _>("listing_attr_value").unwrap_or_default(),
+        tmpl_id: row.get::<Option<Uuid>, _>("tmpl_id").map(|id| Id::from(id.to_string())),
+        access_level_id: row
+            .get::<Option<Uuid>, _>("access_level_id")
+            .map(|id| Id::from(id.to_string())),
+        version: row.get("version"),
+        ..Default::default()
+    }
+}
+
+/// The SQL condition of an item (of the provided table alias) being visible to a user whose clearance
+/// is bound as the provided (numbered) parameter: it has no access level, or one ranked up to the clearance.
+pub(crate) fn item_visible_sql(alias: &str, clearance_param: usize) -> String {
+    format!(
+        "({alias}.access_level_id IS NULL OR \
+          (SELECT al.rank FROM access_levels al WHERE al.id = {alias}.access_level_id) <= ${clearance_param}::INT)"
+    )
+}
+
+/// The SQL expression of the name (the listing attribute value) of an item (of the provided table alias),
+/// which is redacted if the item is not visible to a user whose clearance is bound as the provided parameter.
+pub(crate) fn item_name_sql(alias: &str, clearance_param: usize) -> String {
+    format!(
+        "CASE WHEN {} THEN COALESCE({alias}.listing_attr_value, '') ELSE '{HIDDEN_ITEM_NAME}' END",
+        item_visible_sql(alias, clearance_param)
+    )
+}
+
+/// Get the uuid of an id, or none if the id is zero (not set).
+fn opt_uuid_from(id: &Id) -> Option<Uuid> {
+    if id.is_zero() { None } else { Some(uuid_from(id)) }
+}
@@ -1,15 +1,22 @@
-use crate::utils::uuid_from;
+use crate::{
+    server::{ItemRepo, NewRevision, RevisionRepo, dependency_from_row, item_name_sql},
+    utils::{new_app_error_from_sqlx, uuid_from},
+};
 use cogs_shared::{
     app::{AppError, AppResult},
     domain::model::{
         Id,
-        meta::{AttrTemplate, ItemTemplate, ItemTemplateLink},
+        meta::{AttrTemplate, Dependency, Item, ItemTemplate, ItemTemplateLink},
     },
+    dtos::{Page, PageRequest},
 };
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
+/// The fields that the item templates can be sorted by, mapped to their columns.
+const ITEM_TEMPLATES_SORT_COLUMNS: &[(&str, &str)] = &[("name", "name")];
+
 #[derive(Clone, Debug)]
 pub struct ItemTemplateRepo {
     dbcp: Arc<PgPool>,
@@ -20,33 +27,64 @@ impl ItemTemplateRepo {
         Self { dbcp }
     }
 
-    /// Insert or update an item template (+ replace attributes and links xrefs).
-    pub async fn upsert(&self, item_tmpl: &ItemTemplate) -> AppResult<()> {
+    /// Insert or update an item template (+ replace attributes xrefs and update the links).
+    /// The provided items (created from this template, and already migrated to its new shape)
+    /// are saved in the same transaction, and their links that are no longer declared are deleted.
+    /// The revisions (of the item template and of the items) are added in the same transaction as well.
+    /// An update is refused with `AppError::StaleVersion` (without the current copy) if the item template
+    /// (or any of the items) does not have the provided version anymore.
+    pub async fn upsert(&self, item_tmpl: &ItemTemplate, items: &[Item], revisions: &[NewRevision]) -> AppResult<()> {
         let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
 
-        sqlx::query!(
+        Self::upsert_in_txn(&mut txn, item_tmpl).await?;
+        for item in items {
+            ItemRepo::upsert_in_txn(&mut txn, item).await?;
+        }
+        for revision in revisions {
+            RevisionRepo::add_in_txn(&mut txn, revision).await?;
+        }
+
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Insert or update an item template (+ replace attributes xrefs and update the links)
+    /// as part of an existing transaction.
+    pub(crate) async fn upsert_in_txn(txn: &mut Transaction<'_, Postgres>, item_tmpl: &ItemTemplate) -> AppResult<()> {
+        let result = sqlx::query!(
             r#"
-            INSERT INTO item_templates (id, name, description, listing_attr_tmpl_id)
-            VALUES ($1, $2, $3, $4)
+            INSERT INTO item_templates (id, name, description, listing_attr_tmpl_id, access_level_id)
+            VALUES ($1, $2, $3, $4, $6)
             ON CONFLICT (id) DO UPDATE
                 SET name = EXCLUDED.name,
                     description = EXCLUDED.description,
-                    listing_attr_tmpl_id = EXCLUDED.listing_attr_tmpl_id
+                    listing_attr_tmpl_id = EXCLUDED.listing_attr_tmpl_id,
+                    access_level_id = EXCLUDED.access_level_id,
+                    version = item_templates.version + 1
+                WHERE item_templates.version = $5
             "#,
             uuid_from(&item_tmpl.id),
             item_tmpl.name,
             item_tmpl.description,
             uuid_from(&item_tmpl.listing_attr.id),
+            item_tmpl.version,
+            item_tmpl.access_level_id.as_ref().map(uuid_from),
         )
-        .execute(&mut *txn)
+        .execute(&mut **txn)
         .await
         .map_err(|e| AppError::from(e.to_string()))?;
 
+        // The transaction is rolled back when dropped.
+        if result.rows_affected() == 0 {
+            return Err(AppError::StaleVersion(serde_json::Value::Null));
+        }
+
         sqlx::query!(
             r#"DELETE FROM item_templates_attr_templates_xref WHERE item_tmpl_id = $1"#,
             uuid_from(&item_tmpl.id),
         )
-        .execute(&mut *txn)
+        .execute(&mut **txn)
         .await
         .map_err(|e| AppError::from(e.to_string()))?;
 
@@ -62,18 +100,32 @@ impl ItemTemplateRepo {
                 uuid_from(&attr.id),
                 (index as i16) + 1,
             )
-            .execute(&mut *txn)
+            .execute(&mut **txn)
             .await
             .map_err(|e| AppError::from(e.to_string()))?;
         }
 
+        // The links that are no longer declared are removed, while the others are updated in place,
+        // since they may be referred by existing item links.
+        let link_names: Vec<String> = item_tmpl.links.iter().map(|l| l.name.clone()).collect();
+        // The item links that refer them are removed first.
         sqlx::query!(
-            r#"DELETE FROM item_template_links WHERE source_item_tmpl_id = $1"#,
+            r#"DELETE FROM item_links WHERE source_item_tmpl_id = $1 AND name <> ALL($2)"#,
             uuid_from(&item_tmpl.id),
+            &link_names,
         )
-        .execute(&mut *txn)
+        .execute(&mut **txn)
         .await
-        .map_err(|e| AppError::from(e.to_string()))?;
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item links".to_string())))?;
+
+        sqlx::query!(
+            r#"DELETE FROM item_template_links WHERE source_item_tmpl_id = $1 AND name <> ALL($2)"#,
+            uuid_from(&item_tmpl.id),
+            &link_names,
+        )
+        .execute(&mut **txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete item template links".to_string())))?;
 
         for (index, link) in item_tmpl.links.iter().enumerate() {
             sqlx::query!(
@@ -82,23 +134,61 @@ impl ItemTemplateRepo {
                     (name, source_item_tmpl_id, target_item_tmpl_id, show_index)
                 VALUES
                     ($1, $2, $3, $4)
+                ON CONFLICT (name, source_item_tmpl_id) DO UPDATE
+                    SET target_item_tmpl_id = EXCLUDED.target_item_tmpl_id,
+                        show_index = EXCLUDED.show_index
                 "#,
                 link.name,
                 uuid_from(&item_tmpl.id),
                 uuid_from(&link.item_template_id),
                 (index as i16) + 1,
             )
-            .execute(&mut *txn)
+            .execute(&mut **txn)
             .await
             .map_err(|e| AppError::from(e.to_string()))?;
         }
 
-        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
-
         Ok(())
     }
 
-    pub async fn get_all(&self) -> AppResult<Vec<ItemTemplate>> {
+    /// Retrieve a page of the item templates (the deleted ones are excluded).
+    pub async fn get_page(&self, page: &PageRequest) -> AppResult<Page<ItemTemplate>> {
+        let sort_column = page.sort_column(ITEM_TEMPLATES_SORT_COLUMNS);
+        let sql = format!(
+            r#"
+            SELECT id
+            FROM item_templates
+            WHERE deleted_at IS NULL
+            ORDER BY {sort_column} {}, id ASC
+            LIMIT $1 OFFSET $2
+            "#,
+            page.dir.as_sql()
+        );
+        let ids: Vec<Uuid> = sqlx::query_scalar(&sql)
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item templates".to_string())))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM item_templates WHERE deleted_at IS NULL")
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to count item templates".to_string())))?;
+
+        // The templates are loaded (with their attributes and links) and kept in the page's order.
+        let mut loaded = self.get_by_ids(&ids).await?;
+        let data = ids
+            .iter()
+            .filter_map(|id| loaded.remove(&Id::from(id.to_string())))
+            .collect::<Vec<_>>();
+
+        Ok(Page::new(data, page, total as u64))
+    }
+
+    /// Retrieve the item templates with the provided ids, mapped by their id.
+    /// The deleted ones are excluded.
+    async fn get_by_ids(&self, ids: &[Uuid]) -> AppResult<HashMap<Id, ItemTemplate>> {
         let attr_rows = sqlx::query_as!(
             ItemTemplateAttrRow,
             r#"
@@ -106,6 +196,8 @@ impl ItemTemplateRepo {
                 it.id                           AS it_id,
                 it.name                         AS it_name,
                 it.description                  AS it_description,
+                it.access_level_id              AS it_access_level_id,
+                it.version                      AS it_version,
 
                 lat.id                          AS lat_id,
                 lat.name                        AS lat_name,
@@ -113,13 +205,15 @@ impl ItemTemplateRepo {
                 lat.value_type                  AS lat_value_type,
                 lat.default_value               AS lat_default_value,
                 lat.required                    AS lat_required,
+                lat.version                     AS lat_version,
 
                 at.id                           AS at_id,
                 at.name                         AS at_name,
                 at.description                  AS at_description,
                 at.value_type                   AS at_value_type,
                 at.default_value                AS at_default_value,
-                at.required                     AS at_required
+                at.required                     AS at_required,
+                at.version                      AS "at_version?"
             FROM item_templates it
             INNER JOIN attr_templates lat
                 ON lat.id = it.listing_attr_tmpl_id
@@ -127,8 +221,10 @@ impl ItemTemplateRepo {
                 ON x.item_tmpl_id = it.id
             LEFT JOIN attr_templates at
                 ON at.id = x.attr_tmpl_id
+            WHERE it.id = ANY($1) AND it.deleted_at IS NULL
             ORDER BY it.name ASC, x.show_index ASC
-            "#
+            "#,
+            ids
         )
         .fetch_all(self.dbcp.as_ref())
         .await
@@ -148,9 +244,12 @@ impl ItemTemplateRepo {
                     value_type: row.lat_value_type.clone().into(),
                     default_value: row.lat_default_value.clone().unwrap_or_default(),
                     is_required: row.lat_required.unwrap_or(false),
+                    version: row.lat_version,
                 },
                 attributes: vec![],
                 links: vec![],
+                access_level_id: row.it_access_level_id.map(|id| Id::from(id.to_string())),
+                version: row.it_version,
             });
 
             if let Some(attr_id) = row.at_id {
@@ -161,6 +260,7 @@ impl ItemTemplateRepo {
                     value_type: row.at_value_type.unwrap_or_default().into(),
                     default_value: row.at_default_value.unwrap_or_default(),
                     is_required: row.at_required.unwrap_or(false),
+                    version: row.at_version.unwrap_or_default(),
                 });
             }
         }
@@ -173,8 +273,10 @@ impl ItemTemplateRepo {
                 l.source_item_tmpl_id,
                 l.target_item_tmpl_id
             FROM item_template_links l
+            WHERE l.source_item_tmpl_id = ANY($1)
             ORDER BY l.source_item_tmpl_id, l.show_index
-            "#
+            "#,
+            ids
         )
         .fetch_all(self.dbcp.as_ref())
         .await
@@ -189,18 +291,51 @@ impl ItemTemplateRepo {
             }
         }
 
-        let mut data: Vec<ItemTemplate> = grouped.into_values().collect();
-        data.sort_by(|a, b| a.name.cmp(&b.name));
-        Ok(data)
+        Ok(grouped.into_values().map(|it| (it.id.clone(), it)).collect())
     }
 
-    pub async fn delete(&self, id: Id) -> AppResult<()> {
+    pub async fn get_by_id(&self, id: &Id) -> AppResult<ItemTemplate> {
+        self.get_by_ids(&[uuid_from(id)]).await?.remove(id).ok_or(AppError::NotFound)
+    }
+
+    /// Delete an item template for good.
+    pub async fn purge(&self, id: Id) -> AppResult<()> {
         sqlx::query!(r#"DELETE FROM item_templates WHERE id = $1"#, uuid_from(&id),)
             .execute(self.dbcp.as_ref())
             .await
-            .map_err(|e| AppError::from(e.to_string()))?;
+            .map_err(|err| new_app_error_from_sqlx(err, None))?;
         Ok(())
     }
+
+    /// Get the elements that use an item template: the items created from it
+    /// and the (other) item templates that have links to it.
+    /// The deleted elements (that are in the Trash) are included only if `include_deleted` is set.
+    /// The names of the items that are not visible with the provided clearance are redacted.
+    pub async fn where_used(&self, id: &Id, include_deleted: bool, clearance: i32) -> AppResult<Vec<Dependency>> {
+        let sql = format!(
+            r#"
+            SELECT 'i' AS kind, i.id, {} AS name, 'template' AS relation
+            FROM items i
+            WHERE i.tmpl_id = $1 AND ($2 OR i.deleted_at IS NULL)
+            UNION ALL
+            SELECT 'it', it.id, it.name, 'link "' || l.name || '" target'
+            FROM item_template_links l
+            INNER JOIN item_templates it ON it.id = l.source_item_tmpl_id
+            WHERE l.target_item_tmpl_id = $1 AND l.source_item_tmpl_id <> $1 AND ($2 OR it.deleted_at IS NULL)
+            ORDER BY 1, 3
+            "#,
+            item_name_sql("i", 3)
+        );
+        let rows = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .bind(include_deleted)
+            .bind(clearance)
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get item template usages".to_string())))?;
+
+        Ok(rows.iter().map(dependency_from_row).collect())
+    }
 }
 
 #[derive(Debug)]
@@ -208,6 +343,8 @@ struct ItemTemplateAttrRow {
     it_id: Uuid,
     it_name: String,
     it_description: Option<String>,
+    it_access_level_id: Option<Uuid>,
+    it_version: i32,
 
     lat_id: Uuid,
     lat_name: String,
@@ -215,6 +352,7 @@ struct ItemTemplateAttrRow {
     lat_value_type: String,
     lat_default_value: Option<String>,
     lat_required: Option<bool>,
+    lat_version: i32,
 
     at_id: Option<Uuid>,
     at_name: Option<String>,
@@ -222,6 +360,7 @@ struct ItemTemplateAttrRow {
     at_value_type: Option<String>,
     at_default_value: Option<String>,
     at_required: Option<bool>,
+    at_version: Option<i32>,
 }
 
 #[derive(Debug)]
@@ -12,3 +12,21 @@ pub use item_tmpl_repo::*;
 
 mod access_level_repo;
 pub use access_level_repo::*;
+
+mod item_repo;
+pub use item_repo::*;
+
+mod item_link_repo;
+pub use item_link_repo::*;
+
+mod search_repo;
+pub use search_repo::*;
+
+mod revision_repo;
+pub use revision_repo::*;
+
+mod trash_repo;
+pub use trash_repo::*;
+
+mod bundle_repo;
+pub use bundle_repo::*;
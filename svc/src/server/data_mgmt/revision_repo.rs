@@ -0,0 +1,142 @@
+use crate::utils::{new_app_error_from_sqlx, uuid_from};
+use chrono::{DateTime, Utc};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::{Id, Revision, UserAccount, meta::Kind},
+};
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A revision that is to be added along with the write of its element (see `RevisionRepo::add_in_txn`).
+#[derive(Clone, Debug)]
+pub struct NewRevision {
+    kind: Kind,
+    element_id: Id,
+    snapshot: serde_json::Value,
+    user_id: Option<Id>,
+    user_name: String,
+}
+
+impl NewRevision {
+    /// A revision of an element, as it is saved by the user.
+    pub fn new(kind: Kind, element_id: &Id, element: &impl Serialize, user: Option<&UserAccount>) -> AppResult<Self> {
+        let snapshot = serde_json::to_value(element).map_err(|err| {
+            log::error!("Failed to serialize the revision snapshot of {kind:?} '{element_id}': {err}");
+            AppError::InternalErr
+        })?;
+        Ok(Self {
+            kind,
+            element_id: element_id.clone(),
+            snapshot,
+            user_id: user.map(|u| u.id.clone()),
+            user_name: user.map(|u| u.username.clone()).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RevisionRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl RevisionRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Append a revision of an element, having the next number in its sequence, as part of the transaction
+    /// that writes the element. It must follow that write, whose row lock (held until the commit) makes
+    /// the concurrent saves of the element take their numbers one after the other.
+    pub(crate) async fn add_in_txn(txn: &mut Transaction<'_, Postgres>, revision: &NewRevision) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO revisions (kind, element_id, number, user_id, user_name, snapshot)
+            SELECT $1, $2, COALESCE(MAX(number), 0) + 1, $3, $4, $5::jsonb
+            FROM revisions
+            WHERE kind = $1 AND element_id = $2
+            "#,
+        )
+        .bind(kind_code(&revision.kind))
+        .bind(uuid_from(&revision.element_id))
+        .bind(revision.user_id.as_ref().map(uuid_from))
+        .bind(&revision.user_name)
+        .bind(revision.snapshot.to_string())
+        .execute(&mut **txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to add revision".to_string())))?;
+
+        Ok(())
+    }
+
+    /// Get the revisions of an element, the newest first.
+    pub async fn get_all(&self, kind: &Kind, element_id: &Id) -> AppResult<Vec<Revision>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, element_id, number, user_id, user_name, created_at, snapshot::text AS snapshot
+            FROM revisions
+            WHERE kind = $1 AND element_id = $2
+            ORDER BY number DESC
+            "#,
+        )
+        .bind(kind_code(kind))
+        .bind(uuid_from(element_id))
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get revisions".to_string())))?;
+
+        rows.iter().map(revision_from_row).collect()
+    }
+
+    pub async fn get_by_id(&self, id: &Id) -> AppResult<Revision> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, kind, element_id, number, user_id, user_name, created_at, snapshot::text AS snapshot
+            FROM revisions
+            WHERE id = $1
+            "#,
+        )
+        .bind(uuid_from(id))
+        .fetch_one(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get revision".to_string())))?;
+
+        revision_from_row(&row)
+    }
+}
+
+fn kind_code(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::Item => "i",
+        Kind::ItemTemplate => "it",
+        Kind::AttributeTemplate => "at",
+        Kind::AccessLevel => "al",
+        Kind::LinkTemplate => "lt",
+        Kind::User => "u",
+    }
+}
+
+fn revision_from_row(row: &PgRow) -> AppResult<Revision> {
+    let snapshot = serde_json::from_str(row.get::<&str, _>("snapshot")).map_err(|err| {
+        log::error!("Failed to parse revision snapshot: {err}");
+        AppError::InternalErr
+    })?;
+    Ok(Revision {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        kind: match row.get::<&str, _>("kind") {
+            "it" => Kind::ItemTemplate,
+            "at" => Kind::AttributeTemplate,
+            "al" => Kind::AccessLevel,
+            "lt" => Kind::LinkTemplate,
+            "u" => Kind::User,
+            _ => Kind::Item,
+        },
+        element_id: Id::from(row.get::<Uuid, _>("element_id").to_string()),
+        number: row.get("number"),
+        user_id: row.get::<Option<Uuid>, _>("user_id").map(|id| Id::from(id.to_string())),
+        user_name: row.get("user_name"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        snapshot,
+    })
+}
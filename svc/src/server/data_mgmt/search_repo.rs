@@ -0,0 +1,117 @@
+use crate::{server::item_visible_sql, utils::new_app_error_from_sqlx};
+use cogs_shared::{
+    app::AppResult,
+    domain::model::{Id, meta::Kind},
+    dtos::{SEARCH_HL_START, SEARCH_HL_STOP, SearchHit},
+};
+use sqlx::{PgPool, Row};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The max number of hits that are returned by a search.
+const SEARCH_MAX_HITS: i64 = 100;
+
+#[derive(Clone, Debug)]
+pub struct SearchRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl SearchRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Full-text search the items (by their listing attribute and text attributes values)
+    /// and the item and attribute templates (by their name and description).
+    /// An element is matched if it has words starting with all the words of the query.
+    /// Each element is returned once, with its best ranked match, in descending rank order.
+    /// The items that are not visible with the provided clearance are left out.
+    pub async fn search(&self, query: &str, clearance: i32) -> AppResult<Vec<SearchHit>> {
+        //
+        let tsquery = to_prefix_tsquery(query);
+        if tsquery.is_empty() {
+            return Ok(vec![]);
+        }
+        let hl_options = format!("StartSel={SEARCH_HL_START}, StopSel={SEARCH_HL_STOP}, MaxWords=20, MinWords=5");
+
+        let rows = sqlx::query(&format!(
+            r#"
+            WITH q AS (SELECT to_tsquery('simple', $1) AS query),
+            docs AS (
+                SELECT 'i' AS kind, i.id, COALESCE(i.listing_attr_value, '') AS name,
+                       to_tsvector('simple', COALESCE(i.listing_attr_value, '')) AS doc,
+                       COALESCE(i.listing_attr_value, '') AS body
+                FROM items i
+                WHERE i.deleted_at IS NULL AND {visible}
+                UNION ALL
+                SELECT 'i', i.id, COALESCE(i.listing_attr_value, ''),
+                       to_tsvector('simple', COALESCE(ta.value, '')),
+                       ta.name || ': ' || COALESCE(ta.value, '')
+                FROM text_attributes ta
+                INNER JOIN items i ON i.id = ta.owner_id
+                WHERE i.deleted_at IS NULL AND {visible}
+                UNION ALL
+                SELECT 'it', it.id, it.name,
+                       to_tsvector('simple', it.name || ' ' || COALESCE(it.description, '')),
+                       it.name || ' ' || COALESCE(it.description, '')
+                FROM item_templates it
+                WHERE it.deleted_at IS NULL
+                UNION ALL
+                SELECT 'at', at.id, at.name,
+                       to_tsvector('simple', at.name || ' ' || COALESCE(at.description, '')),
+                       at.name || ' ' || COALESCE(at.description, '')
+                FROM attr_templates at
+                WHERE at.deleted_at IS NULL
+            ),
+            hits AS (
+                SELECT DISTINCT ON (d.kind, d.id)
+                       d.kind, d.id, d.name,
+                       ts_rank(d.doc, q.query) AS rank,
+                       ts_headline('simple', d.body, q.query, $2) AS headline
+                FROM docs d, q
+                WHERE d.doc @@ q.query
+                ORDER BY d.kind, d.id, rank DESC
+            )
+            SELECT kind, id, name, rank, headline
+            FROM hits
+            ORDER BY rank DESC, name ASC
+            LIMIT $3
+            "#,
+            visible = item_visible_sql("i", 4)
+        ))
+        .bind(&tsquery)
+        .bind(&hl_options)
+        .bind(SEARCH_MAX_HITS)
+        .bind(clearance)
+        .fetch_all(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to search".to_string())))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SearchHit {
+                kind: match row.get::<&str, _>("kind") {
+                    "it" => Kind::ItemTemplate,
+                    "at" => Kind::AttributeTemplate,
+                    _ => Kind::Item,
+                },
+                id: Id::from(row.get::<Uuid, _>("id").to_string()),
+                name: row.get("name"),
+                headline: row.get("headline"),
+                rank: row.get("rank"),
+            })
+            .collect())
+    }
+}
+
+/// Build a `tsquery` that matches the words starting with all the (alphanumeric) words
+/// of the provided query, such as `web:* & srv:*` for `web srv`.
+/// Any other characters are ignored, so that the user input cannot break the query syntax.
+fn to_prefix_tsquery(query: &str) -> String {
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("{}:*", w.to_lowercase()))
+        .collect::<Vec<String>>()
+        .join(" & ")
+}
@@ -0,0 +1,197 @@
+use super::{dependency_from_row, item_visible_sql};
+use crate::utils::{new_app_error_from_sqlx, uuid_from};
+use chrono::{DateTime, Utc};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::{
+        Id, UserAccount,
+        meta::{Dependency, Kind},
+    },
+    dtos::{Page, PageRequest, TrashEntry},
+};
+use sqlx::{PgPool, Row, postgres::PgRow, types::Uuid};
+use std::sync::Arc;
+
+/// The fields that the Trash can be sorted by, mapped to their columns.
+const TRASH_SORT_COLUMNS: &[(&str, &str)] = &[("deleted_at", "t.deleted_at"), ("name", "t.name"), ("kind", "t.kind")];
+
+/// The soft deleted elements (items, item templates and attribute templates), across their tables.
+#[derive(Clone, Debug)]
+pub struct TrashRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl TrashRepo {
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Retrieve a page of the elements that are in the Trash, except the items that are not visible with the provided clearance.
+    pub async fn get_page(&self, page: &PageRequest, clearance: i32) -> AppResult<Page<TrashEntry>> {
+        let sort_column = page.sort_column(TRASH_SORT_COLUMNS);
+        let sql = format!(
+            r#"
+            WITH t AS (
+                SELECT 'i' AS kind, i.id, COALESCE(i.listing_attr_value, '') AS name, i.deleted_at, i.deleted_by
+                FROM items i WHERE i.deleted_at IS NOT NULL AND {}
+                UNION ALL
+                SELECT 'it', id, name, deleted_at, deleted_by
+                FROM item_templates WHERE deleted_at IS NOT NULL
+                UNION ALL
+                SELECT 'at', id, name, deleted_at, deleted_by
+                FROM attr_templates WHERE deleted_at IS NOT NULL
+            )
+            SELECT t.kind, t.id, t.name, t.deleted_at, t.deleted_by, COALESCE(u.username, '') AS deleted_by_name,
+                   COUNT(*) OVER() AS total
+            FROM t
+            LEFT JOIN user_accounts u ON u.id = t.deleted_by
+            ORDER BY {sort_column} {}, t.id ASC
+            LIMIT $1 OFFSET $2
+            "#,
+            item_visible_sql("i", 3),
+            page.dir.as_sql()
+        );
+        let rows = sqlx::query(&sql)
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .bind(clearance)
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the trash".to_string())))?;
+
+        let total = rows.first().map(|row| row.get::<i64, _>("total")).unwrap_or_default();
+        let data = rows.iter().map(trash_entry_from_row).collect::<Vec<_>>();
+
+        Ok(Page::new(data, page, total as u64))
+    }
+
+    /// Move an element to the Trash.
+    pub async fn delete(&self, kind: &Kind, id: &Id, user: Option<&UserAccount>) -> AppResult<()> {
+        let sql = format!(
+            "UPDATE {} SET deleted_at = now(), deleted_by = $2 WHERE id = $1 AND deleted_at IS NULL",
+            table_of(kind)?
+        );
+        let res = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .bind(user.map(|u| uuid_from(&u.id)))
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete".to_string())))?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Take an element out of the Trash.
+    pub async fn restore(&self, kind: &Kind, id: &Id) -> AppResult<()> {
+        let sql = format!(
+            "UPDATE {} SET deleted_at = NULL, deleted_by = NULL WHERE id = $1 AND deleted_at IS NOT NULL",
+            table_of(kind)?
+        );
+        let res = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to restore".to_string())))?;
+
+        if res.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Check if an element is in the Trash.
+    pub async fn is_deleted(&self, kind: &Kind, id: &Id) -> AppResult<bool> {
+        let sql = format!("SELECT deleted_at IS NOT NULL FROM {} WHERE id = $1", table_of(kind)?);
+        let deleted: Option<bool> = sqlx::query_scalar(&sql)
+            .bind(uuid_from(id))
+            .fetch_optional(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to check if deleted".to_string())))?;
+
+        deleted.ok_or(AppError::NotFound)
+    }
+
+    /// Get the elements in the Trash that an element refers to, thus must be restored before it:
+    /// the template and the attribute templates of an item, or the attribute templates
+    /// and the link targets of an item template.
+    pub async fn deleted_references(&self, kind: &Kind, id: &Id) -> AppResult<Vec<Dependency>> {
+        let sql = match kind {
+            Kind::Item => {
+                r#"
+                SELECT 'it' AS kind, it.id, it.name, 'template' AS relation
+                FROM items i
+                INNER JOIN item_templates it ON it.id = i.tmpl_id
+                WHERE i.id = $1 AND it.deleted_at IS NOT NULL
+                UNION
+                SELECT 'at', at.id, at.name, 'attribute'
+                FROM attr_templates at
+                INNER JOIN (
+                    SELECT owner_id, tmpl_id FROM text_attributes
+                    UNION ALL SELECT owner_id, tmpl_id FROM numeric_attributes
+                    UNION ALL SELECT owner_id, tmpl_id FROM boolean_attributes
+                    UNION ALL SELECT owner_id, tmpl_id FROM date_attributes
+                    UNION ALL SELECT owner_id, tmpl_id FROM datetime_attributes
+                    UNION ALL SELECT id, listing_attr_tmpl_id FROM items
+                ) a ON a.tmpl_id = at.id
+                WHERE a.owner_id = $1 AND at.deleted_at IS NOT NULL
+                ORDER BY 1, 3
+                "#
+            }
+            Kind::ItemTemplate => {
+                r#"
+                SELECT 'at' AS kind, at.id, at.name, 'attribute' AS relation
+                FROM attr_templates at
+                INNER JOIN (
+                    SELECT item_tmpl_id, attr_tmpl_id FROM item_templates_attr_templates_xref
+                    UNION ALL SELECT id, listing_attr_tmpl_id FROM item_templates
+                ) x ON x.attr_tmpl_id = at.id
+                WHERE x.item_tmpl_id = $1 AND at.deleted_at IS NOT NULL
+                UNION
+                SELECT 'it', it.id, it.name, 'link "' || l.name || '" target'
+                FROM item_template_links l
+                INNER JOIN item_templates it ON it.id = l.target_item_tmpl_id
+                WHERE l.source_item_tmpl_id = $1 AND it.deleted_at IS NOT NULL
+                ORDER BY 1, 3
+                "#
+            }
+            _ => return Ok(Vec::new()),
+        };
+        let rows = sqlx::query(sql)
+            .bind(uuid_from(id))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get deleted references".to_string())))?;
+
+        Ok(rows.iter().map(dependency_from_row).collect())
+    }
+}
+
+/// Get the table of the elements of a kind, if they can be (soft) deleted.
+fn table_of(kind: &Kind) -> AppResult<&'static str> {
+    match kind {
+        Kind::Item => Ok("items"),
+        Kind::ItemTemplate => Ok("item_templates"),
+        Kind::AttributeTemplate => Ok("attr_templates"),
+        _ => Err(AppError::InvalidInput(format!(
+            "{kind:?} elements cannot be moved to the trash"
+        ))),
+    }
+}
+
+fn trash_entry_from_row(row: &PgRow) -> TrashEntry {
+    TrashEntry {
+        kind: match row.get::<&str, _>("kind") {
+            "it" => Kind::ItemTemplate,
+            "at" => Kind::AttributeTemplate,
+            _ => Kind::Item,
+        },
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        deleted_at: row.get::<DateTime<Utc>, _>("deleted_at"),
+        deleted_by: row.get::<Option<Uuid>, _>("deleted_by").map(|id| Id::from(id.to_string())),
+        deleted_by_name: row.get("deleted_by_name"),
+    }
+}
@@ -1,40 +1,108 @@
 use crate::server::{
-    ServerState, delete_access_level, delete_attr_template, delete_item_template, get_all_access_levels, get_all_attr_templates,
-    get_all_item_templates, init_auth_layer, init_session_layer, login, logout, upsert_access_level, upsert_attr_template,
-    upsert_item_template,
+    ServerState, api_token_auth, change_my_password, create_item_link, create_my_api_token, create_my_download_token,
+    delete_access_level, delete_attr_template, delete_item, delete_item_link, delete_item_template, diff_revisions, disable_user,
+    download_token_auth, enable_user, export_bundle, export_items, force_password_reset, get_access_level_revisions,
+    get_all_access_levels, get_all_attr_templates, get_all_item_templates, get_all_items, get_all_users,
+    get_attr_template_revisions, get_attr_template_usages, get_item, get_item_graph, get_item_links, get_item_revisions,
+    get_item_template_revisions, get_item_template_usages, get_my_api_tokens, get_trash, import_bundle, import_items,
+    init_auth_layer, init_session_layer, login, logout, plan_item_template_change, purge_attr_template, purge_item,
+    purge_item_template, require_permissions, restore_attr_template, restore_item, restore_item_template, restore_revision,
+    revoke_my_api_token, search, unlock_user, update_my_profile, upsert_access_level, upsert_attr_template, upsert_item,
+    upsert_item_template, upsert_user,
 };
 use axum::{
-    Router,
+    Router, middleware,
     routing::{get, post},
 };
+use http::header;
 use sqlx::{Pool, Postgres};
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
 
-pub async fn init_router(pg_pool: &Pool<Postgres>) -> Router<ServerState> {
+pub async fn init_router(pg_pool: &Pool<Postgres>, state: &ServerState) -> Router<ServerState> {
     //
     let auth_layer = init_auth_layer(pg_pool).await;
     let session_layer = init_session_layer(&pg_pool).await;
     let tracing_layer = TraceLayer::new_for_http();
-    let cors_layer = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
+    // The `Retry-After` header (of the throttled logins) is exposed, so that the web client can read it.
+    let cors_layer = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods(Any)
+        .allow_headers(Any)
+        .expose_headers([header::RETRY_AFTER]);
 
-    Router::new()
-        // .route("/api/healthcheck", get(health_check))
-        .route("/api/login", post(login))
-        .route("/api/logout", post(logout))
+    // The data routes require an authenticated session (or API token) with the proper permission.
+    let data_routes = Router::new()
         .route("/api/attribute_templates", post(upsert_attr_template))
         .route("/api/attribute_templates", get(get_all_attr_templates))
+        .route("/api/attribute_templates/{id}/usages", get(get_attr_template_usages))
+        .route("/api/attribute_templates/{id}/revisions", get(get_attr_template_revisions))
         .route("/api/attribute_templates/{id}/delete", post(delete_attr_template))
+        .route("/api/attribute_templates/{id}/restore", post(restore_attr_template))
+        .route("/api/attribute_templates/{id}/purge", post(purge_attr_template))
         .route("/api/item_templates", post(upsert_item_template))
         .route("/api/item_templates", get(get_all_item_templates))
+        .route("/api/item_templates/plan", post(plan_item_template_change))
+        .route("/api/item_templates/{id}/usages", get(get_item_template_usages))
+        .route("/api/item_templates/{id}/revisions", get(get_item_template_revisions))
         .route("/api/item_templates/{id}/delete", post(delete_item_template))
+        .route("/api/item_templates/{id}/restore", post(restore_item_template))
+        .route("/api/item_templates/{id}/purge", post(purge_item_template))
+        .route("/api/items", post(upsert_item))
+        .route("/api/items", get(get_all_items))
+        .route("/api/items/import", post(import_items))
+        .route("/api/items/{id}", get(get_item))
+        .route("/api/items/{id}/delete", post(delete_item))
+        .route("/api/items/{id}/restore", post(restore_item))
+        .route("/api/items/{id}/purge", post(purge_item))
+        .route("/api/items/{id}/revisions", get(get_item_revisions))
+        .route("/api/items/{id}/links", post(create_item_link))
+        .route("/api/items/{id}/links", get(get_item_links))
+        .route("/api/items/{id}/graph", get(get_item_graph))
+        .route("/api/items/{id}/links/{link_id}/delete", post(delete_item_link))
         .route("/api/access_levels", post(upsert_access_level))
         .route("/api/access_levels", get(get_all_access_levels))
         .route("/api/access_levels/{id}/delete", post(delete_access_level))
+        .route("/api/access_levels/{id}/revisions", get(get_access_level_revisions))
+        .route("/api/revisions/diff", get(diff_revisions))
+        .route("/api/revisions/{id}/restore", post(restore_revision))
+        .route("/api/search", get(search))
+        .route("/api/bundle/import", post(import_bundle))
+        .route("/api/trash", get(get_trash))
+        .route("/api/users", post(upsert_user))
+        .route("/api/users", get(get_all_users))
+        .route("/api/users/{id}/disable", post(disable_user))
+        .route("/api/users/{id}/enable", post(enable_user))
+        .route("/api/users/{id}/force_password_reset", post(force_password_reset))
+        .route("/api/users/{id}/unlock", post(unlock_user))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_permissions));
+
+    // The exports are downloaded by the browser, thus they may be authenticated by a download token instead.
+    let export_routes = Router::new()
+        .route("/api/item_templates/{id}/items/export", get(export_items))
+        .route("/api/bundle", get(export_bundle))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_permissions))
+        .route_layer(middleware::from_fn_with_state(state.clone(), download_token_auth));
+
+    Router::new()
+        // .route("/api/healthcheck", get(health_check))
+        .route("/api/login", post(login))
+        .route("/api/logout", post(logout))
+        // The account of the user that is logged in needs no permission, just the session.
+        .route("/api/account/profile", post(update_my_profile))
+        .route("/api/account/password", post(change_my_password))
+        .route("/api/account/api_tokens", get(get_my_api_tokens))
+        .route("/api/account/api_tokens", post(create_my_api_token))
+        .route("/api/account/api_tokens/{id}/revoke", post(revoke_my_api_token))
+        .route("/api/account/download_token", post(create_my_download_token))
+        .merge(data_routes)
+        .merge(export_routes)
         .layer(auth_layer)
         .layer(session_layer)
+        // An API token is taken (out of the `Authorization` header) before the session layer sees it as a session id.
+        .layer(middleware::from_fn_with_state(state.clone(), api_token_auth))
         .layer(tracing_layer)
         .layer(cors_layer)
 }
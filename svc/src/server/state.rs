@@ -1,4 +1,7 @@
-use crate::server::{AccessLevelRepo, AttrTemplateRepo, DataMgmt, ItemTemplateRepo, UserAccountsRepo, UserMgmt};
+use crate::server::{
+    AccessLevelRepo, ApiTokensRepo, AttrTemplateRepo, BundleRepo, DataMgmt, ItemLinkRepo, ItemRepo, ItemTemplateRepo,
+    RevisionRepo, SearchRepo, TrashRepo, UserAccountsRepo, UserMgmt,
+};
 use axum::extract::{FromRef, FromRequestParts};
 use http::{StatusCode, request::Parts};
 use sqlx::PgPool;
@@ -13,12 +16,21 @@ pub struct ServerState {
 impl ServerState {
     pub fn new(db_pool: Arc<PgPool>) -> Self {
         //
-        let user_mgmt = Arc::new(UserMgmt::new(Arc::new(UserAccountsRepo::new(db_pool.clone()))));
+        let user_mgmt = Arc::new(UserMgmt::new(
+            Arc::new(UserAccountsRepo::new(db_pool.clone())),
+            Arc::new(ApiTokensRepo::new(db_pool.clone())),
+        ));
 
         let data_mgmt = Arc::new(DataMgmt::new(
             Arc::new(AttrTemplateRepo::new(db_pool.clone())),
             Arc::new(ItemTemplateRepo::new(db_pool.clone())),
             Arc::new(AccessLevelRepo::new(db_pool.clone())),
+            Arc::new(ItemRepo::new(db_pool.clone())),
+            Arc::new(ItemLinkRepo::new(db_pool.clone())),
+            Arc::new(SearchRepo::new(db_pool.clone())),
+            Arc::new(RevisionRepo::new(db_pool.clone())),
+            Arc::new(TrashRepo::new(db_pool.clone())),
+            Arc::new(BundleRepo::new(db_pool.clone())),
         ));
 
         Self { user_mgmt, data_mgmt }
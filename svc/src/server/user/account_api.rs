@@ -0,0 +1,143 @@
+use crate::server::{
+    AuthUserAccount, SESSION_CURRENT_USER_KEY, ServerState, SessionUser, respond_bad_request, respond_internal_server_error,
+    respond_not_found, respond_too_many_requests, respond_unauthorized,
+};
+use axum::{
+    Json,
+    extract::{self, ConnectInfo, Path, State},
+    response::IntoResponse,
+};
+use axum_session::Session;
+use axum_session_sqlx::SessionPgPool;
+use cogs_shared::{
+    app::AppError,
+    domain::model::Id,
+    dtos::{ApiTokenCreateRequest, DownloadToken, PasswordChangeRequest, ProfileUpdateRequest},
+};
+use http::StatusCode;
+use serde_json::json;
+use std::net::SocketAddr;
+
+/// Update the profile (name, email and bio) of the user that is logged in, responding with its account as saved.
+pub async fn update_my_profile(
+    State(state): State<ServerState>,
+    session: Session<SessionPgPool>,
+    SessionUser(user): SessionUser,
+    extract::Json(input): extract::Json<ProfileUpdateRequest>,
+) -> impl IntoResponse {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    log::debug!("Updating the profile of user w/ id {} ...", user.id);
+    match state.user_mgmt.update_profile(&user.id, input).await {
+        Ok(account) => {
+            // The session keeps the user's account, thus it gets the changes as well.
+            session.set(SESSION_CURRENT_USER_KEY, AuthUserAccount::from(account.clone()));
+            (StatusCode::OK, Json(json!(account)))
+        }
+        Err(err) => match err {
+            AppError::InvalidInput(_) | AppError::AlreadyExists(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Change the password of the user that is logged in, given its current one.
+pub async fn change_my_password(
+    State(state): State<ServerState>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    SessionUser(user): SessionUser,
+    extract::Json(input): extract::Json<PasswordChangeRequest>,
+) -> impl IntoResponse {
+    //
+    // The request is not logged, since it includes the passwords.
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    log::debug!("Changing the password of user w/ id {} ...", user.id);
+    let PasswordChangeRequest {
+        current_password,
+        new_password,
+    } = input;
+    match state
+        .user_mgmt
+        .update_password(&user.id, current_password, new_password, Some(client.ip()))
+        .await
+    {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": user.id }))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::LoginThrottled(_) => respond_too_many_requests(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Get the API tokens of the user that is logged in (without their secrets).
+/// Like the rest of the account, the tokens are managed within a session only, not by using a token.
+pub async fn get_my_api_tokens(State(state): State<ServerState>, SessionUser(user): SessionUser) -> impl IntoResponse {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    match state.user_mgmt.get_api_tokens(&user.id).await {
+        Ok(tokens) => (StatusCode::OK, Json(json!(tokens))),
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+/// Create an API token for the user that is logged in, responding with it along with its secret.
+/// The secret is not kept, thus this is the only time that it is provided.
+pub async fn create_my_api_token(
+    State(state): State<ServerState>,
+    SessionUser(user): SessionUser,
+    extract::Json(input): extract::Json<ApiTokenCreateRequest>,
+) -> impl IntoResponse {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    log::debug!("Creating API token {input:?} for user w/ id {} ...", user.id);
+    match state.user_mgmt.create_api_token(&user.id, input).await {
+        Ok(created) => (StatusCode::OK, Json(json!(created))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Revoke an API token of the user that is logged in.
+pub async fn revoke_my_api_token(
+    State(state): State<ServerState>,
+    SessionUser(user): SessionUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    log::debug!("Revoking API token w/ id {id} of user w/ id {} ...", user.id);
+    match state.user_mgmt.revoke_api_token(&user.id, &id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+/// Issue a download token for the user that is logged in, to authenticate the download of an export
+/// that is opened in the browser. It can be used once, shortly.
+pub async fn create_my_download_token(State(state): State<ServerState>, SessionUser(user): SessionUser) -> impl IntoResponse {
+    //
+    let Some(user) = user else {
+        return respond_unauthorized(AppError::Unauthorized("login required".into()));
+    };
+    let token = state.user_mgmt.issue_download_token(&user.id);
+    (StatusCode::OK, Json(json!(DownloadToken { token })))
+}
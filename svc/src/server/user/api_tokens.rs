@@ -0,0 +1,35 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use cogs_shared::domain::model::API_TOKEN_PREFIX;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// The number of random bytes of an API token's secret.
+const API_TOKEN_SECRET_BYTES: usize = 32;
+/// The number of characters (after the prefix) of an API token's secret that are kept as its hint.
+const API_TOKEN_HINT_LEN: usize = 6;
+
+/// Generate the secret of a new API token: the prefix, followed by random bytes (as hex).
+pub fn new_api_token_secret() -> String {
+    //
+    let mut bytes = [0u8; API_TOKEN_SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    format!("{API_TOKEN_PREFIX}{}", to_hex(&bytes))
+}
+
+/// Hash the secret of an API token, as it is kept (and looked up) in the database.
+/// As the secrets are random (unlike the passwords), a plain SHA-256 hash is enough.
+pub fn hash_api_token(secret: &str) -> String {
+    to_hex(&Sha256::digest(secret.as_bytes()))
+}
+
+/// The beginning of an API token's secret, that helps to recognize it.
+pub fn api_token_hint(secret: &str) -> String {
+    secret.chars().take(API_TOKEN_PREFIX.len() + API_TOKEN_HINT_LEN).collect()
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
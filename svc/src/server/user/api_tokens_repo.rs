@@ -0,0 +1,111 @@
+use crate::utils::{new_app_error_from_sqlx, uuid_from};
+use chrono::{DateTime, Utc};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::{ApiToken, Id},
+};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The columns of an API token (except its user and hash), including whether it has expired.
+const API_TOKEN_COLUMNS: &str =
+    "id, name, hint, permissions, created_at, expires_at, last_used_at, COALESCE(expires_at <= NOW(), FALSE) AS expired";
+
+#[derive(Debug)]
+pub struct ApiTokensRepo {
+    dbcp: Arc<PgPool>,
+}
+
+impl ApiTokensRepo {
+    //
+    pub fn new(dbcp: Arc<PgPool>) -> Self {
+        Self { dbcp }
+    }
+
+    /// Insert an API token of a user, along with the hash of its secret.
+    pub async fn insert(&self, user_id: &Id, token: &ApiToken, token_hash: &str) -> AppResult<()> {
+        //
+        sqlx::query(
+            "INSERT INTO api_tokens (id, user_id, name, token_hash, hint, permissions, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(uuid_from(&token.id))
+        .bind(uuid_from(user_id))
+        .bind(&token.name)
+        .bind(token_hash)
+        .bind(&token.hint)
+        .bind(&token.permissions)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .execute(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to add the API token".to_string())))?;
+        Ok(())
+    }
+
+    /// Get the API tokens of a user that are not revoked (including the expired ones), the newest first.
+    pub async fn get_all_of_user(&self, user_id: &Id) -> AppResult<Vec<ApiToken>> {
+        //
+        let sql = format!(
+            "SELECT {API_TOKEN_COLUMNS} FROM api_tokens
+             WHERE user_id = $1 AND revoked_at IS NULL
+             ORDER BY created_at DESC, id ASC"
+        );
+        let rows = sqlx::query(&sql)
+            .bind(uuid_from(user_id))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the API tokens".to_string())))?;
+
+        Ok(rows.iter().map(api_token_from_row).collect())
+    }
+
+    /// Revoke an API token of a user, so that it cannot be used anymore.
+    pub async fn revoke(&self, user_id: &Id, id: &Id) -> AppResult<()> {
+        //
+        let result =
+            sqlx::query("UPDATE api_tokens SET revoked_at = NOW() WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL")
+                .bind(uuid_from(id))
+                .bind(uuid_from(user_id))
+                .execute(self.dbcp.as_ref())
+                .await
+                .map_err(|err| new_app_error_from_sqlx(err, Some("failed to revoke the API token".to_string())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Get the usable (neither revoked nor expired) API token that has the provided hash, along with
+    /// the id of its user. It is also marked as used now.
+    pub async fn use_by_hash(&self, token_hash: &str) -> AppResult<Option<(Id, ApiToken)>> {
+        //
+        let sql = format!(
+            "UPDATE api_tokens SET last_used_at = NOW()
+             WHERE token_hash = $1 AND revoked_at IS NULL AND (expires_at IS NULL OR expires_at > NOW())
+             RETURNING user_id, {API_TOKEN_COLUMNS}"
+        );
+        let row = sqlx::query(&sql)
+            .bind(token_hash)
+            .fetch_optional(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get the API token".to_string())))?;
+
+        Ok(row.map(|row| (Id::from(row.get::<Uuid, _>("user_id").to_string()), api_token_from_row(&row))))
+    }
+}
+
+fn api_token_from_row(row: &PgRow) -> ApiToken {
+    ApiToken {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        hint: row.get("hint"),
+        permissions: row.get("permissions"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        expires_at: row.get::<Option<DateTime<Utc>>, _>("expires_at"),
+        last_used_at: row.get::<Option<DateTime<Utc>>, _>("last_used_at"),
+        expired: row.get("expired"),
+    }
+}
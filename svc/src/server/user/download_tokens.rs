@@ -0,0 +1,47 @@
+use crate::server::to_hex;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use cogs_shared::domain::model::Id;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The number of random bytes of a download token.
+const DOWNLOAD_TOKEN_BYTES: usize = 32;
+/// How long a download token can be used, after it was issued.
+const DOWNLOAD_TOKEN_LIFESPAN: Duration = Duration::from_secs(60);
+
+/// The short-lived, single-use tokens that authenticate the downloads (of the exports) that are opened
+/// in the browser, thus they cannot include the session header. Each one is issued for a user that is
+/// logged in, and is kept in memory until it is used or it expires.
+#[derive(Debug, Default)]
+pub struct DownloadTokens {
+    tokens: Mutex<HashMap<String, (Id, Instant)>>,
+}
+
+impl DownloadTokens {
+    //
+    /// Issue a download token for a user.
+    pub fn issue(&self, user_id: &Id) -> String {
+        //
+        let mut bytes = [0u8; DOWNLOAD_TOKEN_BYTES];
+        OsRng.fill_bytes(&mut bytes);
+        let token = to_hex(&bytes);
+        let now = Instant::now();
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        tokens.retain(|_, (_, expires_at)| *expires_at > now);
+        tokens.insert(token.clone(), (user_id.clone(), now + DOWNLOAD_TOKEN_LIFESPAN));
+        token
+    }
+
+    /// Use a download token, getting the id of its user, unless it is unknown, used or expired.
+    pub fn take(&self, token: &str) -> Option<Id> {
+        //
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        tokens
+            .remove(token)
+            .filter(|(_, expires_at)| *expires_at > Instant::now())
+            .map(|(user_id, _)| user_id)
+    }
+}
@@ -0,0 +1,110 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The consecutive failed logins that are allowed without any delay.
+const FREE_FAILURES: u32 = 3;
+/// The delay that is required after the first failure beyond the free ones. It doubles with each further failure.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+/// The max delay that is required between two login attempts.
+const MAX_DELAY: Duration = Duration::from_secs(5 * 60);
+/// The failures are forgotten after this long without another one.
+const FORGET_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// The consecutive failed logins of a username after which its account is locked.
+pub const LOCK_AFTER_FAILURES: u32 = 10;
+/// How long an account stays locked, after too many failed logins.
+pub const LOCK_DURATION: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ThrottleKey {
+    Username(String),
+    ClientIp(IpAddr),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Failures {
+    count: u32,
+    last: Instant,
+}
+
+impl Failures {
+    fn is_stale(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last) >= FORGET_AFTER
+    }
+
+    /// How long to wait (since `now`) before another attempt is allowed, if at all.
+    fn retry_after(&self, now: Instant) -> Option<Duration> {
+        if self.is_stale(now) {
+            return None;
+        }
+        let exp = self.count.checked_sub(FREE_FAILURES)?;
+        let delay = BASE_DELAY.saturating_mul(2u32.saturating_pow(exp)).min(MAX_DELAY);
+        (self.last + delay).checked_duration_since(now).filter(|wait| !wait.is_zero())
+    }
+}
+
+/// It counts the consecutive failed logins, per username and per client IP, to require an exponentially
+/// growing delay between the login attempts. The counters are kept in memory, thus they start over
+/// on a restart (while the account lock, that follows too many failures, is persisted).
+#[derive(Debug, Default)]
+pub struct LoginThrottle {
+    failures: Mutex<HashMap<ThrottleKey, Failures>>,
+}
+
+impl LoginThrottle {
+    //
+    /// How long to wait before another login of the username (or from the client IP) is allowed, if at all.
+    pub fn retry_after(&self, username: &str, client_ip: Option<IpAddr>) -> Option<Duration> {
+        //
+        let now = Instant::now();
+        let failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        keys(username, client_ip)
+            .iter()
+            .filter_map(|key| failures.get(key))
+            .filter_map(|f| f.retry_after(now))
+            .max()
+    }
+
+    /// Record a failed login, getting the number of consecutive failures of the username.
+    pub fn record_failure(&self, username: &str, client_ip: Option<IpAddr>) -> u32 {
+        //
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures.retain(|_, f| !f.is_stale(now));
+        let mut username_count = 0;
+        for key in keys(username, client_ip) {
+            let is_username = matches!(key, ThrottleKey::Username(_));
+            let entry = failures.entry(key).or_insert(Failures { count: 0, last: now });
+            entry.count = entry.count.saturating_add(1);
+            entry.last = now;
+            if is_username {
+                username_count = entry.count;
+            }
+        }
+        username_count
+    }
+
+    /// Forget the failures of the username and of the client IP, after a successful login.
+    pub fn record_success(&self, username: &str, client_ip: Option<IpAddr>) {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        for key in keys(username, client_ip) {
+            failures.remove(&key);
+        }
+    }
+
+    /// Forget the failures of the username, as its account was locked (or unlocked).
+    pub fn forget_username(&self, username: &str) {
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures.remove(&ThrottleKey::Username(username.to_string()));
+    }
+}
+
+fn keys(username: &str, client_ip: Option<IpAddr>) -> Vec<ThrottleKey> {
+    let mut keys = vec![ThrottleKey::Username(username.to_string())];
+    keys.extend(client_ip.map(ThrottleKey::ClientIp));
+    keys
+}
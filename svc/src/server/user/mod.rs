@@ -3,3 +3,24 @@ pub use user_mgmt::*;
 
 mod user_accounts_repo;
 pub use user_accounts_repo::*;
+
+mod user_mgmt_api;
+pub use user_mgmt_api::*;
+
+mod passwords;
+pub use passwords::*;
+
+mod account_api;
+pub use account_api::*;
+
+mod login_throttle;
+pub use login_throttle::*;
+
+mod api_tokens;
+pub use api_tokens::*;
+
+mod api_tokens_repo;
+pub use api_tokens_repo::*;
+
+mod download_tokens;
+pub use download_tokens::*;
@@ -0,0 +1,68 @@
+use argon2::{
+    Argon2,
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+};
+use cogs_shared::app::{AppError, AppResult};
+use subtle::ConstantTimeEq;
+
+/// The prefix of the PHC strings of the Argon2 password hashes.
+const ARGON2_HASH_PREFIX: &str = "$argon2";
+
+/// An Argon2id hash (with the default parameters) of a password that nobody has. It is verified against
+/// when the user is not found, so that an unknown username takes as long as a wrong password.
+pub const DUMMY_PASSWORD_HASH: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$ojwYUGOdeQ8w/2S34NPaFw$v6JLhW6WIBqQPflrTSH1P+v+ke+5mx3A+U/zvZN5v0M";
+
+/// Hash a password with Argon2id (and a random salt), getting its PHC string.
+/// Besides the hash, it includes the algorithm, its parameters and the salt.
+/// The (CPU bound) hashing runs on the blocking thread pool.
+pub async fn hash_password(pwd: &str) -> AppResult<String> {
+    //
+    let pwd = pwd.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(pwd.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|err| AppError::ErrDetails("failed to hash the password".into(), err.to_string()))
+    })
+    .await
+    .map_err(|err| AppError::ErrDetails("failed to hash the password".into(), err.to_string()))?
+}
+
+/// Verify (in constant time) a provided password against a stored hash, which is either
+/// an Argon2 PHC string or a legacy salted MD5 hash (that has a separate `salt`).
+/// The (CPU bound) verification runs on the blocking thread pool.
+pub async fn verify_password(input_pwd: &str, hash: &str, salt: Option<&str>) -> bool {
+    //
+    let (input_pwd, hash, salt) = (input_pwd.to_owned(), hash.to_owned(), salt.map(ToOwned::to_owned));
+    tokio::task::spawn_blocking(move || verify_password_blocking(&input_pwd, &hash, salt.as_deref()))
+        .await
+        .unwrap_or_else(|err| {
+            log::error!("[verify_password] Failed to verify the password. Error: {err}");
+            false
+        })
+}
+
+fn verify_password_blocking(input_pwd: &str, hash: &str, salt: Option<&str>) -> bool {
+    //
+    if !is_legacy_hash(hash) {
+        return match PasswordHash::new(hash) {
+            Ok(parsed) => Argon2::default().verify_password(input_pwd.as_bytes(), &parsed).is_ok(),
+            Err(err) => {
+                log::error!("[verify_password] Failed to parse the stored password hash. Error: {err}");
+                false
+            }
+        };
+    }
+    let Some(salt) = salt else {
+        return false;
+    };
+    let digest = format!("{:x}", md5::compute(format!("@{salt}${input_pwd}").as_bytes()));
+    digest.as_bytes().ct_eq(hash.as_bytes()).into()
+}
+
+/// Whether a stored hash is a legacy (salted MD5) one, that should be replaced by an Argon2id one.
+pub fn is_legacy_hash(hash: &str) -> bool {
+    !hash.starts_with(ARGON2_HASH_PREFIX)
+}
@@ -5,11 +5,27 @@ use crate::{
 use cogs_shared::{
     app::{AppError, AppResult},
     domain::model::{Id, UserAccount, UserEntry, UserPasswordSalt},
+    dtos::{Page, PageRequest, ProfileUpdateRequest},
 };
-use sqlx::{PgPool, Row, postgres::PgRow};
-use std::sync::Arc;
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
+use std::{sync::Arc, time::Duration};
 use uuid::Uuid;
 
+/// The fields that the user accounts can be sorted by, mapped to their columns.
+const USERS_SORT_COLUMNS: &[(&str, &str)] = &[("name", "ua.name"), ("username", "ua.username"), ("email", "ua.email")];
+
+/// The columns of a user account, including its clearance and permissions. It expects `ua` as the `user_accounts` alias.
+const USER_ACCOUNT_COLUMNS: &str = "ua.id, ua.name, ua.email, ua.username, ua.bio, ua.is_anonymous, ua.state, ua.access_level_id,
+     ua.must_reset_password, (ua.state = 'L' AND ua.locked_until > NOW()) AS locked, COALESCE(al.rank, 0) AS clearance,
+     ARRAY(SELECT up.permission FROM user_permissions up WHERE up.user_id = ua.id ORDER BY up.permission) AS permissions";
+
+/// The `state` of a user account that can log in.
+const STATE_ACTIVE: &str = "A";
+/// The `state` of a user account that is disabled, thus cannot log in.
+const STATE_DISABLED: &str = "D";
+/// The `state` of a user account that is temporarily locked (until its `locked_until`), due to repeated failed logins.
+const STATE_LOCKED: &str = "L";
+
 #[derive(Debug)]
 pub struct UserAccountsRepo {
     dbcp: Arc<PgPool>,
@@ -30,14 +46,19 @@ impl UserAccountsRepo {
     pub async fn get_by_username(&self, username: &String) -> AppResult<UserEntry> {
         //
         let row = sqlx::query(
-            "SELECT id, name, email, password, salt, bio, is_anonymous FROM user_accounts 
-             WHERE username = $1",
+            "SELECT ua.id, ua.name, ua.email, ua.password, ua.salt, ua.bio, ua.is_anonymous, ua.state, ua.access_level_id,
+                    ua.must_reset_password, COALESCE(al.rank, 0) AS clearance,
+                    CASE WHEN ua.state = 'L' AND ua.locked_until > NOW()
+                         THEN CEIL(EXTRACT(EPOCH FROM ua.locked_until - NOW()))::BIGINT END AS locked_secs
+             FROM user_accounts ua LEFT JOIN access_levels al ON al.id = ua.access_level_id
+             WHERE ua.username = $1",
         )
         .bind(username)
         .fetch_one(self.dbcp.as_ref())
         .await
         .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get user by email".to_string())))?;
 
+        let locked_secs = row.get::<Option<i64>, _>("locked_secs").map(|secs| secs.max(1) as u64);
         let mut user_account = UserAccount {
             id: row.get::<Uuid, _>("id").to_string().into(),
             name: row.get("name"),
@@ -46,6 +67,13 @@ impl UserAccountsRepo {
             bio: row.get("bio"),
             is_anonymous: row.get("is_anonymous"),
             permissions: Vec::new(),
+            clearance: row.get("clearance"),
+            access_level_id: row
+                .get::<Option<Uuid>, _>("access_level_id")
+                .map(|id| Id::from(id.to_string())),
+            disabled: row.get::<&str, _>("state") == STATE_DISABLED,
+            must_reset_password: row.get("must_reset_password"),
+            locked: locked_secs.is_some(),
         };
 
         let permissions = sqlx::query("SELECT permission FROM user_permissions WHERE user_id = $1")
@@ -60,6 +88,7 @@ impl UserAccountsRepo {
             user: user_account,
             password: row.get("password"),
             salt: row.get("salt"),
+            locked_secs,
         })
     }
 
@@ -79,6 +108,7 @@ impl UserAccountsRepo {
             bio: row.get("bio"),
             is_anonymous: row.get("is_anonymous"),
             permissions: Vec::new(),
+            ..Default::default()
         };
 
         let mut permissions = sqlx::query("SELECT permission FROM user_permissions WHERE user_id = $1;")
@@ -114,21 +144,19 @@ impl UserAccountsRepo {
         email: &String,
         username: &String,
         pwd: &String,
-        salt: &String,
         permissions: Vec<String>,
     ) -> AppResult<Id> {
         //
         let id = new_id();
         let res = sqlx::query(
-            "INSERT INTO user_accounts (id, name, email, username, password, salt) 
-             VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO user_accounts (id, name, email, username, password) 
+             VALUES ($1, $2, $3, $4, $5)",
         )
         .bind(uuid_from(&id))
         .bind(name)
         .bind(email)
         .bind(username)
         .bind(pwd)
-        .bind(salt)
         .execute(self.dbcp.as_ref())
         .await
         .map_err(|err| new_app_error_from_sqlx(err, None));
@@ -151,6 +179,211 @@ impl UserAccountsRepo {
         AppResult::Ok(id)
     }
 
+    /// Get a page of the (non anonymous) user accounts, along with their permissions.
+    pub async fn get_page(&self, page: &PageRequest) -> AppResult<Page<UserAccount>> {
+        //
+        let sort_column = page.sort_column(USERS_SORT_COLUMNS);
+        let sql = format!(
+            "SELECT {USER_ACCOUNT_COLUMNS}
+             FROM user_accounts ua LEFT JOIN access_levels al ON al.id = ua.access_level_id
+             WHERE NOT ua.is_anonymous
+             ORDER BY {sort_column} {}, ua.id ASC
+             LIMIT $1 OFFSET $2",
+            page.dir.as_sql()
+        );
+        let rows = sqlx::query(&sql)
+            .bind(i64::from(page.limit()))
+            .bind(i64::from(page.offset))
+            .fetch_all(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get users".to_string())))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_accounts WHERE NOT is_anonymous")
+            .fetch_one(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to count users".to_string())))?;
+
+        let data = rows.iter().map(user_account_from_row).collect();
+
+        Ok(Page::new(data, page, total as u64))
+    }
+
+    /// Get a user account, along with its permissions.
+    pub async fn get_account(&self, id: &Id) -> AppResult<UserAccount> {
+        //
+        let sql = format!(
+            "SELECT {USER_ACCOUNT_COLUMNS}
+             FROM user_accounts ua LEFT JOIN access_levels al ON al.id = ua.access_level_id
+             WHERE ua.id = $1"
+        );
+        let row = sqlx::query(&sql)
+            .bind(uuid_from(id))
+            .fetch_optional(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to get user".to_string())))?;
+
+        row.as_ref().map(user_account_from_row).ok_or(AppError::NotFound)
+    }
+
+    /// Insert a user account, along with its permissions.
+    pub async fn insert(&self, user: &UserAccount, pwd: &str) -> AppResult<()> {
+        //
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+        sqlx::query(
+            "INSERT INTO user_accounts (id, name, email, username, password, bio, state, access_level_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(uuid_from(&user.id))
+        .bind(&user.name)
+        .bind(&user.email)
+        .bind(&user.username)
+        .bind(pwd)
+        .bind(&user.bio)
+        .bind(state_of(user))
+        .bind(user.access_level_id.as_ref().map(uuid_from))
+        .execute(&mut *txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some(user.username.clone())))?;
+
+        Self::replace_permissions(&mut txn, &user.id, &user.permissions).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Update a user account (except its password), along with its permissions.
+    pub async fn update(&self, user: &UserAccount) -> AppResult<()> {
+        //
+        let mut txn = self.dbcp.begin().await.map_err(|e| AppError::from(e.to_string()))?;
+        let result = sqlx::query(
+            "UPDATE user_accounts
+             SET name = $2, email = $3, username = $4, bio = $5, access_level_id = $7,
+                 state = CASE WHEN state = 'L' AND $6 = 'A' THEN state ELSE $6 END
+             WHERE id = $1 AND NOT is_anonymous",
+        )
+        .bind(uuid_from(&user.id))
+        .bind(&user.name)
+        .bind(&user.email)
+        .bind(&user.username)
+        .bind(&user.bio)
+        .bind(state_of(user))
+        .bind(user.access_level_id.as_ref().map(uuid_from))
+        .execute(&mut *txn)
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some(user.username.clone())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+
+        Self::replace_permissions(&mut txn, &user.id, &user.permissions).await?;
+        txn.commit().await.map_err(|e| AppError::from(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Disable (so that it cannot log in anymore) or enable a user account.
+    pub async fn set_disabled(&self, id: &Id, disabled: bool) -> AppResult<()> {
+        //
+        let result = sqlx::query("UPDATE user_accounts SET state = $2 WHERE id = $1 AND NOT is_anonymous")
+            .bind(uuid_from(id))
+            .bind(if disabled { STATE_DISABLED } else { STATE_ACTIVE })
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to change the user state".to_string())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Update the profile (name, email and bio) of a user account.
+    pub async fn update_profile(&self, id: &Id, profile: &ProfileUpdateRequest) -> AppResult<()> {
+        //
+        let result = sqlx::query("UPDATE user_accounts SET name = $2, email = $3, bio = $4 WHERE id = $1 AND NOT is_anonymous")
+            .bind(uuid_from(id))
+            .bind(profile.name.trim())
+            .bind(profile.email.trim())
+            .bind(&profile.bio)
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some(format!("an account with email '{}'", profile.email.trim()))))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Lock an active user account (by its username) for the provided duration, getting whether it was locked.
+    pub async fn lock(&self, username: &str, duration: Duration) -> AppResult<bool> {
+        //
+        let result = sqlx::query(
+            "UPDATE user_accounts SET state = $2, locked_until = NOW() + make_interval(secs => $3)
+             WHERE username = $1 AND state IN ($4, $2) AND NOT is_anonymous",
+        )
+        .bind(username)
+        .bind(STATE_LOCKED)
+        .bind(duration.as_secs_f64())
+        .bind(STATE_ACTIVE)
+        .execute(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to lock the user".to_string())))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Unlock a user account (if it is locked), getting its username.
+    pub async fn unlock(&self, id: &Id) -> AppResult<String> {
+        //
+        let row = sqlx::query(
+            "UPDATE user_accounts SET state = CASE WHEN state = $2 THEN $3 ELSE state END, locked_until = NULL
+             WHERE id = $1 AND NOT is_anonymous
+             RETURNING username",
+        )
+        .bind(uuid_from(id))
+        .bind(STATE_LOCKED)
+        .bind(STATE_ACTIVE)
+        .fetch_optional(self.dbcp.as_ref())
+        .await
+        .map_err(|err| new_app_error_from_sqlx(err, Some("failed to unlock the user".to_string())))?;
+
+        row.map(|row| row.get("username")).ok_or(AppError::NotFound)
+    }
+
+    /// Require a user to set a new password on the next login.
+    pub async fn force_password_reset(&self, id: &Id) -> AppResult<()> {
+        //
+        let result = sqlx::query("UPDATE user_accounts SET must_reset_password = TRUE WHERE id = $1 AND NOT is_anonymous")
+            .bind(uuid_from(id))
+            .execute(self.dbcp.as_ref())
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to force the password reset".to_string())))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn replace_permissions(txn: &mut Transaction<'_, Postgres>, user_id: &Id, permissions: &[String]) -> AppResult<()> {
+        //
+        sqlx::query("DELETE FROM user_permissions WHERE user_id = $1")
+            .bind(uuid_from(user_id))
+            .execute(&mut **txn)
+            .await
+            .map_err(|err| new_app_error_from_sqlx(err, Some("failed to delete user permissions".to_string())))?;
+
+        for permission in permissions {
+            sqlx::query("INSERT INTO user_permissions (user_id, permission) VALUES ($1, $2)")
+                .bind(uuid_from(user_id))
+                .bind(permission)
+                .execute(&mut **txn)
+                .await
+                .map_err(|err| new_app_error_from_sqlx(err, Some("failed to add user permission".to_string())))?;
+        }
+        Ok(())
+    }
+
     pub async fn get_password_by_id(&self, user_id: &Id) -> AppResult<UserPasswordSalt> {
         //
         let row = sqlx::query("SELECT password, salt FROM user_accounts WHERE id = $1")
@@ -165,9 +398,11 @@ impl UserAccountsRepo {
         })
     }
 
+    /// Set the (hashed) password of a user, which also fulfills a forced reset of it.
+    /// The separate salt is cleared, as only the legacy hashes need it.
     pub async fn update_password(&self, user_id: &Id, pwd: String) -> AppResult<()> {
         //
-        match sqlx::query("UPDATE user_accounts SET password = $1 WHERE id = $2")
+        match sqlx::query("UPDATE user_accounts SET password = $1, salt = NULL, must_reset_password = FALSE WHERE id = $2")
             .bind(pwd)
             .bind(uuid_from(user_id))
             .execute(self.dbcp.as_ref())
@@ -179,3 +414,26 @@ impl UserAccountsRepo {
         }
     }
 }
+
+fn state_of(user: &UserAccount) -> &'static str {
+    if user.disabled { STATE_DISABLED } else { STATE_ACTIVE }
+}
+
+fn user_account_from_row(row: &PgRow) -> UserAccount {
+    UserAccount {
+        id: Id::from(row.get::<Uuid, _>("id").to_string()),
+        name: row.get("name"),
+        email: row.get("email"),
+        username: row.get("username"),
+        bio: row.get::<Option<String>, _>("bio").unwrap_or_default(),
+        is_anonymous: row.get("is_anonymous"),
+        permissions: row.get("permissions"),
+        clearance: row.get("clearance"),
+        access_level_id: row
+            .get::<Option<Uuid>, _>("access_level_id")
+            .map(|id| Id::from(id.to_string())),
+        disabled: row.get::<&str, _>("state") == STATE_DISABLED,
+        must_reset_password: row.get("must_reset_password"),
+        locked: row.get("locked"),
+    }
+}
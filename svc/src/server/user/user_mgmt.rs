@@ -1,84 +1,332 @@
-use crate::server::UserAccountsRepo;
+use crate::{
+    server::{
+        ApiTokensRepo, DUMMY_PASSWORD_HASH, DownloadTokens, LOCK_AFTER_FAILURES, LOCK_DURATION, LoginThrottle, UserAccountsRepo,
+        api_token_hint, hash_api_token, hash_password, is_legacy_hash, new_api_token_secret, verify_password,
+    },
+    utils::new_id,
+};
+use chrono::Utc;
 use cogs_shared::{
     app::{AppError, AppResult},
-    domain::model::{Id, UserAccount},
+    domain::{
+        logic::{check_api_token_request, check_new_password, check_user_account, check_user_profile},
+        model::{API_TOKEN_PREFIX, ApiToken, Id, PERM_ADMIN_READ, PERM_ADMIN_WRITE, UserAccount},
+    },
+    dtos::{ApiTokenCreateRequest, ApiTokenCreated, Page, PageRequest, ProfileUpdateRequest, UserUpsertRequest},
 };
-use randoid::randoid;
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc, time::Duration};
 
 #[derive(Clone, Debug)]
 pub struct UserMgmt {
     user_repo: Arc<UserAccountsRepo>,
+    api_tokens_repo: Arc<ApiTokensRepo>,
+    login_throttle: Arc<LoginThrottle>,
+    download_tokens: Arc<DownloadTokens>,
 }
 
 impl UserMgmt {
     //
-    pub fn new(user_repo: Arc<UserAccountsRepo>) -> Self {
-        Self { user_repo }
+    pub fn new(user_repo: Arc<UserAccountsRepo>, api_tokens_repo: Arc<ApiTokensRepo>) -> Self {
+        Self {
+            user_repo,
+            api_tokens_repo,
+            login_throttle: Arc::new(LoginThrottle::default()),
+            download_tokens: Arc::new(DownloadTokens::default()),
+        }
     }
 
-    pub async fn authenticate_user(&self, email: String, pwd: String) -> AppResult<UserAccount> {
+    /// Log in a user, as in `authenticate_user`, but throttled: the failed logins (of the username and from
+    /// the client IP) require an exponentially growing delay before the next attempt, and too many of them
+    /// lock the account for a while.
+    pub async fn login(
+        &self,
+        username: String,
+        pwd: String,
+        new_pwd: Option<String>,
+        client_ip: Option<IpAddr>,
+    ) -> AppResult<UserAccount> {
         //
-        let user_entry = self.user_repo.get_by_username(&email).await.map_err(|err| {
-            if err == AppError::NotFound {
-                AppError::Unauthorized("wrong credentials".into())
-            } else {
-                err
+        if let Some(wait) = self.login_throttle.retry_after(&username, client_ip) {
+            return Err(AppError::LoginThrottled(secs_of(wait)));
+        }
+        match self.authenticate_user(username.clone(), pwd, new_pwd).await {
+            Ok(user) => {
+                self.login_throttle.record_success(&username, client_ip);
+                Ok(user)
+            }
+            Err(err @ AppError::Unauthorized(_)) => {
+                let failures = self.login_throttle.record_failure(&username, client_ip);
+                if failures >= LOCK_AFTER_FAILURES && self.user_repo.lock(&username, LOCK_DURATION).await? {
+                    log::warn!("[login] Locked the account of '{username}', after {failures} failed logins.");
+                    self.login_throttle.forget_username(&username);
+                    return Err(AppError::LoginLocked(LOCK_DURATION.as_secs()));
+                }
+                Err(err)
             }
-        })?;
-        match Self::check_password(&pwd, &user_entry.password, &user_entry.salt) {
-            true => Ok(user_entry.into()),
-            false => Err(AppError::Unauthorized("wrong credentials".into())),
+            Err(err) => Err(err),
         }
     }
 
+    /// How long to wait before another login of the username (or from the client IP) is allowed, if at all.
+    pub fn login_retry_after(&self, username: &str, client_ip: Option<IpAddr>) -> Option<u64> {
+        self.login_throttle.retry_after(username, client_ip).map(secs_of)
+    }
+
+    /// Authenticate a user by its credentials. If the user must reset its password, the `new_pwd` is required
+    /// (and it replaces the current one). Otherwise, a legacy (salted MD5) password hash is upgraded to Argon2id.
+    pub async fn authenticate_user(&self, email: String, pwd: String, new_pwd: Option<String>) -> AppResult<UserAccount> {
+        //
+        let user_entry = match self.user_repo.get_by_username(&email).await {
+            Ok(user_entry) => user_entry,
+            Err(AppError::NotFound) => {
+                // A password is verified anyway, so that an unknown username cannot be told apart by the response time.
+                verify_password(&pwd, DUMMY_PASSWORD_HASH, None).await;
+                return Err(AppError::Unauthorized("wrong credentials".into()));
+            }
+            Err(err) => return Err(err),
+        };
+        // A locked account is refused before checking the password, so that it cannot be guessed meanwhile.
+        if let Some(secs) = user_entry.locked_secs {
+            return Err(AppError::LoginLocked(secs));
+        }
+        if !verify_password(&pwd, &user_entry.password, user_entry.salt.as_deref()).await {
+            return Err(AppError::Unauthorized("wrong credentials".into()));
+        }
+        if user_entry.user.disabled {
+            return Err(AppError::Unauthorized("the account is disabled".into()));
+        }
+
+        let mut user = user_entry.user;
+        if user.must_reset_password {
+            let Some(new_pwd) = new_pwd else {
+                return Err(AppError::LoginPasswordResetRequired);
+            };
+            check_new_password(&new_pwd)?;
+            if new_pwd == pwd {
+                return Err(AppError::InvalidInput(
+                    "the new password must differ from the current one".into(),
+                ));
+            }
+            self.user_repo
+                .update_password(&user.id, hash_password(&new_pwd).await?)
+                .await?;
+            user.must_reset_password = false;
+        } else if is_legacy_hash(&user_entry.password) {
+            // The login does not fail if the upgrade does, as the legacy hash is still valid.
+            match hash_password(&pwd).await {
+                Ok(hash) => match self.user_repo.update_password(&user.id, hash).await {
+                    Ok(()) => log::info!("[authenticate_user] Upgraded the password hash of user w/ id {}.", user.id),
+                    Err(err) => log::error!("[authenticate_user] Failed to upgrade the password hash. Error: {err}"),
+                },
+                Err(err) => log::error!("[authenticate_user] Failed to upgrade the password hash. Error: {err}"),
+            }
+        }
+        Ok(user)
+    }
+
+    /// Authenticate a user by the secret of one of its API tokens. The user gets just the permissions
+    /// that the token grants. An unknown, revoked or expired token is refused, as is a disabled user's one.
+    pub async fn authenticate_api_token(&self, secret: &str) -> AppResult<UserAccount> {
+        //
+        if !secret.starts_with(API_TOKEN_PREFIX) {
+            return Err(AppError::Unauthorized("invalid API token".into()));
+        }
+        let Some((user_id, token)) = self.api_tokens_repo.use_by_hash(&hash_api_token(secret)).await? else {
+            return Err(AppError::Unauthorized("invalid, expired or revoked API token".into()));
+        };
+        let mut user = match self.user_repo.get_account(&user_id).await {
+            Ok(user) => user,
+            Err(AppError::NotFound) => return Err(AppError::Unauthorized("invalid API token".into())),
+            Err(err) => return Err(err),
+        };
+        if user.disabled {
+            return Err(AppError::Unauthorized("the account is disabled".into()));
+        }
+        user.permissions = token.granted_permissions(&user.permissions);
+        Ok(user)
+    }
+
+    /// Create an API token for a user, getting it along with its secret (which is not kept).
+    pub async fn create_api_token(&self, user_id: &Id, req: ApiTokenCreateRequest) -> AppResult<ApiTokenCreated> {
+        //
+        // The account is loaded, as the permissions of the session's copy of it may be outdated.
+        let user = self.user_repo.get_account(user_id).await?;
+        check_api_token_request(&req, &user)?;
+        let secret = new_api_token_secret();
+        let created_at = Utc::now();
+        let token = ApiToken {
+            id: new_id(),
+            name: req.name.trim().to_string(),
+            hint: api_token_hint(&secret),
+            permissions: req.permissions,
+            created_at,
+            expires_at: req
+                .expires_in_days
+                .map(|days| created_at + chrono::Duration::days(i64::from(days))),
+            last_used_at: None,
+            expired: false,
+        };
+        self.api_tokens_repo.insert(user_id, &token, &hash_api_token(&secret)).await?;
+        Ok(ApiTokenCreated { token, secret })
+    }
+
+    /// Get the API tokens of a user that are not revoked.
+    pub async fn get_api_tokens(&self, user_id: &Id) -> AppResult<Vec<ApiToken>> {
+        self.api_tokens_repo.get_all_of_user(user_id).await
+    }
+
+    /// Revoke an API token of a user.
+    pub async fn revoke_api_token(&self, user_id: &Id, id: &Id) -> AppResult<()> {
+        self.api_tokens_repo.revoke(user_id, id).await
+    }
+
+    /// Issue a (short-lived, single-use) download token for a user, to authenticate a download of an export.
+    pub fn issue_download_token(&self, user_id: &Id) -> String {
+        self.download_tokens.issue(user_id)
+    }
+
+    /// Authenticate a user by a download token, that cannot be used anymore afterwards.
+    /// An unknown, used or expired token is refused, as is the one of a user that cannot act anymore.
+    pub async fn authenticate_download_token(&self, token: &str) -> AppResult<UserAccount> {
+        //
+        let Some(user_id) = self.download_tokens.take(token) else {
+            return Err(AppError::Unauthorized("invalid, expired or used download token".into()));
+        };
+        self.get_active_account(&user_id)
+            .await
+            .ok_or_else(|| AppError::Unauthorized("the account cannot be used".into()))
+    }
+
     pub async fn register_admin_user(&self, name: String, email: String, username: String, pwd: String) -> AppResult<Id> {
         //
-        let (pwd, salt) = Self::generate_password(pwd);
+        let pwd = hash_password(&pwd).await?;
         self.user_repo
             .save_with_permissions(
                 &name,
                 &email,
                 &username,
                 &pwd,
-                &salt,
-                vec!["Admin::Read".to_string(), "Admin::Write".to_string()],
+                vec![PERM_ADMIN_READ.to_string(), PERM_ADMIN_WRITE.to_string()],
             )
             .await
     }
 
-    pub async fn update_password(&self, user_id: &Id, curr_password: String, new_password: String) -> AppResult<()> {
+    /// Change the password of a user, that must provide its current one. As the user is already logged in,
+    /// a wrong current password is reported as an invalid input (thus it is not confused with a missing session).
+    /// The wrong current passwords are throttled like the failed logins, refused with `AppError::LoginThrottled`.
+    pub async fn update_password(
+        &self,
+        user_id: &Id,
+        curr_password: String,
+        new_password: String,
+        client_ip: Option<IpAddr>,
+    ) -> AppResult<()> {
         //
+        check_new_password(&new_password)?;
+        if new_password == curr_password {
+            return Err(AppError::InvalidInput(
+                "the new password must differ from the current one".into(),
+            ));
+        }
+        let username = self.user_repo.get_account(user_id).await?.username;
+        if let Some(wait) = self.login_throttle.retry_after(&username, client_ip) {
+            return Err(AppError::LoginThrottled(secs_of(wait)));
+        }
         let ups = self.user_repo.get_password_by_id(user_id).await?;
-        match Self::check_password(&curr_password, &ups.password, &ups.salt) {
+        match verify_password(&curr_password, &ups.password, ups.salt.as_deref()).await {
             true => {
-                let new_hash_pwd = Self::regenerate_password(new_password, ups.salt);
+                self.login_throttle.record_success(&username, client_ip);
+                let new_hash_pwd = hash_password(&new_password).await?;
                 self.user_repo.update_password(user_id, new_hash_pwd).await
             }
-            false => Err(AppError::Unauthorized("wrong password".into())),
+            false => {
+                // Counted like a failed login, so that the current password cannot be guessed by using a session.
+                self.login_throttle.record_failure(&username, client_ip);
+                Err(AppError::InvalidInput("the current password is wrong".into()))
+            }
+        }
+    }
+
+    /// Update the profile (name, email and bio) of a user, getting its account as saved.
+    pub async fn update_profile(&self, user_id: &Id, profile: ProfileUpdateRequest) -> AppResult<UserAccount> {
+        //
+        check_user_profile(&profile.name, &profile.email)?;
+        self.user_repo.update_profile(user_id, &profile).await?;
+        self.user_repo.get_account(user_id).await
+    }
+
+    /// Get the account of a user that is logged in, as currently saved, for acting on its behalf.
+    /// It's none if the user cannot act anymore: its account was disabled or removed, or it must reset its password.
+    pub async fn get_active_account(&self, id: &Id) -> Option<UserAccount> {
+        //
+        match self.user_repo.get_account(id).await {
+            Ok(user) if !user.disabled && !user.must_reset_password => Some(user),
+            Ok(_) | Err(AppError::NotFound) => None,
+            Err(err) => {
+                log::error!("[get_active_account] Failed to get the account of user w/ id {id}. Error: {err}");
+                None
+            }
         }
     }
 
-    fn generate_password(pwd: String) -> (String, String) {
+    pub async fn get_users(&self, page: PageRequest) -> AppResult<Page<UserAccount>> {
+        self.user_repo.get_page(&page).await
+    }
+
+    /// Create (if it has no id) or update a user account, along with its permissions and (optionally) its password.
+    /// The `acting` user (the one that is logged in) cannot lock itself out, by disabling itself or
+    /// by giving up its `Admin::Write` permission.
+    pub async fn upsert_user(&self, req: UserUpsertRequest, acting: Option<&UserAccount>) -> AppResult<Id> {
         //
-        // let salt: String = std::iter::repeat_with(fastrand::alphanumeric)
-        // .take(12)
-        // .collect();
+        let UserUpsertRequest { mut user, password } = req;
+        check_user_account(&user, password.as_deref())?;
+        user.is_anonymous = false;
+
+        if user.id.is_zero() {
+            user.id = new_id();
+            let pwd = hash_password(&password.unwrap_or_default()).await?;
+            self.user_repo.insert(&user, &pwd).await?;
+            return Ok(user.id);
+        }
 
-        let salt = randoid!(12);
-        let digest = md5::compute(format!("@{salt}${pwd}").as_bytes());
-        (format!("{:x}", digest), salt)
+        if acting.is_some_and(|acting| acting.id == user.id) && (user.disabled || !user.is_admin_write()) {
+            return Err(AppError::InvalidInput(
+                "you cannot disable yourself or give up your own write permission".into(),
+            ));
+        }
+        self.user_repo.update(&user).await?;
+        if let Some(password) = password {
+            self.user_repo
+                .update_password(&user.id, hash_password(&password).await?)
+                .await?;
+        }
+        Ok(user.id)
     }
 
-    fn regenerate_password(pwd: String, salt: String) -> String {
+    /// Disable (so that it cannot log in anymore) or enable a user account.
+    pub async fn set_user_disabled(&self, id: &Id, disabled: bool, acting: Option<&UserAccount>) -> AppResult<()> {
         //
-        let digest = md5::compute(format!("@{salt}${pwd}").as_bytes());
-        format!("{:x}", digest)
+        if disabled && acting.is_some_and(|acting| acting.id == *id) {
+            return Err(AppError::InvalidInput("you cannot disable yourself".into()));
+        }
+        self.user_repo.set_disabled(id, disabled).await
     }
 
-    fn check_password(input_pwd: &str, pwd: &str, salt: &str) -> bool {
+    /// Unlock a user account that was locked due to repeated failed logins.
+    pub async fn unlock_user(&self, id: &Id) -> AppResult<()> {
         //
-        let digest = md5::compute(format!("@{salt}${input_pwd}").as_bytes());
-        pwd == format!("{:x}", digest)
+        let username = self.user_repo.unlock(id).await?;
+        self.login_throttle.forget_username(&username);
+        Ok(())
     }
+
+    /// Require a user to set a new password on the next login.
+    pub async fn force_password_reset(&self, id: &Id) -> AppResult<()> {
+        self.user_repo.force_password_reset(id).await
+    }
+}
+
+/// The whole seconds of a duration, rounded up (thus a wait is never reported as zero seconds).
+fn secs_of(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
 }
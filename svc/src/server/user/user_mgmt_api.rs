@@ -0,0 +1,100 @@
+use crate::server::{CurrentUser, ServerState, respond_bad_request, respond_internal_server_error, respond_not_found};
+use axum::{
+    Json,
+    extract::{self, Path, Query, State},
+    response::IntoResponse,
+};
+use cogs_shared::{
+    app::AppError,
+    domain::model::{Id, UserAccount},
+    dtos::{PageRequest, UserUpsertRequest},
+};
+use http::StatusCode;
+use serde_json::{Value, json};
+
+pub async fn get_all_users(State(state): State<ServerState>, Query(page): Query<PageRequest>) -> impl IntoResponse {
+    //
+    match state.user_mgmt.get_users(page).await {
+        Ok(page) => {
+            log::debug!("Got {} of {} users.", page.items.len(), page.total);
+            (StatusCode::OK, Json(json!(page)))
+        }
+        Err(err) => respond_internal_server_error(err),
+    }
+}
+
+pub async fn upsert_user(
+    State(state): State<ServerState>,
+    CurrentUser(acting): CurrentUser,
+    extract::Json(input): extract::Json<UserUpsertRequest>,
+) -> impl IntoResponse {
+    //
+    // The request is not logged, since it may include a password.
+    log::debug!("Upserting user '{}' ...", input.user.username);
+    match state.user_mgmt.upsert_user(input, acting.as_ref()).await {
+        Ok(id) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) | AppError::AlreadyExists(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn disable_user(
+    State(state): State<ServerState>,
+    CurrentUser(acting): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    set_user_disabled(&state, acting, id, true).await
+}
+
+pub async fn enable_user(
+    State(state): State<ServerState>,
+    CurrentUser(acting): CurrentUser,
+    Path(id): Path<Id>,
+) -> impl IntoResponse {
+    set_user_disabled(&state, acting, id, false).await
+}
+
+pub async fn force_password_reset(State(state): State<ServerState>, Path(id): Path<Id>) -> impl IntoResponse {
+    //
+    log::debug!("Forcing the password reset of user w/ id {id} ...");
+    match state.user_mgmt.force_password_reset(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+pub async fn unlock_user(State(state): State<ServerState>, Path(id): Path<Id>) -> impl IntoResponse {
+    //
+    log::debug!("Unlocking user w/ id {id} ...");
+    match state.user_mgmt.unlock_user(&id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
+
+async fn set_user_disabled(
+    state: &ServerState,
+    acting: Option<UserAccount>,
+    id: Id,
+    disabled: bool,
+) -> (StatusCode, Json<Value>) {
+    //
+    log::debug!("Setting user w/ id {id} as disabled: {disabled} ...");
+    match state.user_mgmt.set_user_disabled(&id, disabled, acting.as_ref()).await {
+        Ok(()) => (StatusCode::OK, Json(json!({ "id": id }))),
+        Err(err) => match err {
+            AppError::InvalidInput(_) => respond_bad_request(err),
+            AppError::NotFound => respond_not_found(err),
+            _ => respond_internal_server_error(err),
+        },
+    }
+}
@@ -23,8 +23,10 @@ pub fn new_app_error_from_sqlx(err: sqlx::Error, msg: Option<String>) -> AppErro
             // FYI: For now, any specifically unhandled error is considered as internal error.
             if let Some(db_err) = err.as_database_error() {
                 if let Some(code) = db_err.code() {
-                    if code.as_ref() == "23505" {
-                        aerr = AppError::AlreadyExists(msg.unwrap_or_default());
+                    match code.as_ref() {
+                        "23505" => aerr = AppError::AlreadyExists(msg.unwrap_or_default()),
+                        "23503" => aerr = AppError::DependenciesExist(Vec::new()),
+                        _ => {}
                     }
                 }
             }
@@ -1,18 +1,27 @@
 use crate::{
-    comps::{AppComponent, Footer, Header},
+    comps::{AppComponent, Footer, Header, SaveConflict},
     constants::{APP_KEY, CORNER_RADIUS},
     explore::ExploreView,
+    graph::GraphView,
     handle_msg,
     messages::UiMessage,
+    security::close_user_window,
     state::UiState,
-    views::{AppView, HomeView, LoginView, SettingsView, ViewName},
+    views::{AppView, HomeView, LoginView, SettingsView, SettingsViewState, ViewName},
+};
+use cogs_shared::{
+    app::AppError,
+    domain::model::meta::{AttrTemplate, Item, ItemTemplate, Kind},
+    dtos::DOWNLOAD_TOKEN_PARAM,
 };
-use cogs_shared::domain::model::meta::Kind;
 use egui::{
     FontData,
     epaint::text::{FontInsert, InsertFontFamily},
 };
-use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{
+    Arc, Mutex,
+    mpsc::{Receiver, Sender, channel},
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 struct CatppuccinTheme {
@@ -105,11 +114,14 @@ impl CogsApp {
 
         // Load previous app state, if any.
         // Note: The `persistence` feature must be enabled for this to work.
-        if let Some(storage) = cc.storage {
+        let mut app: Self = if let Some(storage) = cc.storage {
             eframe::get_value(storage, APP_KEY).unwrap_or_default()
         } else {
             Default::default()
-        }
+        };
+        // The (restored) user session is used by the data requests.
+        app.state.data.set_session(app.state.auth.user_session.clone());
+        app
     }
 
     fn init_font(ctx: &egui::Context) {
@@ -138,6 +150,12 @@ impl CogsApp {
             "#/explore" => {
                 self.state.set_curr_view(ViewName::Explore);
             }
+            "#/graph" => {
+                self.state.set_curr_view(ViewName::Graph);
+            }
+            "#/settings" => {
+                self.state.set_curr_view(ViewName::Settings);
+            }
             "#/login" => {
                 self.state.set_curr_view(ViewName::Login);
             }
@@ -171,13 +189,29 @@ impl eframe::App for CogsApp {
 
         if let Ok(res) = self.recvr.try_recv() {
             log::trace!("Received {:?}", res);
+            // Any change of the elements may change where the templates are used,
+            // and it adds a revision to the changed element (and to the migrated items, for item templates).
+            if matches!(
+                res,
+                UiMessage::ElementCreated(..)
+                    | UiMessage::ElementUpdated(..)
+                    | UiMessage::ElementDeleted(..)
+                    | UiMessage::ElementRestored(..)
+                    | UiMessage::ElementPurged(..)
+            ) {
+                self.state.data.invalidate_usages();
+                self.state.data.invalidate_revisions();
+            }
             match res {
                 UiMessage::Login(data) => match data {
                     Ok(acc_sess) => match acc_sess {
                         Some((account, session)) => {
                             self.state.auth.user_account = Some(account);
+                            self.state.data.set_session(Some(session.clone()));
                             self.state.auth.user_session = Some(session);
                             self.state.auth.login_error = None;
+                            self.state.auth.login_reset_required = false;
+                            self.state.auth.new_pass.clear();
                             self.state.set_curr_view(ViewName::Home);
                         }
                         None => {
@@ -186,18 +220,30 @@ impl eframe::App for CogsApp {
                         }
                     },
                     Err(err) => {
+                        if err == AppError::LoginPasswordResetRequired {
+                            self.state.auth.login_reset_required = true;
+                        }
                         self.state.auth.login_error = Some(err);
                     }
                 },
 
+                UiMessage::LoginRetryAfter(secs) => {
+                    self.state.auth.login_retry_until = Some(ectx.input(|i| i.time) + secs as f64);
+                    ectx.request_repaint();
+                }
+
                 UiMessage::Logout => {
                     handle_msg(UiMessage::Logout, &self.state);
                     self.state.auth.user_account = None;
                     self.state.auth.user_session = None;
+                    self.state.data.set_session(None);
+                    self.state.settings = SettingsViewState::default();
                     self.state.set_curr_view(ViewName::Home);
                 }
 
-                UiMessage::Settings => {}
+                UiMessage::Settings => {
+                    self.state.set_curr_view(ViewName::Settings);
+                }
 
                 UiMessage::AttrTemplateUpserted(_) => {
                     self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
@@ -212,17 +258,22 @@ impl eframe::App for CogsApp {
                 UiMessage::ElementCreated(kind, ars) => {
                     match ars {
                         Ok(_id) => match kind {
-                            Kind::Item => todo!(),
+                            Kind::Item => {
+                                self.state.data.invalidate_items();
+                                ectx.request_repaint();
+                            }
                             Kind::ItemTemplate => {
+                                // The items created from it may have been migrated as well.
                                 self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone());
+                                self.state.data.invalidate_items();
                                 ectx.request_repaint();
                             }
                             Kind::AttributeTemplate => {
                                 self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
                                 ectx.request_repaint();
                             }
-                            Kind::LinkTemplate => todo!(),
-                            Kind::AccessLevel => {}
+                            // The link templates are part of the item templates, thus nothing else is fetched for them.
+                            Kind::LinkTemplate | Kind::AccessLevel | Kind::User => {}
                         },
                         Err(_err) => {
                             // TODO: show a popup window.
@@ -232,35 +283,139 @@ impl eframe::App for CogsApp {
 
                 UiMessage::ElementUpdated(kind, ar) => match ar {
                     Ok(_id) => match kind {
-                        Kind::Item => todo!(),
-                        Kind::ItemTemplate => todo!(),
+                        Kind::Item => {
+                            self.state.data.invalidate_items();
+                            ectx.request_repaint();
+                        }
+                        Kind::ItemTemplate => {
+                            self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone());
+                            self.state.data.invalidate_items();
+                            ectx.request_repaint();
+                        }
                         Kind::AttributeTemplate => {
                             self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
                             ectx.request_repaint();
                         }
-                        Kind::LinkTemplate => todo!(),
-                        Kind::AccessLevel => {}
+                        Kind::LinkTemplate | Kind::AccessLevel | Kind::User => {}
                     },
-                    Err(_) => {
-                        todo!();
+                    Err(err) => {
+                        log::error!("[app.update] Error updating {kind:?}: {err}");
                     }
                 },
 
                 UiMessage::ElementDeleted(kind, ar) => match ar {
-                    Ok(_id) => match kind {
-                        Kind::Item => todo!(),
+                    Ok(_id) => {
+                        // The deleted element is moved to the Trash.
+                        self.state.data.invalidate_trash();
+                        match kind {
+                            Kind::Item => {
+                                self.state.data.invalidate_items();
+                                ectx.request_repaint();
+                            }
+                            Kind::ItemTemplate => {
+                                self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone());
+                                ectx.request_repaint();
+                            }
+                            Kind::AttributeTemplate => {
+                                self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
+                                ectx.request_repaint();
+                            }
+                            Kind::LinkTemplate | Kind::AccessLevel | Kind::User => {}
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error deleting {kind:?}: {err}");
+                    }
+                },
+
+                UiMessage::ElementSaveConflict(kind, id, mine, current) => {
+                    // The window (closed on save) is reopened with the user's copy, to resolve the conflict there.
+                    let explore = &mut self.state.explore;
+                    match kind {
+                        Kind::Item => {
+                            if let Ok(item) = serde_json::from_value::<Item>(mine.clone()) {
+                                explore.open_windows_item.insert(id.clone(), item);
+                            }
+                        }
                         Kind::ItemTemplate => {
-                            self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone());
-                            ectx.request_repaint();
+                            if let Ok(tmpl) = serde_json::from_value::<ItemTemplate>(mine.clone()) {
+                                explore
+                                    .open_windows_item_template
+                                    .insert(id.clone(), Arc::new(Mutex::new(tmpl)));
+                            }
                         }
                         Kind::AttributeTemplate => {
-                            self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
-                            ectx.request_repaint();
+                            if let Ok(tmpl) = serde_json::from_value::<AttrTemplate>(mine.clone()) {
+                                explore
+                                    .open_windows_attr_template
+                                    .insert(id.clone(), Arc::new(Mutex::new(tmpl)));
+                            }
                         }
-                        Kind::LinkTemplate => todo!(),
-                        Kind::AccessLevel => {}
-                    },
-                    Err(_) => todo!(),
+                        Kind::LinkTemplate | Kind::AccessLevel | Kind::User => {}
+                    }
+                    let conflict = SaveConflict {
+                        kind,
+                        mine,
+                        current,
+                        compare: false,
+                    };
+                    explore.save_conflicts.insert(id, conflict);
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ItemsImported(ars) => {
+                    if matches!(&ars, Ok(report) if !report.dry_run && report.imported > 0) {
+                        self.state.data.invalidate_items();
+                    }
+                    if let Some(import) = &mut self.state.explore.items_import {
+                        import.pending = false;
+                        import.report = Some(ars);
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::BundleImported(ars) => {
+                    if ars.is_ok() {
+                        self.state.data.invalidate_items();
+                        self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone());
+                        self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone());
+                        self.state.data.fetch_all_access_levels(&ectx, self.sendr.clone());
+                    }
+                    if let Some(import) = &mut self.state.explore.bundle_import {
+                        import.pending = false;
+                        import.report = Some(ars);
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ElementRestored(kind, ar) => match ar {
+                    Ok(_id) => {
+                        self.state.explore.trash_err = None;
+                        self.state.data.invalidate_trash();
+                        match kind {
+                            Kind::Item => self.state.data.invalidate_items(),
+                            Kind::ItemTemplate => self.state.data.fetch_all_item_templates(&ectx, self.sendr.clone()),
+                            Kind::AttributeTemplate => self.state.data.fetch_all_attr_templates(&ectx, self.sendr.clone()),
+                            Kind::LinkTemplate | Kind::AccessLevel | Kind::User => {}
+                        }
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error restoring {kind:?}: {err}");
+                        self.state.explore.trash_err = Some(err);
+                    }
+                },
+
+                UiMessage::ElementPurged(kind, ar) => match ar {
+                    Ok(_id) => {
+                        self.state.explore.trash_err = None;
+                        self.state.data.invalidate_trash();
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error purging {kind:?}: {err}");
+                        self.state.explore.trash_err = Some(err);
+                    }
                 },
 
                 UiMessage::AttrTemplatesFetched(data) => match data {
@@ -289,6 +444,279 @@ impl eframe::App for CogsApp {
                         log::error!("[app.update] Error fetching access levels: {}", err);
                     }
                 },
+
+                UiMessage::UsersFetched(data) => match data {
+                    Ok(data) => {
+                        self.state.data.set_users(data);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching users: {}", err);
+                    }
+                },
+
+                UiMessage::UserSaved(win_id, ar) => match ar {
+                    Ok(id) => {
+                        close_user_window(self, &win_id);
+                        self.state.data.fetch_all_users(&ectx, self.sendr.clone());
+                        self.state.explore.curr_sel_elem = Some((Kind::User, id));
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error saving user: {err}");
+                        self.state.explore.user_cu_err.insert(win_id, err);
+                    }
+                },
+
+                UiMessage::UserDisabledSet(id, disabled, ar) => match ar {
+                    Ok(_) => {
+                        if let Some(user) = self.state.explore.open_windows_user.get_mut(&id) {
+                            user.disabled = disabled;
+                        }
+                        self.state.data.fetch_all_users(&ectx, self.sendr.clone());
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error disabling (or enabling) user: {err}");
+                        self.state.explore.user_cu_err.insert(id, err);
+                    }
+                },
+
+                UiMessage::UserPasswordResetForced(id, ar) => match ar {
+                    Ok(_) => {
+                        if let Some(user) = self.state.explore.open_windows_user.get_mut(&id) {
+                            user.must_reset_password = true;
+                        }
+                        self.state.data.fetch_all_users(&ectx, self.sendr.clone());
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error forcing the password reset of user: {err}");
+                        self.state.explore.user_cu_err.insert(id, err);
+                    }
+                },
+
+                UiMessage::UserUnlocked(id, ar) => match ar {
+                    Ok(_) => {
+                        if let Some(user) = self.state.explore.open_windows_user.get_mut(&id) {
+                            user.locked = false;
+                        }
+                        self.state.data.fetch_all_users(&ectx, self.sendr.clone());
+                        ectx.request_repaint();
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error unlocking user: {err}");
+                        self.state.explore.user_cu_err.insert(id, err);
+                    }
+                },
+
+                UiMessage::ProfileUpdated(ar) => {
+                    self.state.settings.profile_saving = false;
+                    match ar {
+                        Ok(account) => {
+                            self.state.auth.user_account = Some(account);
+                            self.state.settings.profile = None;
+                            self.state.settings.profile_saved = true;
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error updating the profile: {err}");
+                            self.state.settings.profile_err = Some(err);
+                        }
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::PasswordChanged(ar) => {
+                    self.state.settings.password_saving = false;
+                    match ar {
+                        Ok(_) => {
+                            self.state.settings.clear_passwords();
+                            self.state.settings.password_changed = true;
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error changing the password: {err}");
+                            self.state.settings.password_err = Some(err);
+                        }
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ApiTokensFetched(ar) => {
+                    self.state.settings.api_tokens_fetching = false;
+                    match ar {
+                        Ok(tokens) => {
+                            self.state.settings.api_tokens = Some(tokens);
+                            self.state.settings.api_tokens_err = None;
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error fetching the API tokens: {err}");
+                            self.state.settings.api_tokens_err = Some(err);
+                        }
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::DownloadTokenIssued(url, ar) => match ar {
+                    Ok(token) => {
+                        let sep = if url.contains('?') { '&' } else { '?' };
+                        let url = format!("{url}{sep}{DOWNLOAD_TOKEN_PARAM}={}", token.token);
+                        ectx.open_url(egui::OpenUrl::new_tab(url));
+                    }
+                    Err(err) => log::error!("[app.update] Error getting a download token: {err}"),
+                },
+
+                UiMessage::ApiTokenCreated(ar) => {
+                    self.state.settings.api_token_saving = false;
+                    match ar {
+                        Ok(created) => {
+                            if let Some(tokens) = &mut self.state.settings.api_tokens {
+                                tokens.insert(0, created.token.clone());
+                            }
+                            self.state.settings.new_api_token = Default::default();
+                            self.state.settings.created_api_token = Some(created);
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error creating the API token: {err}");
+                            self.state.settings.api_token_err = Some(err);
+                        }
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ApiTokenRevoked(id, ar) => {
+                    self.state.settings.api_token_revoking = None;
+                    match ar {
+                        Ok(_) => {
+                            if let Some(tokens) = &mut self.state.settings.api_tokens {
+                                tokens.retain(|token| token.id != id);
+                            }
+                            if self
+                                .state
+                                .settings
+                                .created_api_token
+                                .as_ref()
+                                .is_some_and(|c| c.token.id == id)
+                            {
+                                self.state.settings.created_api_token = None;
+                            }
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error revoking the API token w/ id {id}: {err}");
+                            self.state.settings.api_tokens_err = Some(err);
+                        }
+                    }
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ItemsFetched(params, page_req, data) => {
+                    if let Err(err) = &data {
+                        log::error!("[app.update] Error fetching items with {:?}: {}", params, err);
+                    }
+                    self.state.data.set_items_page(params, page_req, data);
+                }
+
+                UiMessage::ItemFetched(id, data) => match data {
+                    Ok(item) => {
+                        // The item may be fetched for opening its window.
+                        if self.state.explore.item_window_to_open.as_ref() == Some(&id) {
+                            self.state.explore.item_window_to_open = None;
+                            self.state.explore.open_windows_item.insert(id, item.clone());
+                        }
+                        self.state.data.set_item(item);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching item {}: {}", id, err);
+                    }
+                },
+
+                UiMessage::LinkTargetsFetched(tmpl_id, data) => match data {
+                    Ok(page) => {
+                        self.state.data.set_link_targets(tmpl_id, page);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching link targets of {:?}: {}", tmpl_id, err);
+                    }
+                },
+
+                UiMessage::ItemLinksFetched(item_id, data) => match data {
+                    Ok(data) => {
+                        self.state.data.set_item_links(item_id, data);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching links of item {}: {}", item_id, err);
+                    }
+                },
+
+                UiMessage::ItemLinkCreated(item_id, ars) | UiMessage::ItemLinkDeleted(item_id, ars) => {
+                    match ars {
+                        Ok(_) => {
+                            self.state.explore.item_cu_add_link_err.remove(&item_id);
+                        }
+                        Err(err) => {
+                            log::error!("[app.update] Error updating links of item {}: {}", item_id, err);
+                            self.state.explore.item_cu_add_link_err.insert(item_id.clone(), err);
+                        }
+                    }
+                    self.state.data.invalidate_item_links(&item_id);
+                    ectx.request_repaint();
+                }
+
+                UiMessage::UsagesFetched(id, data) => match data {
+                    Ok(data) => {
+                        self.state.data.set_usages(id, data);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching usages of template {}: {}", id, err);
+                    }
+                },
+
+                UiMessage::RevisionsFetched(id, data) => match data {
+                    Ok(data) => {
+                        self.state.data.set_revisions(id, data);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching revisions of element {}: {}", id, err);
+                    }
+                },
+
+                UiMessage::TrashFetched(data) => match data {
+                    Ok(data) => {
+                        self.state.data.set_trash(data);
+                    }
+                    Err(err) => {
+                        log::error!("[app.update] Error fetching the trash: {}", err);
+                    }
+                },
+
+                UiMessage::SearchDone(query, result) => {
+                    if let Err(err) = &result {
+                        log::error!("[app.update] Error searching for '{}': {}", query, err);
+                    }
+                    // The results of an older search (the query was changed meanwhile) are ignored.
+                    if query == self.state.explore.search_query.trim() {
+                        self.state.explore.search_results = Some((query, result));
+                        ectx.request_repaint();
+                    }
+                }
+
+                UiMessage::ItemGraphFetched(id, graph) => {
+                    if let Err(err) = &graph {
+                        log::error!("[app.update] Error fetching the graph of item {}: {}", id, err);
+                    }
+                    self.state.graph.on_graph_fetched(id, graph);
+                    ectx.request_repaint();
+                }
+
+                UiMessage::ItemTemplateChangePlanned(item_tmpl_id, plan) => {
+                    if let Err(err) = &plan {
+                        log::error!(
+                            "[app.update] Error planning the change of item template {}: {}",
+                            item_tmpl_id,
+                            err
+                        );
+                    }
+                    self.state.explore.item_template_cu_change_plan.insert(item_tmpl_id, plan);
+                    ectx.request_repaint();
+                }
             }
         }
 
@@ -299,6 +727,7 @@ impl eframe::App for CogsApp {
         match self.state.curr_view() {
             ViewName::Home => HomeView::show(self, ui),
             ViewName::Explore => ExploreView::show(self, ui),
+            ViewName::Graph => GraphView::show(self, ui),
             ViewName::Settings => SettingsView::show(self, ui),
             ViewName::Login => {
                 self.state.set_curr_view(ViewName::Login);
@@ -1,11 +1,11 @@
 use crate::{
     CogsApp,
-    comps::AppComponent,
+    comps::{AppComponent, get_usages, usages_text},
     constants::{EXPLORE_ELEMENT, ICON_ATTR_TMPL},
     utils::strong_separator,
 };
-use cogs_shared::domain::model::meta::AttrTemplate;
-use egui::{CursorIcon, FontId, Grid, Label, RichText, TextStyle};
+use cogs_shared::domain::model::meta::{AttrTemplate, Kind};
+use egui::{Align, CursorIcon, FontId, Grid, Label, Layout, RichText, TextStyle};
 
 pub struct AttrTemplatePreview {}
 
@@ -14,12 +14,13 @@ impl AppComponent for AttrTemplatePreview {
 
     /// It shows the properties of an attribute template.
     /// It expects to get the attribute template in `ui`'s `.data()` key named `EXPLORE_ELEMENT`.
-    fn show(_: &mut Self::Context, ui: &mut egui::Ui) {
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
         let element = ui
             .ctx()
             .data(|d| d.get_temp::<AttrTemplate>(egui::Id::from(EXPLORE_ELEMENT)))
             .clone()
             .unwrap_or_default();
+        let usages = get_usages(ctx, ui.ctx(), &Kind::AttributeTemplate, &element.id);
 
         ui.label(format!("{} {}", ICON_ATTR_TMPL, element.name.as_str()))
             .on_hover_cursor(CursorIcon::Help)
@@ -50,6 +51,14 @@ impl AppComponent for AttrTemplatePreview {
                 ui.add_enabled(false, Label::new(RichText::new("is required")));
                 ui.add(Label::new(element.is_required.to_string()));
                 ui.end_row();
+
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add_enabled(false, Label::new(RichText::new("used by")));
+                });
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add(Label::new(usages_text(&usages)));
+                });
+                ui.end_row();
             });
 
             ui.add_space(4.0);
@@ -1,9 +1,13 @@
-use crate::{CogsApp, comps::AppComponent, constants::EXPLORE_ELEMENT};
+use crate::{
+    CogsApp,
+    comps::{AppComponent, ConflictChoice, delete_disabled_text, get_usages, render_history, render_save_conflict},
+    constants::EXPLORE_ELEMENT,
+};
 use cogs_shared::domain::model::{
     Action, Id,
-    meta::{AttrTemplate, AttributeValueType},
+    meta::{AttrTemplate, AttributeValueType, Kind},
 };
-use egui::{Align, Button, Checkbox, ComboBox, CursorIcon, Direction, Grid, Label, Layout, Margin, Window, vec2};
+use egui::{Align, Button, Checkbox, CollapsingHeader, ComboBox, CursorIcon, Direction, Grid, Label, Layout, Margin, Window};
 use std::sync::{Arc, Mutex};
 use strum::IntoEnumIterator;
 
@@ -90,6 +94,25 @@ impl AttrTemplateWindow {
         });
     }
 
+    fn render_history(app: &mut CogsApp, ui: &mut egui::Ui, ectx: &egui::Context, s: &FormUiState) {
+        if s.id.is_zero() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.vertical(|ui| {
+                CollapsingHeader::new("History")
+                    .id_salt(format!("attr_tmpl_win_{}_history", s.id))
+                    .show(ui, |ui| {
+                        if render_history(app, ui, &Kind::AttributeTemplate, &s.id) {
+                            cleanup(app, ectx, &s.id, s.act_id, s.focus_id);
+                        }
+                    });
+            });
+            ui.add_space(14.0);
+        });
+    }
+
     fn render_footer_buttons(
         app: &mut CogsApp,
         ui: &mut egui::Ui,
@@ -129,7 +152,13 @@ impl AttrTemplateWindow {
                     Layout::from_main_dir_and_cross_align(Direction::LeftToRight, Align::Min),
                     |ui| {
                         ui.add_space(18.0);
-                        if ui.button("  Delete   ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                        let usages = get_usages(app, ectx, &Kind::AttributeTemplate, &element.id);
+                        let enabled = usages.as_ref().is_some_and(|deps| deps.is_empty());
+                        let resp = ui
+                            .add_enabled(enabled, Button::new("  Delete   "))
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_disabled_hover_text(delete_disabled_text(&usages));
+                        if resp.clicked() {
                             app.state.data.delete_attr_template(s.id.clone(), ectx, app.sendr.clone());
                             cleanup(app, ectx, &s.id, s.act_id, s.focus_id);
                         }
@@ -139,6 +168,24 @@ impl AttrTemplateWindow {
         });
     }
 
+    /// Resolve the conflict of the last save (if any), as chosen in its dialog.
+    fn render_save_conflict(app: &mut CogsApp, ectx: &egui::Context, element: &mut AttrTemplate, s: &FormUiState) {
+        match render_save_conflict(app, ectx, &s.id) {
+            Some(ConflictChoice::Overwrite(version)) => {
+                element.version = version;
+                app.state.data.save_attr_template(element.clone(), ectx, app.sendr.clone());
+                cleanup(app, ectx, &s.id, s.act_id, s.focus_id);
+            }
+            Some(ConflictChoice::Reload(current)) => {
+                if let Ok(current) = serde_json::from_value::<AttrTemplate>(current) {
+                    *element = current;
+                }
+                ectx.data_mut(|d| d.insert_temp(s.act_id, Action::View));
+            }
+            None => {}
+        }
+    }
+
     fn row_name(ui: &mut egui::Ui, ectx: &egui::Context, element: &mut AttrTemplate, s: &mut FormUiState) {
         ui.add_enabled(false, Label::new("            Name"));
         let resp = ui.add(egui::TextEdit::singleline(&mut element.name).interactive(!s.action.is_view()));
@@ -230,13 +277,17 @@ impl AppComponent for AttrTemplateWindow {
         Window::new(format!("attr_tmpl_form_{}_win", element.id))
             .title_bar(false)
             .resizable(false)
-            .fixed_size(vec2(320.0, 300.0))
+            .min_width(320.0)
+            .max_width(320.0)
+            .min_height(300.0)
             .frame(egui::Frame::window(&ectx.global_style()).inner_margin(Margin::ZERO))
             .show(ectx, |ui| {
                 ui.vertical(|ui| {
                     Self::render_header(ui, &s);
                     ui.add_space(20.0); // only the space you explicitly want
                     Self::render_form_grid(ui, ectx, &mut element, &mut s);
+                    ui.add_space(12.0);
+                    Self::render_history(ctx, ui, ectx, &s);
                     ui.add_space(20.0);
                     Self::render_footer_buttons(ctx, ui, ectx, &mut element, &s);
                     ui.add_space(10.0);
@@ -244,6 +295,8 @@ impl AppComponent for AttrTemplateWindow {
                 .response
                 .on_hover_cursor(CursorIcon::Grab);
             });
+
+        Self::render_save_conflict(ctx, ectx, &mut element, &s);
     }
 }
 
@@ -0,0 +1,267 @@
+use crate::{
+    CogsApp,
+    colors::faded_color,
+    comps::{AppComponent, Dropdown, DropdownItem, DropdownStyle},
+};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::meta::Kind,
+    dtos::{BundleClashPolicy, BundleIdsPolicy, BundleImportReport, BundleImportRequest, WorkspaceBundle},
+};
+use egui::{Align, Button, CursorIcon, Grid, Label, Layout, Margin, RichText, ScrollArea, TextEdit, Window};
+
+/// The state of importing a workspace bundle (as exported by another instance, or as a backup).
+#[derive(Clone, Debug, Default)]
+pub struct BundleImportState {
+    /// The JSON text of the bundle.
+    pub json: String,
+
+    pub ids: BundleIdsPolicy,
+
+    pub clashes: BundleClashPolicy,
+
+    /// Whether the import is in progress.
+    pub pending: bool,
+
+    /// The outcome of the import.
+    pub report: Option<AppResult<BundleImportReport>>,
+}
+
+impl BundleImportState {
+    fn request(&self) -> AppResult<BundleImportRequest> {
+        let bundle = serde_json::from_str::<WorkspaceBundle>(&self.json)
+            .map_err(|err| AppError::InvalidInput(format!("The text is not a workspace bundle: {err}")))?;
+        Ok(BundleImportRequest {
+            bundle,
+            ids: self.ids,
+            clashes: self.clashes,
+        })
+    }
+}
+
+fn kind_name(kind: &Kind) -> &'static str {
+    match kind {
+        Kind::Item => "Item",
+        Kind::ItemTemplate => "Item Template",
+        Kind::AttributeTemplate => "Attribute Template",
+        Kind::LinkTemplate => "Link Template",
+        Kind::AccessLevel => "Access Level",
+        Kind::User => "User",
+    }
+}
+
+/// The window of importing a workspace bundle, from a JSON text.
+pub struct BundleImportWindow;
+
+impl BundleImportWindow {
+    fn render_header(ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let w = ui.available_width();
+            ui.allocate_ui_with_layout(
+                egui::vec2(w.max(0.0), 0.0),
+                egui::Layout::top_down(egui::Align::Center),
+                |ui| {
+                    ui.add_enabled(false, Label::new(RichText::new("Import Workspace").size(13.0)));
+                },
+            );
+        });
+        ui.add_space(10.0);
+    }
+
+    fn render_source(ui: &mut egui::Ui, state: &mut BundleImportState) {
+        // A dropped file replaces the JSON text.
+        let dropped = ui.ctx().input(|i| i.raw.dropped_files.first().cloned());
+        if let Some(file) = dropped {
+            match (&file.bytes, &file.path) {
+                (Some(bytes), _) => state.json = String::from_utf8_lossy(bytes).into_owned(),
+                (None, Some(path)) => match std::fs::read_to_string(path) {
+                    Ok(text) => state.json = text,
+                    Err(err) => log::error!("[BundleImportWindow] Failed to read file {}: {err}", path.display()),
+                },
+                (None, None) => {}
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.vertical(|ui| {
+                ui.label("The bundle, as exported by \"Export Workspace\"");
+                ScrollArea::vertical()
+                    .id_salt("bundle_import_json")
+                    .max_height(180.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            TextEdit::multiline(&mut state.json)
+                                .code_editor()
+                                .desired_rows(8)
+                                .desired_width(440.0)
+                                .hint_text("Paste it here, or drop a .json file on the window."),
+                        );
+                    });
+            });
+        });
+        ui.add_space(8.0);
+
+        let ids = [
+            DropdownItem::new("preserve", BundleIdsPolicy::Preserve),
+            DropdownItem::new("remap", BundleIdsPolicy::Remap),
+        ];
+        let clashes = [
+            DropdownItem::new("skip", BundleClashPolicy::Skip),
+            DropdownItem::new("overwrite", BundleClashPolicy::Overwrite),
+            DropdownItem::new("rename", BundleClashPolicy::Rename),
+        ];
+        let style = || DropdownStyle {
+            min_width: 120.0,
+            ..Default::default()
+        };
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            Grid::new("bundle_import_policies_grid")
+                .num_columns(3)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    ui.label("Ids");
+                    if let Some(ids) = Dropdown::show(ui, ui.id().with("bundle_import_ids"), &state.ids, &ids, style()) {
+                        state.ids = ids;
+                    }
+                    ui.label(RichText::new("Remapped ids make copies of the elements.").color(faded_color(ui)));
+                    ui.end_row();
+
+                    ui.label("Clashes");
+                    if let Some(clashes) =
+                        Dropdown::show(ui, ui.id().with("bundle_import_clashes"), &state.clashes, &clashes, style())
+                    {
+                        state.clashes = clashes;
+                    }
+                    ui.label(RichText::new("Of the same id, or the same unique name.").color(faded_color(ui)));
+                    ui.end_row();
+                });
+        });
+    }
+
+    fn render_report(ui: &mut egui::Ui, state: &BundleImportState) {
+        let Some(report) = &state.report else {
+            return;
+        };
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.vertical(|ui| match report {
+                Err(err) => {
+                    ui.add(Label::new(RichText::new(err.to_string()).color(ui.visuals().error_fg_color)).wrap());
+                    // The items that are not valid against their templates are listed, to be fixed in the bundle.
+                    if let AppError::ValidationFailed(violations) = err {
+                        ScrollArea::vertical()
+                            .id_salt("bundle_import_violations")
+                            .max_height(160.0)
+                            .show(ui, |ui| {
+                                for violation in violations {
+                                    ui.add(Label::new(RichText::new(violation.to_string()).color(faded_color(ui))).wrap());
+                                }
+                            });
+                    }
+                }
+                Ok(report) => {
+                    ui.label(format!(
+                        "{} element(s) were created, {} overwritten and {} skipped, with {} item link(s).",
+                        report.created, report.overwritten, report.skipped, report.item_links
+                    ));
+                    if report.clashes.is_empty() {
+                        return;
+                    }
+                    ScrollArea::vertical()
+                        .id_salt("bundle_import_clashes")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for clash in &report.clashes {
+                                let kind = kind_name(&clash.kind);
+                                let text = match &clash.renamed_to {
+                                    Some(name) => format!("{kind} '{}': renamed to '{name}'", clash.name),
+                                    None => format!("{kind} '{}': {}", clash.name, clash.resolution),
+                                };
+                                ui.add(Label::new(RichText::new(text).color(faded_color(ui))).wrap());
+                            }
+                        });
+                }
+            });
+        });
+    }
+
+    fn render_footer_buttons(app: &mut CogsApp, ui: &mut egui::Ui, state: &mut BundleImportState) -> bool {
+        let mut close = false;
+        let imported = matches!(&state.report, Some(Ok(_)));
+
+        ui.add_space(16.0);
+        ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+            ui.add_space(18.0);
+            if imported {
+                if ui.button("  Close  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    close = true;
+                }
+                return;
+            }
+
+            let enabled = !state.json.trim().is_empty() && !state.pending;
+            let resp = ui
+                .add_enabled(enabled, Button::new("  Import  "))
+                .on_hover_cursor(CursorIcon::PointingHand)
+                .on_hover_text("Either all the elements are imported, or none of them.")
+                .on_disabled_hover_text("Provide the bundle.");
+            if resp.clicked() {
+                match state.request() {
+                    Ok(req) => {
+                        state.pending = true;
+                        state.report = None;
+                        app.state.data.import_bundle(req, ui.ctx(), app.sendr.clone());
+                    }
+                    Err(err) => state.report = Some(Err(err)),
+                }
+            }
+
+            ui.add_space(8.0);
+            if ui.button("  Cancel  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                close = true;
+            }
+            if state.pending {
+                ui.spinner();
+            }
+        });
+        close
+    }
+}
+
+impl AppComponent for BundleImportWindow {
+    type Context = CogsApp;
+
+    /// It shows the window of importing a workspace bundle, based on `explore.bundle_import` state.
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        let Some(mut state) = ctx.state.explore.bundle_import.take() else {
+            return;
+        };
+        let ectx = ui.ctx().clone();
+        let mut close = false;
+
+        Window::new("bundle_import_win")
+            .title_bar(false)
+            .resizable(false)
+            .min_width(480.0)
+            .max_width(480.0)
+            .frame(egui::Frame::window(&ectx.global_style()).inner_margin(Margin::ZERO))
+            .show(&ectx, |ui| {
+                ui.vertical(|ui| {
+                    Self::render_header(ui);
+                    Self::render_source(ui, &mut state);
+                    Self::render_report(ui, &state);
+                    close = Self::render_footer_buttons(ctx, ui, &mut state);
+                    ui.add_space(10.0);
+                })
+                .response
+                .on_hover_cursor(CursorIcon::Grab);
+            });
+
+        if !close {
+            ctx.state.explore.bundle_import = Some(state);
+        }
+    }
+}
@@ -4,7 +4,7 @@ use egui::{Align, Layout};
 use crate::{
     CogsApp,
     comps::{AppComponent, UserMenu},
-    constants::{ICON_EXPLORE, ICON_HOME},
+    constants::{ICON_EXPLORE, ICON_GRAPH, ICON_HOME},
     views::{AppView, ViewName},
 };
 
@@ -53,6 +53,11 @@ impl AppView for Header {
                         state.set_curr_view(ViewName::Explore);
                     }
 
+                    let resp = ui.selectable_value(&mut state.curr_view(), &ViewName::Graph, concatcp!(ICON_GRAPH, "  Graph "));
+                    if resp.clicked() {
+                        state.set_curr_view(ViewName::Graph);
+                    }
+
                     egui::global_theme_preference_switch(ui);
                     ui.with_layout(Layout::right_to_left(Align::LEFT), |ui| {
                         ui.add_space(6.0);
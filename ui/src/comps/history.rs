@@ -0,0 +1,119 @@
+use crate::{CogsApp, colors::faded_color};
+use cogs_shared::domain::{
+    logic::{FieldChange, diff_revisions},
+    model::{Id, Revision, meta::Kind},
+};
+use egui::{Button, CollapsingHeader, CursorIcon, Grid, Label, RichText, Ui};
+
+/// Render the revisions of an element (the newest first), each one with the fields
+/// that it changed compared to the previous one, and with a Restore button (except the latest one).
+/// It returns `true` if the restore of a revision was requested, so that the window can be closed.
+pub fn render_history(app: &mut CogsApp, ui: &mut Ui, kind: &Kind, id: &Id) -> bool {
+    if id.is_zero() {
+        ui.label(RichText::new("The history starts after saving it.").color(faded_color(ui)));
+        return false;
+    }
+
+    let ectx = ui.ctx().clone();
+    if app.state.data.should_fetch_revisions(id) {
+        app.state.data.fetch_revisions(kind, id.clone(), &ectx, app.sendr.clone());
+    }
+
+    let Some(revisions) = app.state.data.get_revisions(id).cloned() else {
+        ui.spinner();
+        return false;
+    };
+    if revisions.is_empty() {
+        ui.label(RichText::new("No revisions.").color(faded_color(ui)));
+        return false;
+    }
+
+    let mut restored = false;
+    egui::ScrollArea::vertical()
+        .max_height(260.0)
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            for (idx, revision) in revisions.iter().enumerate() {
+                let previous = revisions.get(idx + 1);
+                CollapsingHeader::new(revision_title(revision))
+                    .id_salt(format!("revision_{}", revision.id))
+                    .default_open(idx == 0)
+                    .show(ui, |ui| {
+                        render_changes(ui, revision, previous);
+                        if idx > 0 {
+                            ui.add_space(4.0);
+                            let resp = ui
+                                .add(Button::new("  Restore  "))
+                                .on_hover_cursor(CursorIcon::PointingHand)
+                                .on_hover_text("Save it as it was in this revision.");
+                            if resp.clicked() {
+                                app.state
+                                    .data
+                                    .restore_revision(kind.clone(), revision.id.clone(), &ectx, app.sendr.clone());
+                                restored = true;
+                            }
+                        }
+                    });
+            }
+        });
+    restored
+}
+
+fn revision_title(revision: &Revision) -> String {
+    let user = match revision.user_name.as_str() {
+        "" => "unknown",
+        name => name,
+    };
+    format!(
+        "#{}  {}  by {}",
+        revision.number,
+        revision.created_at.format("%Y-%m-%d %H:%M UTC"),
+        user
+    )
+}
+
+/// Render the fields changed by a revision, compared to the previous one.
+/// The first revision (without a previous one) shows all the fields, as created.
+fn render_changes(ui: &mut Ui, revision: &Revision, previous: Option<&Revision>) {
+    let changes = match previous {
+        Some(previous) => diff_revisions(previous, revision),
+        None => revision.fields().map(|fields| {
+            fields
+                .into_iter()
+                .map(|(field, value)| FieldChange {
+                    field,
+                    old: None,
+                    new: Some(value),
+                })
+                .collect()
+        }),
+    };
+    let changes = match changes {
+        Ok(changes) => changes,
+        Err(err) => {
+            ui.label(RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+            return;
+        }
+    };
+    if changes.is_empty() {
+        ui.label(RichText::new("No changes.").color(faded_color(ui)));
+        return;
+    }
+
+    Grid::new(format!("revision_{}_changes", revision.id))
+        .num_columns(2)
+        .spacing([10.0, 4.0])
+        .show(ui, |ui| {
+            for change in changes {
+                ui.add_enabled(false, Label::new(change.field));
+                let text = match (change.old, change.new) {
+                    (Some(old), Some(new)) => format!("{old} → {new}"),
+                    (None, Some(new)) => new,
+                    (Some(old), None) => format!("{old} (removed)"),
+                    (None, None) => String::new(),
+                };
+                ui.add(Label::new(text).wrap());
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,71 @@
+use crate::{
+    CogsApp,
+    comps::AppComponent,
+    constants::{EXPLORE_ELEMENT, ICON_GRAPH, ICON_ITEM},
+    utils::strong_separator,
+    views::ViewName,
+};
+use cogs_shared::domain::model::meta::Item;
+use const_format::concatcp;
+use egui::{CursorIcon, FontId, Grid, Label, RichText, TextStyle};
+
+pub struct ItemPreview {}
+
+impl AppComponent for ItemPreview {
+    type Context = CogsApp;
+
+    /// It shows the attributes of an item.
+    /// It expects to get the item in `ui`'s `.data()` key named `EXPLORE_ELEMENT`.
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        let element = ui
+            .ctx()
+            .data(|d| d.get_temp::<Item>(egui::Id::from(EXPLORE_ELEMENT)))
+            .clone()
+            .unwrap_or_default();
+
+        ui.label(format!("{} {}", ICON_ITEM, element.listing_attr_value.as_str()))
+            .on_hover_cursor(CursorIcon::Help)
+            .on_hover_text("This is an item.");
+
+        ui.add_space(4.0);
+        strong_separator(ui, ui.available_width());
+        ui.add_space(2.0);
+
+        ui.scope(|ui| {
+            // Apply once for everything inside this scope.
+            let base = FontId::proportional(12.0);
+            ui.style_mut().text_styles.insert(TextStyle::Body, base.clone());
+
+            Grid::new("explore_curr_elem_preview").num_columns(2).show(ui, |ui| {
+                ui.add_enabled(false, Label::new(RichText::new("template")));
+                if element.kind.is_empty() {
+                    ui.add_enabled(false, Label::new(RichText::new("(none)").italics()));
+                } else {
+                    ui.add(Label::new(element.kind.as_str()));
+                }
+                ui.end_row();
+
+                for attr in element.attributes() {
+                    ui.add_enabled(false, Label::new(RichText::new(attr.name)));
+                    ui.add(Label::new(attr.value));
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(4.0);
+            strong_separator(ui, ui.available_width());
+        });
+
+        if !element.id.is_zero() {
+            ui.add_space(6.0);
+            let resp = ui
+                .button(concatcp!(ICON_GRAPH, "  Graph"))
+                .on_hover_cursor(CursorIcon::PointingHand)
+                .on_hover_text("Show how this item is linked to the other ones.");
+            if resp.clicked() {
+                ctx.state.graph.set_root(element.id.clone());
+                ctx.state.set_curr_view(ViewName::Graph);
+            }
+        }
+    }
+}
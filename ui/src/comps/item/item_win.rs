@@ -1,12 +1,17 @@
 use crate::{
     CogsApp,
     comps::{
-        AppComponent, AttrsLinksTab, horiz_tab,
-        item::{render_add_attr, render_ask_window, render_attrs},
+        AppComponent, AttrsLinksTab, ConflictChoice, horiz_tab,
+        item::{item_violations, render_add_attr, render_add_link, render_ask_window, render_attrs, render_links},
+        render_history, render_save_conflict,
     },
     constants::EXPLORE_ELEMENT,
+    security::access_level_select,
+};
+use cogs_shared::domain::model::{
+    Action, Id,
+    meta::{Item, Kind},
 };
-use cogs_shared::domain::model::{Action, Id, meta::Item};
 use egui::{Align, Button, CursorIcon, Direction, Grid, Layout, Margin, Rect, Window};
 
 pub struct ItemWindow;
@@ -14,7 +19,7 @@ pub struct ItemWindow;
 pub(super) struct ItemWindowState {
     pub(super) id: Id,
     act_id: egui::Id,
-    action: Action,
+    pub(super) action: Action,
     title: &'static str,
 
     tab_id: egui::Id,
@@ -80,6 +85,23 @@ impl ItemWindow {
             ui.add_space(14.0);
             Self::render_tabs(ui, state);
             ui.add_space(16.0);
+            if state.tab == AttrsLinksTab::History {
+                ui.horizontal(|ui| {
+                    ui.add_space(20.0);
+                    ui.vertical(|ui| {
+                        if render_history(ctx, ui, &Kind::Item, &element.id) {
+                            cleanup(ctx, ui.ctx(), state);
+                        }
+                    });
+                    ui.add_space(20.0);
+                });
+                ui.add_space(8.0);
+                return;
+            }
+            if state.tab == AttrsLinksTab::Attributes {
+                Self::render_access_level(ctx, ui, element, state);
+                ui.add_space(10.0);
+            }
             Grid::new(format!("item_win_{}_grid", state.id))
                 .spacing([10.0, 10.0])
                 .num_columns(2)
@@ -89,22 +111,44 @@ impl ItemWindow {
                         AttrsLinksTab::Attributes => {
                             render_attrs(ctx, ui, element, state);
                         }
-                        AttrsLinksTab::Links => {}
+                        AttrsLinksTab::Links => {
+                            render_links(ctx, ui, element, state);
+                        }
+                        AttrsLinksTab::History => {}
                     }
                 });
             if state.action != Action::View {
                 ui.add_space(16.0);
-                render_add_attr(ctx, ui, element, state);
+                match state.tab {
+                    // The attributes of an item that has a template are only the ones that it declares.
+                    AttrsLinksTab::Attributes if element.tmpl_id.is_none() => render_add_attr(ctx, ui, element, state),
+                    AttrsLinksTab::Links => render_add_link(ctx, ui, element, state),
+                    AttrsLinksTab::Attributes | AttrsLinksTab::History => {}
+                }
             }
             ui.add_space(8.0);
         });
     }
 
+    fn render_access_level(ctx: &mut CogsApp, ui: &mut egui::Ui, element: &mut Item, state: &ItemWindowState) {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.add_enabled(false, egui::Label::new("Access Level"))
+                .on_hover_text("Only the users that are cleared for it see this item.");
+            let id = ui.id().with(("item_access_level", &state.id));
+            if let Some(access_level_id) =
+                access_level_select(ctx, ui, id, &element.access_level_id, state.action != Action::View)
+            {
+                element.access_level_id = access_level_id;
+            }
+        });
+    }
+
     fn render_tabs(ui: &mut egui::Ui, s: &mut ItemWindowState) {
         ui.scope(|ui| {
             ui.spacing_mut().button_padding = egui::vec2(4.0, 2.0);
 
-            let desired = egui::vec2(127.8, 21.7);
+            let desired = egui::vec2(if s.id.is_zero() { 127.8 } else { 185.0 }, 21.7);
             let full = ui.max_rect();
             let x = full.center().x - desired.x * 0.5;
             let y = ui.cursor().min.y;
@@ -125,6 +169,13 @@ impl ItemWindow {
                         s.tab = AttrsLinksTab::Links;
                         ui.ctx().data_mut(|d| d.insert_temp(s.tab_id, s.tab));
                     }
+                    if !s.id.is_zero() {
+                        let history_selected = s.tab == AttrsLinksTab::History;
+                        if horiz_tab(ui, "History", history_selected).clicked() {
+                            s.tab = AttrsLinksTab::History;
+                            ui.ctx().data_mut(|d| d.insert_temp(s.tab_id, s.tab));
+                        }
+                    }
                 },
             );
 
@@ -147,14 +198,20 @@ impl ItemWindow {
                     ectx.data_mut(|d| d.insert_temp(state.act_id, Action::Edit));
                 }
             } else {
-                let enabled = item.has_attributes();
+                let has_violations = !item_violations(app, item).is_empty();
+                let enabled = item.has_attributes() && !has_violations;
+                let disabled_hover_text = if has_violations {
+                    "Fix the attributes marked with (!)\nbefore saving the item."
+                } else {
+                    "Provide at least one attribute\nbefore saving the item."
+                };
                 let resp = ui
                     .add_enabled(enabled, Button::new("    Save    "))
                     .on_hover_cursor(CursorIcon::PointingHand)
-                    .on_disabled_hover_text("Provide at least one attribute\nbefore saving the item.");
+                    .on_disabled_hover_text(disabled_hover_text);
 
                 if resp.clicked() {
-                    // TODO: save the item
+                    app.state.data.save_item(item.clone(), ectx, app.sendr.clone());
                     cleanup(app, ectx, state);
                 }
             }
@@ -171,7 +228,7 @@ impl ItemWindow {
                     |ui| {
                         ui.add_space(18.0);
                         if ui.button("  Delete   ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
-                            // TODO: delete the item
+                            app.state.data.delete_item(item.id.clone(), ectx, app.sendr.clone());
                             cleanup(app, ectx, state);
                         }
                     },
@@ -179,6 +236,24 @@ impl ItemWindow {
             }
         });
     }
+
+    /// Resolve the conflict of the last save (if any), as chosen in its dialog.
+    fn render_save_conflict(app: &mut CogsApp, ectx: &egui::Context, item: &mut Item, state: &mut ItemWindowState) {
+        match render_save_conflict(app, ectx, &state.id) {
+            Some(ConflictChoice::Overwrite(version)) => {
+                item.version = version;
+                app.state.data.save_item(item.clone(), ectx, app.sendr.clone());
+                cleanup(app, ectx, state);
+            }
+            Some(ConflictChoice::Reload(current)) => {
+                if let Ok(current) = serde_json::from_value::<Item>(current) {
+                    app.state.explore.open_windows_item.insert(state.id.clone(), current);
+                }
+                ectx.data_mut(|d| d.insert_temp(state.act_id, Action::View));
+            }
+            None => {}
+        }
+    }
 }
 
 impl AppComponent for ItemWindow {
@@ -224,6 +299,8 @@ impl AppComponent for ItemWindow {
                 .response
                 .on_hover_cursor(CursorIcon::Grab);
             });
+
+        Self::render_save_conflict(ctx, ectx, &mut element, &mut state);
     }
 }
 
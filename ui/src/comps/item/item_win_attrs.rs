@@ -4,9 +4,12 @@ use crate::{
     comps::item::ItemWindowState,
     constants::{ICON_REORDER, ICON_X_DEL},
 };
-use cogs_shared::domain::model::{
-    Id,
-    meta::{Attr, AttributeValueType, DateAttribute, DateTimeAttribute, Item},
+use cogs_shared::domain::{
+    logic::{AttrViolation, validate_item},
+    model::{
+        Id,
+        meta::{Attr, AttributeValueType, DateAttribute, DateTimeAttribute, Item},
+    },
 };
 use egui::{Button, CollapsingHeader, Color32, ComboBox, CursorIcon, Grid, Label, RichText, Stroke, TextEdit, Ui};
 use std::collections::HashMap;
@@ -170,6 +173,7 @@ pub(super) fn render_attrs(app: &mut CogsApp, ui: &mut egui::Ui, item: &mut Item
     // If an attribute has invalid value (entered in is corresponding input value),
     // it will be added to this map and have the error included.
     let mut invalid_values = HashMap::<String, String>::new();
+    let violations = item_violations(app, item);
 
     ui.allocate_ui_with_layout(
         egui::vec2(ui.available_width(), list_h),
@@ -202,7 +206,22 @@ pub(super) fn render_attrs(app: &mut CogsApp, ui: &mut egui::Ui, item: &mut Item
                                     egui::vec2(row_w, row_h),
                                     egui::Layout::left_to_right(egui::Align::Center),
                                     |ui| {
-                                        ui.add_space(18.0);
+                                        let attr_violations: Vec<String> = item
+                                            .attribute(&ao.0, &ao.1)
+                                            .map(|a| {
+                                                violations
+                                                    .iter()
+                                                    .filter(|v| v.attr_name == a.name)
+                                                    .map(ToString::to_string)
+                                                    .collect()
+                                            })
+                                            .unwrap_or_default();
+                                        if attr_violations.is_empty() {
+                                            ui.add_space(18.0);
+                                        } else {
+                                            ui.add_sized([18.0, row_h], Label::new(RichText::new("(!)").color(Color32::RED)))
+                                                .on_hover_text_at_pointer(attr_violations.join("\n"));
+                                        }
                                         match ao.0 {
                                             ////////// Text //////////
                                             AttributeValueType::Text => {
@@ -587,6 +606,14 @@ pub(super) fn render_attrs(app: &mut CogsApp, ui: &mut egui::Ui, item: &mut Item
         },
     );
 
+    // The violations that cannot be shown next to an attribute (as it is missing).
+    for v in violations.iter().filter(|v| !item.has_attribute(&v.attr_name)) {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.label(RichText::new(format!("(!) {v}")).color(Color32::RED));
+        });
+    }
+
     // Apply removal after list render (avoid mutating while egui_dnd iterates).
     if let Some((vt, id)) = to_remove {
         item.attributes_order.retain(|(_, oid)| *oid != id);
@@ -650,3 +677,15 @@ fn update(item: &mut Item, app: &mut CogsApp, attr: Attr, value_type: Option<Att
         app.state.explore.open_windows_item.insert(item.id.clone(), item.clone());
     }
 }
+
+/// Validate the item against its template, using the same rules as the server does.
+/// If the template of the item is not (yet) fetched, no violations are reported.
+pub(super) fn item_violations(app: &CogsApp, item: &Item) -> Vec<AttrViolation> {
+    match &item.tmpl_id {
+        Some(tmpl_id) => match app.state.data.get_item_templates().into_iter().find(|it| &it.id == tmpl_id) {
+            Some(tmpl) => validate_item(item, Some(&tmpl)),
+            None => Vec::new(),
+        },
+        None => validate_item(item, None),
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{CogsApp, colors::faded_color, comps::item::ItemWindowState, constants::ICON_X_DEL, explore::open_item_window};
+use cogs_shared::{
+    domain::model::{
+        Action,
+        meta::{Item, ItemLink},
+    },
+    dtos::Page,
+};
+use egui::{Button, CollapsingHeader, ComboBox, CursorIcon, Grid, Label, RichText, TextEdit, Ui};
+use std::collections::BTreeMap;
+
+pub(super) fn render_links(app: &mut CogsApp, ui: &mut Ui, item: &Item, state: &ItemWindowState) {
+    if item.id.is_zero() {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.label(RichText::new("The links can be added after saving the item.").color(faded_color(ui)));
+        });
+        return;
+    }
+
+    let ectx = ui.ctx().clone();
+    if app.state.data.should_fetch_item_links(&item.id) {
+        app.state.data.fetch_item_links(item.id.clone(), &ectx, app.sendr.clone());
+    }
+
+    let Some(links) = app.state.data.get_item_links(&item.id).cloned() else {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.spinner();
+        });
+        return;
+    };
+
+    if links.is_empty() {
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            ui.label(RichText::new("No links.").color(faded_color(ui)));
+        });
+        return;
+    }
+
+    // The links are grouped by their name.
+    let mut grouped: BTreeMap<String, Vec<ItemLink>> = BTreeMap::new();
+    for link in links {
+        grouped.entry(link.name.clone()).or_default().push(link);
+    }
+
+    ui.vertical(|ui| {
+        for (name, links) in grouped {
+            ui.horizontal(|ui| {
+                ui.add_space(20.0);
+                ui.label(RichText::new(name).size(11.0).color(faded_color(ui)));
+            });
+            for link in links {
+                ui.horizontal(|ui| {
+                    ui.add_space(32.0);
+                    let label = if link.target_item_kind.is_empty() {
+                        link.target_item_listing_attr_value.clone()
+                    } else {
+                        format!("{}  ({})", link.target_item_listing_attr_value, link.target_item_kind)
+                    };
+                    if ui
+                        .link(label)
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .on_hover_text("Open the linked item.")
+                        .clicked()
+                    {
+                        open_item_window(app, &link.target_item_id, &ectx);
+                    }
+                    if state.action != Action::View
+                        && ui
+                            .add(Button::new(RichText::new(ICON_X_DEL).color(faded_color(ui))).frame(false))
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_hover_text("Delete this link.")
+                            .clicked()
+                    {
+                        app.state
+                            .data
+                            .delete_item_link(item.id.clone(), link.id.clone(), &ectx, app.sendr.clone());
+                    }
+                });
+            }
+            ui.add_space(4.0);
+        }
+    });
+}
+
+pub(super) fn render_add_link(app: &mut CogsApp, ui: &mut Ui, item: &Item, state: &ItemWindowState) {
+    const FORM_FIELD_W: f32 = 200.0;
+    if item.id.is_zero() {
+        return;
+    }
+
+    let (mut name, mut target_id) = app.state.explore.item_cu_add_link.get(&state.id).cloned().unwrap_or_default();
+
+    // For items created from a template, only the links declared by it can be added.
+    let tmpl_links = item.tmpl_id.as_ref().map(|tmpl_id| {
+        app.state
+            .data
+            .get_item_templates()
+            .into_iter()
+            .find(|it| &it.id == tmpl_id)
+            .map(|it| it.links)
+            .unwrap_or_default()
+    });
+    let target_tmpl_id = tmpl_links
+        .as_ref()
+        .and_then(|links| links.iter().find(|l| l.name == name).map(|l| l.item_template_id.clone()));
+    // The candidate targets are the items created from the link's item template,
+    // or any items (in case of the items created from scratch).
+    let targets_tmpl_id = match (&tmpl_links, target_tmpl_id) {
+        (None, _) => Some(None),
+        (Some(_), Some(tid)) => Some(Some(tid)),
+        (Some(_), None) => None,
+    };
+    let targets = targets_tmpl_id.and_then(|tmpl_id| {
+        if app.state.data.should_fetch_link_targets(&tmpl_id) {
+            app.state
+                .data
+                .fetch_link_targets(tmpl_id.clone(), ui.ctx(), app.sendr.clone());
+        }
+        app.state.data.get_link_targets(&tmpl_id).cloned()
+    });
+    let more_targets = targets.as_ref().is_some_and(Page::has_more);
+    let candidates: Vec<Item> = targets
+        .map(|page| page.items)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|i| i.id != item.id)
+        .collect();
+    if let Some(tid) = &target_id
+        && !candidates.iter().any(|c| &c.id == tid)
+    {
+        target_id = None;
+    }
+
+    ui.horizontal(|ui| {
+        ui.add_space(10.0);
+        CollapsingHeader::new(RichText::new("Add a link").color(faded_color(ui))).show(ui, |ui| {
+            ui.add_space(10.0);
+            Grid::new(format!("item_win_{}_add_link_grid", state.id))
+                .spacing([10.0, 10.0])
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.add_enabled(false, Label::new("   Name"));
+                    match &tmpl_links {
+                        Some(links) => {
+                            ComboBox::from_id_salt(format!("item_{}_add_link_name", state.id))
+                                .width(FORM_FIELD_W)
+                                .selected_text(name.clone())
+                                .show_ui(ui, |ui| {
+                                    for link in links {
+                                        ui.selectable_value(&mut name, link.name.clone(), link.name.clone());
+                                    }
+                                });
+                        }
+                        None => {
+                            ui.add_sized([FORM_FIELD_W, ui.spacing().interact_size.y], TextEdit::singleline(&mut name));
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.add_enabled(false, Label::new("  Target"));
+                    ui.horizontal(|ui| {
+                        let selected = target_id
+                            .as_ref()
+                            .and_then(|tid| candidates.iter().find(|c| &c.id == tid))
+                            .map(|c| c.listing_attr_value.clone())
+                            .unwrap_or_default();
+                        ComboBox::from_id_salt(format!("item_{}_add_link_target", state.id))
+                            .width(FORM_FIELD_W)
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for c in &candidates {
+                                    ui.selectable_value(&mut target_id, Some(c.id.clone()), c.listing_attr_value.clone());
+                                }
+                                if more_targets {
+                                    ui.label(
+                                        RichText::new(format!("Only the first {} items are listed.", candidates.len()))
+                                            .color(faded_color(ui)),
+                                    );
+                                }
+                            });
+
+                        let btn_enabled = !name.trim().is_empty() && target_id.is_some();
+                        if ui
+                            .add_enabled(btn_enabled, Button::new(" + "))
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_disabled_hover_text("Specify the name and the target item\nbefore adding the link.")
+                            .clicked()
+                            && let Some(tid) = target_id.take()
+                        {
+                            let link = ItemLink {
+                                name: name.trim().to_string(),
+                                source_item_id: item.id.clone(),
+                                target_item_id: tid,
+                                ..Default::default()
+                            };
+                            app.state.data.create_item_link(link, ui.ctx(), app.sendr.clone());
+                            name.clear();
+                        }
+                    });
+                    ui.end_row();
+                });
+
+            if let Some(err) = app.state.explore.item_cu_add_link_err.get(&item.id) {
+                ui.label(RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+            }
+        });
+    });
+
+    app.state.explore.item_cu_add_link.insert(state.id.clone(), (name, target_id));
+}
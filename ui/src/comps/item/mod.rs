@@ -2,7 +2,13 @@ mod item_win;
 pub use item_win::*;
 
 mod new_item_ask;
-pub(self) use new_item_ask::*;
+use new_item_ask::*;
 
 mod item_win_attrs;
-pub(self) use item_win_attrs::*;
+use item_win_attrs::*;
+
+mod item_win_links;
+use item_win_links::*;
+
+mod item_preview;
+pub use item_preview::*;
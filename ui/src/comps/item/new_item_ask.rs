@@ -6,7 +6,7 @@ use crate::{
     },
     state::SourceType,
 };
-use cogs_shared::domain::model::meta::ItemTemplate;
+use cogs_shared::domain::model::meta::{Item, ItemTemplate};
 use egui::{Align, Button, CursorIcon, Layout, Ui};
 
 pub(super) fn render_ask_window(ctx: &mut CogsApp, ui: &mut Ui, state: &mut ItemWindowState) {
@@ -106,6 +106,9 @@ pub(super) fn render_ask_footer(ctx: &mut CogsApp, ui: &mut egui::Ui, state: &mu
             .on_hover_cursor(CursorIcon::PointingHand)
             .on_disabled_hover_text("To continue, select either from scratch\nor choose an existing item template.");
         if resp.clicked() {
+            if let Some(tmpl) = &src_tmpl {
+                ctx.state.explore.open_windows_item.insert(state.id.clone(), Item::from(tmpl));
+            }
             ctx.state.explore.add_item_src_type_tmpl_cont = Some((src_type, src_tmpl, true)); // set to continue
         }
 
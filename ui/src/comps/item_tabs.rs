@@ -7,6 +7,7 @@ use crate::constants::CORNER_RADIUS;
 pub enum AttrsLinksTab {
     Attributes,
     Links,
+    History,
 }
 
 pub fn horiz_tab(ui: &mut egui::Ui, text: &str, selected: bool) -> egui::Response {
@@ -1,10 +1,10 @@
 use crate::{
     CogsApp,
-    comps::AppComponent,
+    comps::{AppComponent, get_usages, usages_text},
     constants::{EXPLORE_ELEMENT, ICON_ITEM_TMPL},
     utils::strong_separator,
 };
-use cogs_shared::domain::model::meta::ItemTemplate;
+use cogs_shared::domain::model::meta::{ItemTemplate, Kind};
 use egui::{Align, CursorIcon, FontId, Grid, Label, Layout, TextStyle};
 
 pub struct ItemTemplatePreview {}
@@ -20,6 +20,7 @@ impl AppComponent for ItemTemplatePreview {
             .data(|d| d.get_temp::<ItemTemplate>(egui::Id::from(EXPLORE_ELEMENT)))
             .clone()
             .unwrap_or_default();
+        let usages = get_usages(ctx, ui.ctx(), &Kind::ItemTemplate, &element.id);
 
         ui.label(format!("{} {}", ICON_ITEM_TMPL, element.name.as_str()))
             .on_hover_cursor(CursorIcon::Help)
@@ -80,6 +81,14 @@ impl AppComponent for ItemTemplatePreview {
                     ui.add(Label::new(links_str));
                 });
                 ui.end_row();
+
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add_enabled(false, Label::new("used by"));
+                });
+                ui.with_layout(Layout::top_down(Align::Min), |ui| {
+                    ui.add(Label::new(usages_text(&usages)));
+                });
+                ui.end_row();
             });
         });
 
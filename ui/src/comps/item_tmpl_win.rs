@@ -1,12 +1,19 @@
 use crate::{
     CogsApp,
     colors::faded_color,
-    comps::{AppComponent, AttrsLinksTab, horiz_tab},
+    comps::{
+        AppComponent, AttrsLinksTab, ConflictChoice, delete_disabled_text, get_usages, horiz_tab, render_history,
+        render_save_conflict,
+    },
     constants::{CORNER_RADIUS, EXPLORE_ELEMENT, FORM_FIELD_W},
+    security::access_level_select,
 };
-use cogs_shared::domain::model::{
-    Action, Id,
-    meta::{AttrTemplate, ItemTemplate, ItemTemplateLink},
+use cogs_shared::domain::{
+    logic::OrphanAttrsPolicy,
+    model::{
+        Action, Id,
+        meta::{AttrTemplate, ItemTemplate, ItemTemplateLink, Kind},
+    },
 };
 use egui::{
     Align, Button, CollapsingHeader, Color32, ComboBox, CursorIcon, Direction, Frame, Grid, Label, Layout, Margin, RichText,
@@ -114,6 +121,7 @@ impl ItemTemplateWindow {
                     .show(ui, |ui| {
                         Self::row_name(ui, ectx, element, s);
                         Self::row_description(ui, element, s);
+                        Self::row_access_level(app, ui, element, s);
                         Self::row_tabs(ui, ectx, s);
 
                         match s.tab {
@@ -128,19 +136,31 @@ impl ItemTemplateWindow {
                                 ui.label("");
                                 ui.end_row();
                             }
+                            AttrsLinksTab::History => {}
                         }
                     });
 
                 ui.add_space(8.0);
             });
 
-            if s.action != Action::View {
+            if s.tab == AttrsLinksTab::History {
+                ui.horizontal(|ui| {
+                    ui.add_space(14.0);
+                    ui.vertical(|ui| {
+                        if render_history(app, ui, &Kind::ItemTemplate, &element.id) {
+                            shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
+                        }
+                    });
+                    ui.add_space(14.0);
+                });
+            } else if s.action != Action::View {
                 ui.add_space(8.0);
                 ui.horizontal(|ui| {
                     ui.add_space(14.0);
                     match s.tab {
                         AttrsLinksTab::Attributes => Self::row_add_attr_template(app, ui, element, s),
                         AttrsLinksTab::Links => Self::row_add_link_template(app, ui, element, s),
+                        AttrsLinksTab::History => {}
                     }
                 });
             }
@@ -167,6 +187,14 @@ impl ItemTemplateWindow {
                     s.tab = AttrsLinksTab::Links;
                     ectx.data_mut(|d| d.insert_temp(s.tab_id, s.tab));
                 }
+
+                if !s.id.is_zero() {
+                    let history_selected = s.tab == AttrsLinksTab::History;
+                    if horiz_tab(ui, "History", history_selected).clicked() {
+                        s.tab = AttrsLinksTab::History;
+                        ectx.data_mut(|d| d.insert_temp(s.tab_id, s.tab));
+                    }
+                }
             });
         });
 
@@ -423,10 +451,21 @@ impl ItemTemplateWindow {
                     );
 
                 if resp.clicked() {
-                    app.state
-                        .data
-                        .save_item_template(element.clone(), ui.ctx(), app.sendr.clone());
-                    shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
+                    if element.id.is_zero() {
+                        app.state.data.save_item_template(
+                            element.clone(),
+                            OrphanAttrsPolicy::default(),
+                            ui.ctx(),
+                            app.sendr.clone(),
+                        );
+                        shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
+                    } else {
+                        // The change plan (of the existing items) is first reviewed.
+                        app.state.explore.item_template_cu_change_plan.remove(&element.id);
+                        app.state
+                            .data
+                            .plan_item_template_change(element.clone(), ui.ctx(), app.sendr.clone());
+                    }
                 }
             }
 
@@ -441,7 +480,13 @@ impl ItemTemplateWindow {
                     Layout::from_main_dir_and_cross_align(Direction::LeftToRight, Align::Min),
                     |ui| {
                         ui.add_space(18.0);
-                        if ui.button("  Delete  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                        let usages = get_usages(app, ectx, &Kind::ItemTemplate, &element.id);
+                        let enabled = usages.as_ref().is_some_and(|deps| deps.is_empty());
+                        let resp = ui
+                            .add_enabled(enabled, Button::new("  Delete  "))
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_disabled_hover_text(delete_disabled_text(&usages));
+                        if resp.clicked() {
                             app.state.data.delete_item_template(s.id.clone(), ectx, app.sendr.clone());
                             shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
                         }
@@ -451,6 +496,136 @@ impl ItemTemplateWindow {
         });
     }
 
+    /// Resolve the conflict of the last save (if any), as chosen in its dialog.
+    /// Overwriting it goes through the change plan again, since the existing items may have changed meanwhile.
+    fn render_save_conflict(app: &mut CogsApp, ectx: &egui::Context, element: &mut ItemTemplate, s: &FormUiState) {
+        match render_save_conflict(app, ectx, &s.id) {
+            Some(ConflictChoice::Overwrite(version)) => {
+                element.version = version;
+                ectx.data_mut(|d| d.insert_temp(s.act_id, Action::Edit));
+                app.state.explore.item_template_cu_change_plan.remove(&element.id);
+                app.state
+                    .data
+                    .plan_item_template_change(element.clone(), ectx, app.sendr.clone());
+            }
+            Some(ConflictChoice::Reload(current)) => {
+                if let Ok(current) = serde_json::from_value::<ItemTemplate>(current) {
+                    *element = current;
+                }
+                ectx.data_mut(|d| d.insert_temp(s.act_id, Action::View));
+            }
+            None => {}
+        }
+    }
+
+    /// Once the plan of changing the existing items is received, it is either applied directly
+    /// (if no items are affected) or shown for confirmation.
+    fn render_change_plan(app: &mut CogsApp, ectx: &egui::Context, element: &ItemTemplate, s: &FormUiState) {
+        let Some(plan) = app.state.explore.item_template_cu_change_plan.get(&element.id).cloned() else {
+            return;
+        };
+        let plan = match plan {
+            Ok(plan) if plan.is_empty() || plan.affected_items == 0 => {
+                app.state.explore.item_template_cu_change_plan.remove(&element.id);
+                app.state
+                    .data
+                    .save_item_template(element.clone(), OrphanAttrsPolicy::default(), ectx, app.sendr.clone());
+                shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
+                return;
+            }
+            Ok(plan) => plan,
+            Err(err) => {
+                egui::Modal::new(egui::Id::new(format!("item_tmpl_form_{}_plan", element.id))).show(ectx, |ui| {
+                    ui.set_width(280.0);
+                    ui.label(RichText::new(format!("Failed to check the impact on existing items:\n{err}")).color(Color32::RED));
+                    ui.add_space(10.0);
+                    if ui.button("  Back  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                        app.state.explore.item_template_cu_change_plan.remove(&element.id);
+                    }
+                });
+                return;
+            }
+        };
+
+        let mut orphans = app
+            .state
+            .explore
+            .item_template_cu_orphans
+            .get(&element.id)
+            .copied()
+            .unwrap_or_default();
+        let (mut confirmed, mut cancelled) = (false, false);
+
+        egui::Modal::new(egui::Id::new(format!("item_tmpl_form_{}_plan", element.id))).show(ectx, |ui| {
+            ui.set_width(300.0);
+            ui.vertical_centered(|ui| {
+                ui.heading(RichText::new("Update existing items").size(14.0));
+            });
+            ui.add_space(10.0);
+            ui.label(format!(
+                "{} item(s) created from this template will be updated:",
+                plan.affected_items
+            ));
+            ui.add_space(6.0);
+            for a in &plan.added_attrs {
+                ui.label(format!("+ '{}' is added, with '{}' as value", a.name, a.default_value));
+            }
+            for tc in &plan.type_changes {
+                ui.label(format!(
+                    "~ '{}' changes from {} to {} (invalid values become '{}')",
+                    tc.to.name, tc.from.value_type, tc.to.value_type, tc.to.default_value
+                ));
+            }
+            for a in &plan.removed_attrs {
+                ui.label(format!(
+                    "- '{}' is {}",
+                    a.name,
+                    if orphans == OrphanAttrsPolicy::Drop {
+                        "dropped"
+                    } else {
+                        "detached"
+                    }
+                ));
+            }
+            for name in &plan.removed_link_names {
+                ui.label(format!("- the '{name}' links are deleted"));
+            }
+            if !plan.removed_attrs.is_empty() {
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.label("Removed attributes:");
+                    ui.radio_value(&mut orphans, OrphanAttrsPolicy::Detach, "detach")
+                        .on_hover_text("Keep them as free-form attributes of the items, to be removed on their next save.");
+                    ui.radio_value(&mut orphans, OrphanAttrsPolicy::Drop, "drop")
+                        .on_hover_text("Remove them (and their values) from the items.");
+                });
+            }
+            ui.add_space(16.0);
+            ui.horizontal(|ui| {
+                if ui.button("  Back  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    cancelled = true;
+                }
+                ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                    if ui.button("  Save  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                        confirmed = true;
+                    }
+                });
+            });
+        });
+
+        app.state.explore.item_template_cu_orphans.insert(element.id.clone(), orphans);
+        if confirmed || cancelled {
+            app.state.explore.item_template_cu_change_plan.remove(&element.id);
+            app.state.explore.item_template_cu_orphans.remove(&element.id);
+        }
+        if confirmed {
+            app.state
+                .data
+                .save_item_template(element.clone(), orphans, ectx, app.sendr.clone());
+            shutdown(app, ectx, &s.id, s.act_id, s.focus_id);
+        }
+    }
+
     fn row_name(ui: &mut egui::Ui, ectx: &egui::Context, element: &mut ItemTemplate, s: &mut FormUiState) {
         const FORM_FIELD_W: f32 = 240.0;
 
@@ -480,6 +655,16 @@ impl ItemTemplateWindow {
         ui.end_row();
     }
 
+    fn row_access_level(app: &mut CogsApp, ui: &mut egui::Ui, element: &mut ItemTemplate, s: &FormUiState) {
+        ui.add_enabled(false, Label::new("        Access Level"))
+            .on_hover_text("The default access level of the items that are created from it.");
+        let id = ui.id().with(("item_tmpl_access_level", &s.id));
+        if let Some(access_level_id) = access_level_select(app, ui, id, &element.access_level_id, !s.action.is_view()) {
+            element.access_level_id = access_level_id;
+        }
+        ui.end_row();
+    }
+
     fn row_listing_attr(ui: &mut egui::Ui, element: &mut ItemTemplate, s: &FormUiState) {
         ui.add_enabled(false, Label::new("Listing Attribute"));
         if s.action.is_view() {
@@ -753,6 +938,9 @@ impl AppComponent for ItemTemplateWindow {
                 .response
                 .on_hover_cursor(CursorIcon::Grab);
             });
+
+        Self::render_save_conflict(ctx, ectx, &mut element, &s);
+        Self::render_change_plan(ctx, ectx, &element, &s);
     }
 }
 
@@ -0,0 +1,361 @@
+use crate::{
+    CogsApp,
+    colors::faded_color,
+    comps::{AppComponent, Dropdown, DropdownItem, DropdownStyle},
+};
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::parse_csv,
+        model::{Id, meta::ItemTemplate},
+    },
+    dtos::{ItemsImportReport, ItemsImportRequest},
+};
+use egui::{Align, Button, CursorIcon, Grid, Label, Layout, Margin, RichText, ScrollArea, TextEdit, Window};
+
+/// The state of the wizard of importing items from a CSV text.
+#[derive(Clone, Debug, Default)]
+pub struct ItemsImportState {
+    /// The item template that the items are created from.
+    pub tmpl: Option<ItemTemplate>,
+
+    pub csv: String,
+
+    /// The columns (of the CSV header row), each one with the attribute template that it's mapped to (if any).
+    pub columns: Vec<(String, Option<Id>)>,
+
+    /// Whether the columns are being mapped, as the second step (after choosing the item template and the CSV text).
+    pub mapping: bool,
+
+    /// Whether a (dry run or real) import is in progress.
+    pub pending: bool,
+
+    /// The outcome of the last (dry run or real) import.
+    pub report: Option<AppResult<ItemsImportReport>>,
+}
+
+impl ItemsImportState {
+    /// Get the columns of the CSV header row, each one mapped to the attribute template
+    /// (of the item template) with the same name (case insensitive), if any.
+    fn auto_map_columns(&self) -> Result<Vec<(String, Option<Id>)>, String> {
+        let rows = parse_csv(&self.csv)?;
+        let Some((_, header)) = rows.into_iter().next() else {
+            return Err("The CSV text has no header row.".to_string());
+        };
+        let attrs = self.tmpl.as_ref().map(|t| t.attributes.as_slice()).unwrap_or_default();
+        Ok(header
+            .into_iter()
+            .map(|column| {
+                let attr_id = attrs
+                    .iter()
+                    .find(|at| at.name.eq_ignore_ascii_case(column.trim()))
+                    .map(|at| at.id.clone());
+                (column, attr_id)
+            })
+            .collect())
+    }
+
+    fn request(&self, dry_run: bool) -> ItemsImportRequest {
+        ItemsImportRequest {
+            tmpl_id: self.tmpl.as_ref().map(|t| t.id.clone()).unwrap_or_default(),
+            csv: self.csv.clone(),
+            columns: self
+                .columns
+                .iter()
+                .filter_map(|(column, attr_id)| attr_id.clone().map(|id| (column.clone(), id)))
+                .collect(),
+            dry_run,
+        }
+    }
+}
+
+/// The wizard of importing items, from the rows of a CSV text, into an item template.
+pub struct ItemsImportWindow;
+
+impl ItemsImportWindow {
+    fn render_header(ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let w = ui.available_width();
+            ui.allocate_ui_with_layout(
+                egui::vec2(w.max(0.0), 0.0),
+                egui::Layout::top_down(egui::Align::Center),
+                |ui| {
+                    ui.add_enabled(false, Label::new(RichText::new("Import Items (CSV)").size(13.0)));
+                },
+            );
+        });
+        ui.add_space(10.0);
+    }
+
+    /// The first step: choose the item template and provide the CSV text.
+    fn render_source(app: &mut CogsApp, ui: &mut egui::Ui, state: &mut ItemsImportState) {
+        if !app.state.data.has_fetched_item_templates() {
+            app.state.data.fetch_all_item_templates(ui.ctx(), app.sendr.clone());
+        }
+
+        // A dropped file replaces the CSV text.
+        let dropped = ui.ctx().input(|i| i.raw.dropped_files.first().cloned());
+        if let Some(file) = dropped {
+            match (&file.bytes, &file.path) {
+                (Some(bytes), _) => state.csv = String::from_utf8_lossy(bytes).into_owned(),
+                (None, Some(path)) => match std::fs::read_to_string(path) {
+                    Ok(text) => state.csv = text,
+                    Err(err) => log::error!("[ItemsImportWindow] Failed to read file {}: {err}", path.display()),
+                },
+                (None, None) => {}
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.label("Item template");
+            let templates: Vec<DropdownItem<Option<ItemTemplate>>> = app
+                .state
+                .data
+                .get_item_templates()
+                .iter()
+                .map(|it| DropdownItem::new(it.name.clone(), Some(it.clone())))
+                .collect();
+            if let Some(tmpl) = Dropdown::show(
+                ui,
+                ui.id().with("items_import_tmpl"),
+                &state.tmpl,
+                templates.as_slice(),
+                DropdownStyle {
+                    min_width: 200.0,
+                    ..Default::default()
+                },
+            ) {
+                state.tmpl = tmpl;
+            }
+        });
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.vertical(|ui| {
+                ui.label("CSV text, having a header row with the names of the columns");
+                ScrollArea::vertical()
+                    .id_salt("items_import_csv")
+                    .max_height(220.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            TextEdit::multiline(&mut state.csv)
+                                .code_editor()
+                                .desired_rows(10)
+                                .desired_width(440.0)
+                                .hint_text("Paste it here, or drop a .csv file on the window."),
+                        );
+                    });
+            });
+        });
+    }
+
+    /// The second step: map the columns to the attributes of the item template.
+    fn render_mapping(ui: &mut egui::Ui, state: &mut ItemsImportState) {
+        let Some(tmpl) = state.tmpl.clone() else {
+            return;
+        };
+        let mut attrs: Vec<DropdownItem<Option<Id>>> = vec![DropdownItem {
+            italic: true,
+            ..DropdownItem::new("(skip)", None)
+        }];
+        attrs.extend(
+            tmpl.attributes
+                .iter()
+                .map(|at| DropdownItem::new(format!("{} ({})", at.name, at.value_type), Some(at.id.clone()))),
+        );
+
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.label(format!("Map the columns to the attributes of '{}':", tmpl.name));
+        });
+        ui.add_space(6.0);
+        ui.horizontal(|ui| {
+            ui.add_space(18.0);
+            ScrollArea::vertical()
+                .id_salt("items_import_columns")
+                .max_height(220.0)
+                .show(ui, |ui| {
+                    Grid::new("items_import_columns_grid")
+                        .num_columns(2)
+                        .spacing([12.0, 6.0])
+                        .show(ui, |ui| {
+                            for (idx, (column, attr_id)) in state.columns.iter_mut().enumerate() {
+                                ui.add_enabled(false, Label::new(column.as_str()));
+                                if let Some(id) = Dropdown::show(
+                                    ui,
+                                    ui.id().with(("items_import_column", idx)),
+                                    attr_id,
+                                    attrs.as_slice(),
+                                    DropdownStyle {
+                                        min_width: 220.0,
+                                        ..Default::default()
+                                    },
+                                ) {
+                                    *attr_id = id;
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+
+    fn render_report(ui: &mut egui::Ui, state: &ItemsImportState) {
+        let Some(report) = &state.report else {
+            return;
+        };
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            ui.add_space(14.0);
+            ui.vertical(|ui| match report {
+                Err(err) => {
+                    ui.label(RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+                }
+                Ok(report) if !report.errors.is_empty() => {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} row(s) are not valid, thus nothing is imported:",
+                            report.errors.len()
+                        ))
+                        .color(ui.visuals().error_fg_color),
+                    );
+                    ScrollArea::vertical()
+                        .id_salt("items_import_errors")
+                        .max_height(160.0)
+                        .show(ui, |ui| {
+                            for error in &report.errors {
+                                ui.add(Label::new(format!("line {}: {}", error.line, error.message)).wrap());
+                            }
+                        });
+                }
+                Ok(report) if report.dry_run => {
+                    ui.label(format!("All rows are valid, {} item(s) can be imported.", report.imported));
+                }
+                Ok(report) => {
+                    ui.label(format!("{} item(s) were imported.", report.imported));
+                }
+            });
+        });
+    }
+
+    fn render_footer_buttons(app: &mut CogsApp, ui: &mut egui::Ui, state: &mut ItemsImportState) -> bool {
+        let mut close = false;
+        let imported = matches!(&state.report, Some(Ok(report)) if !report.dry_run && report.errors.is_empty());
+
+        ui.add_space(16.0);
+        ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+            ui.add_space(18.0);
+            if imported {
+                if ui.button("  Close  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    close = true;
+                }
+                return;
+            }
+
+            if state.mapping {
+                let mapped = state.columns.iter().any(|(_, attr_id)| attr_id.is_some());
+                let resp = ui
+                    .add_enabled(mapped && !state.pending, Button::new("  Import  "))
+                    .on_hover_cursor(CursorIcon::PointingHand)
+                    .on_hover_text("Either all the rows are imported, or none of them.")
+                    .on_disabled_hover_text("Map at least one column.");
+                if resp.clicked() {
+                    state.pending = true;
+                    app.state.data.import_items(state.request(false), ui.ctx(), app.sendr.clone());
+                }
+                ui.add_space(8.0);
+                let resp = ui
+                    .add_enabled(mapped && !state.pending, Button::new("  Check  "))
+                    .on_hover_cursor(CursorIcon::PointingHand)
+                    .on_hover_text("Check the rows, without importing them.")
+                    .on_disabled_hover_text("Map at least one column.");
+                if resp.clicked() {
+                    state.pending = true;
+                    app.state.data.import_items(state.request(true), ui.ctx(), app.sendr.clone());
+                }
+                ui.add_space(8.0);
+                if ui.button("  Back  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    state.mapping = false;
+                    state.report = None;
+                }
+            } else {
+                let enabled = state.tmpl.is_some() && !state.csv.trim().is_empty();
+                let resp = ui
+                    .add_enabled(enabled, Button::new("  Continue  "))
+                    .on_hover_cursor(CursorIcon::PointingHand)
+                    .on_disabled_hover_text("Choose an item template,\nand provide the CSV text.");
+                if resp.clicked() {
+                    match state.auto_map_columns() {
+                        Ok(columns) => {
+                            state.columns = columns;
+                            state.mapping = true;
+                            state.report = None;
+                        }
+                        Err(err) => state.report = Some(Err(AppError::InvalidInput(err))),
+                    }
+                }
+            }
+
+            ui.add_space(8.0);
+            if ui.button("  Cancel  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                close = true;
+            }
+            if state.pending {
+                ui.spinner();
+            }
+        });
+        close
+    }
+}
+
+impl AppComponent for ItemsImportWindow {
+    type Context = CogsApp;
+
+    /// It shows the wizard of importing items, based on `explore.items_import` state.
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        let Some(mut state) = ctx.state.explore.items_import.take() else {
+            return;
+        };
+        let ectx = ui.ctx().clone();
+        let mut close = false;
+
+        Window::new("items_import_win")
+            .title_bar(false)
+            .resizable(false)
+            .min_width(480.0)
+            .max_width(480.0)
+            .frame(egui::Frame::window(&ectx.global_style()).inner_margin(Margin::ZERO))
+            .show(&ectx, |ui| {
+                ui.vertical(|ui| {
+                    Self::render_header(ui);
+                    if state.mapping {
+                        Self::render_mapping(ui, &mut state);
+                    } else {
+                        Self::render_source(ctx, ui, &mut state);
+                    }
+                    if state.report.is_none() && !state.mapping {
+                        ui.add_space(4.0);
+                        ui.horizontal(|ui| {
+                            ui.add_space(14.0);
+                            ui.label(
+                                RichText::new(
+                                    "The items are created from the item template, with the values of the mapped columns.",
+                                )
+                                .color(faded_color(ui)),
+                            );
+                        });
+                    }
+                    Self::render_report(ui, &state);
+                    close = Self::render_footer_buttons(ctx, ui, &mut state);
+                    ui.add_space(10.0);
+                })
+                .response
+                .on_hover_cursor(CursorIcon::Grab);
+            });
+
+        if !close {
+            ctx.state.explore.items_import = Some(state);
+        }
+    }
+}
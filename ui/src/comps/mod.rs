@@ -6,12 +6,18 @@ pub use attr_templ_preview::*;
 mod attr_tmpl_win;
 pub use attr_tmpl_win::*;
 
+mod bundle_import_win;
+pub use bundle_import_win::*;
+
 mod dropdown;
 pub use dropdown::*;
 
 mod header;
 pub use header::*;
 
+mod history;
+pub use history::*;
+
 mod footer;
 pub use footer::*;
 
@@ -24,6 +30,9 @@ pub use item_tmpl_preview::*;
 mod item;
 pub use item::*;
 
+mod items_import_win;
+pub use items_import_win::*;
+
 mod item_tmpl_win;
 pub use item_tmpl_win::*;
 
@@ -33,6 +42,9 @@ pub use modal::*;
 mod password_input;
 pub use password_input::*;
 
+mod save_conflict;
+pub use save_conflict::*;
+
 mod user_menu;
 pub use user_menu::*;
 
@@ -42,6 +54,9 @@ pub use menu_utils::*;
 mod symbols;
 pub use symbols::*;
 
+mod usages;
+pub use usages::*;
+
 /////////////////////////////////////////////////////////
 
 use crate::messages::UiMessage;
@@ -0,0 +1,112 @@
+use crate::{CogsApp, colors::faded_color};
+use cogs_shared::domain::{
+    logic::diff_snapshots,
+    model::{Id, meta::Kind},
+};
+use egui::{CursorIcon, Grid, Label, RichText, ScrollArea, Ui};
+use serde_json::Value;
+
+/// A save that was refused since the element was changed by someone else meanwhile.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaveConflict {
+    pub kind: Kind,
+    /// The (unsaved) copy of the element, as changed by the user.
+    pub mine: Value,
+    /// The current (saved) copy of the element, as changed by someone else.
+    pub current: Value,
+    /// Whether the two copies are shown side by side.
+    pub compare: bool,
+}
+
+/// The way that a save conflict is resolved.
+pub enum ConflictChoice {
+    /// Save the user's copy anyway, on top of the provided (current) version.
+    Overwrite(i32),
+    /// Drop the user's changes, and continue with the provided (current) copy.
+    Reload(Value),
+}
+
+/// Render the dialog of the save conflict of the element (if any),
+/// offering to overwrite it, to reload it, or to compare the two copies.
+/// It returns the choice of the user, once made (and the conflict is then considered resolved).
+pub fn render_save_conflict(app: &mut CogsApp, ectx: &egui::Context, id: &Id) -> Option<ConflictChoice> {
+    let conflict = app.state.explore.save_conflicts.get_mut(id)?;
+    let mut choice = None;
+
+    egui::Modal::new(egui::Id::new(format!("save_conflict_{id}"))).show(ectx, |ui| {
+        ui.set_max_width(if conflict.compare { 560.0 } else { 360.0 });
+        ui.heading("Save conflict");
+        ui.add_space(10.0);
+        ui.label("It was changed by someone else since you opened it, so your changes were not saved.");
+        ui.add_space(10.0);
+
+        if conflict.compare {
+            render_compare(ui, id, conflict);
+            ui.add_space(10.0);
+        }
+
+        ui.horizontal(|ui| {
+            let resp = ui
+                .button("  Overwrite  ")
+                .on_hover_cursor(CursorIcon::PointingHand)
+                .on_hover_text("Save your changes, replacing theirs.");
+            if resp.clicked() {
+                let version = conflict.current.get("version").and_then(Value::as_i64).unwrap_or_default();
+                choice = Some(ConflictChoice::Overwrite(version as i32));
+            }
+            let resp = ui
+                .button("  Reload  ")
+                .on_hover_cursor(CursorIcon::PointingHand)
+                .on_hover_text("Drop your changes, and continue with theirs.");
+            if resp.clicked() {
+                choice = Some(ConflictChoice::Reload(conflict.current.clone()));
+            }
+            let label = if conflict.compare { "  Hide  " } else { "  Compare  " };
+            if ui.button(label).on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                conflict.compare = !conflict.compare;
+            }
+        });
+    });
+
+    if choice.is_some() {
+        app.state.explore.save_conflicts.remove(id);
+    }
+    choice
+}
+
+/// Render the fields that differ between the two copies, side by side.
+fn render_compare(ui: &mut Ui, id: &Id, conflict: &SaveConflict) {
+    let changes = match diff_snapshots(&conflict.kind, &conflict.current, &conflict.mine) {
+        Ok(changes) => changes,
+        Err(err) => {
+            ui.label(RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+            return;
+        }
+    };
+    if changes.is_empty() {
+        ui.label(RichText::new("There are no differences.").color(faded_color(ui)));
+        return;
+    }
+
+    ScrollArea::vertical()
+        .max_height(300.0)
+        .auto_shrink([false, true])
+        .show(ui, |ui| {
+            Grid::new(format!("save_conflict_{id}_compare"))
+                .num_columns(3)
+                .spacing([12.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("Field").strong());
+                    ui.label(RichText::new("Yours").strong());
+                    ui.label(RichText::new("Theirs").strong());
+                    ui.end_row();
+                    for change in changes {
+                        ui.add_enabled(false, Label::new(change.field));
+                        ui.add(Label::new(change.new.unwrap_or_else(|| "-".to_string())).wrap());
+                        ui.add(Label::new(change.old.unwrap_or_else(|| "-".to_string())).wrap());
+                        ui.end_row();
+                    }
+                });
+        });
+}
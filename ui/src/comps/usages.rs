@@ -0,0 +1,35 @@
+use crate::CogsApp;
+use cogs_shared::domain::model::{
+    Id,
+    meta::{Dependency, Kind},
+};
+
+/// Get the elements that use the template (of the provided kind and id).
+/// If they are not yet known, they are fetched and `None` is returned meanwhile.
+pub fn get_usages(app: &mut CogsApp, ectx: &egui::Context, kind: &Kind, id: &Id) -> Option<Vec<Dependency>> {
+    //
+    if id.is_zero() {
+        return Some(vec![]);
+    }
+    if app.state.data.should_fetch_usages(id) {
+        app.state.data.fetch_usages(kind, id.clone(), ectx, app.sendr.clone());
+    }
+    app.state.data.get_usages(id).cloned()
+}
+
+/// The (multi-line) text that lists the elements that use a template.
+pub fn usages_text(usages: &Option<Vec<Dependency>>) -> String {
+    match usages {
+        None => "...".to_string(),
+        Some(deps) if deps.is_empty() => "none".to_string(),
+        Some(deps) => deps.iter().map(|d| d.to_string()).collect::<Vec<String>>().join("\n"),
+    }
+}
+
+/// The hover text of a (disabled) Delete button of a template.
+pub fn delete_disabled_text(usages: &Option<Vec<Dependency>>) -> String {
+    match usages {
+        None => "Checking where it is used ...".to_string(),
+        Some(_) => format!("It cannot be deleted, since it is used by:\n{}", usages_text(usages)),
+    }
+}
@@ -8,6 +8,8 @@ pub const ICON_HOME: &str = "\u{2771}"; //
 // pub const ICON_EXPLORE: &str = "\u{26EF}"; // a symbol for lighthouse (⛯)
 // pub const ICON_EXPLORE: &str = "\u{221E}"; // infinity symbol
 pub const ICON_EXPLORE: &str = "\u{E619}";
+// From the material icons font.
+pub const ICON_GRAPH: &str = "\u{E9F4}";
 pub const ICON_ITEM: &str = "\u{E0CF}";
 pub const ICON_RARROW: &str = "\u{F44A}";
 // TODO: To be used.
@@ -22,6 +24,9 @@ pub const ICON_HELP: &str = "\u{E66A}";
 pub const ICON_TMPL: &str = "\u{E64E}";
 pub const ICON_REORDER: &str = "\u{E612}";
 pub const ICON_X_DEL: &str = "\u{F530}";
+// From the material icons font.
+pub const ICON_IMPORT: &str = "\u{E9FC}";
+pub const ICON_EXPORT: &str = "\u{F090}";
 
 ///////////////////////////
 // General app constants //
@@ -29,6 +34,9 @@ pub const ICON_X_DEL: &str = "\u{F530}";
 
 pub const APP_KEY: &str = "cogs";
 
+/// The header that carries the id of the user session, as expected by the server.
+pub const SESSION_HEADER: &str = "Authorization";
+
 pub const CORNER_RADIUS: f32 = 4.0;
 
 pub const FORM_FIELD_W: f32 = 240.0;
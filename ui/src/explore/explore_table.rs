@@ -3,9 +3,12 @@ use crate::{
     comps::AppComponent,
     explore::{ExploreCategory, ExploreKind, ExploreViewState, TemplateTypeFilter},
 };
-use cogs_shared::domain::model::{
-    AccessLevel,
-    meta::{AttrTemplate, ItemTemplate, Kind},
+use cogs_shared::{
+    domain::model::{
+        AccessLevel, UserAccount,
+        meta::{AttrTemplate, Item, ItemTemplate, Kind},
+    },
+    dtos::{SortDirection, TrashEntry},
 };
 use egui::{Color32, CursorIcon, RichText, Sense, Ui};
 use egui_extras::{Column, TableBody, TableBuilder};
@@ -20,8 +23,19 @@ impl AppComponent for ExploreTable {
         // Fetch data.
         match ctx.state.explore.category {
             ExploreCategory::Items => {
-                // TODO: fetch items when implemented.
-                // ctx.state.data.fetch_all_items(ui.ctx(), ctx.sendr.clone());
+                // The items are fetched (page by page) for the selected item template, if any.
+                let tmpl_id = match &ctx.state.explore.kind {
+                    ExploreKind::ItemTemplateId(tmpl_id) => Some(tmpl_id.clone()),
+                    _ => None,
+                };
+                ctx.state.data.set_items_tmpl_id(tmpl_id);
+                if ctx.state.data.should_fetch_items() {
+                    ctx.state.data.fetch_items_page(ui.ctx(), ctx.sendr.clone());
+                }
+                // The item templates are needed for the per-template filter.
+                if !ctx.state.data.has_fetched_item_templates() {
+                    ctx.state.data.fetch_all_item_templates(ui.ctx(), ctx.sendr.clone());
+                }
             }
             ExploreCategory::Templates => {
                 if !ctx.state.data.has_fetched_all() {
@@ -34,18 +48,32 @@ impl AppComponent for ExploreTable {
                 if ctx.state.data.should_fetch_access_levels() {
                     ctx.state.data.fetch_all_access_levels(ui.ctx(), ctx.sendr.clone());
                 }
+                if ctx.state.data.should_fetch_users() {
+                    ctx.state.data.fetch_all_users(ui.ctx(), ctx.sendr.clone());
+                }
+            }
+            ExploreCategory::Trash => {
+                if ctx.state.data.should_fetch_trash() {
+                    ctx.state.data.fetch_trash(ui.ctx(), ctx.sendr.clone());
+                }
             }
         }
 
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.add_space(10.0);
+            let ectx = ui.ctx().clone();
             let available_height = ui.available_height();
             let available_width = ui.available_width();
             let w = ctx.state.explore.table_col_widths.unwrap_or([40.0, 120.0, 150.0]);
 
             if matches!(ctx.state.explore.category, ExploreCategory::Security) {
-                let access_levels = access_level_rows_filtered(ctx);
-                show_access_levels(ctx, ui, &access_levels, available_height, available_width);
+                let (access_levels, users) = security_rows_filtered(ctx);
+                show_security(ctx, ui, &access_levels, &users, available_height, available_width);
+                return;
+            }
+            if matches!(ctx.state.explore.category, ExploreCategory::Trash) {
+                let trash = ctx.state.data.get_trash().to_vec();
+                show_trash(ctx, ui, &trash, available_height);
                 return;
             }
 
@@ -70,12 +98,18 @@ impl AppComponent for ExploreTable {
                 .max_scroll_height(available_height)
                 .sense(Sense::click());
 
+            // The items can be sorted by clicking the type and name headers.
+            let items_sort = match ctx.state.explore.category {
+                ExploreCategory::Items => Some(ctx.state.data.get_items_sort()),
+                _ => None,
+            };
+            let mut sort_by = None;
             let table = table.header(20.0, |mut header| {
                 header.col(|ui| {
-                    ui.label(RichText::new("type").color(Color32::GRAY));
+                    show_header(ui, "type", "kind", items_sort, &mut sort_by);
                 });
                 header.col(|ui| {
-                    ui.label(RichText::new("name").color(Color32::GRAY));
+                    show_header(ui, "name", "name", items_sort, &mut sort_by);
                 });
                 header.col(|ui| {
                     ui.label(RichText::new("description").color(Color32::GRAY));
@@ -91,10 +125,19 @@ impl AppComponent for ExploreTable {
 
             match ctx.state.explore.category {
                 ExploreCategory::Items => {
+                    let items = ctx.state.data.get_items();
+                    let mut reached_end = false;
+
                     table.body(|body| {
-                        // todo: render items rows here.
                         remember_widths(&body, &mut ctx.state.explore);
+
+                        reached_end = show_items(ctx, body, &items);
                     });
+
+                    // The next page is fetched once the last fetched item is shown.
+                    if reached_end && ctx.state.data.should_fetch_more_items() {
+                        ctx.state.data.fetch_items_page(&ectx, ctx.sendr.clone());
+                    }
                 }
                 ExploreCategory::Templates => {
                     let (item_templates, attr_templates) = template_rows_filtered(ctx);
@@ -106,17 +149,50 @@ impl AppComponent for ExploreTable {
                         remember_widths(&body, &mut ctx.state.explore);
                     });
                 }
-                ExploreCategory::Security => {}
+                ExploreCategory::Security | ExploreCategory::Trash => {}
+            }
+
+            if let Some(sort) = sort_by {
+                ctx.state.data.set_items_sort(sort);
             }
         });
     }
 }
 
-fn access_level_rows_filtered(ctx: &CogsApp) -> Vec<AccessLevel> {
+/// Show a header of the table. If the items are shown, it can be clicked to sort them by `sort` field,
+/// in which case the sort direction is also shown.
+fn show_header(
+    ui: &mut Ui,
+    label: &str,
+    sort: &'static str,
+    items_sort: Option<Option<(&str, SortDirection)>>,
+    sort_by: &mut Option<&'static str>,
+) {
+    let Some(curr_sort) = items_sort else {
+        ui.label(RichText::new(label).color(Color32::GRAY));
+        return;
+    };
+    let text = match curr_sort {
+        Some((field, SortDirection::Asc)) if field == sort => format!("{label} \u{2B06}"),
+        Some((field, SortDirection::Desc)) if field == sort => format!("{label} \u{2B07}"),
+        _ => label.to_string(),
+    };
+    if ui
+        .add(egui::Label::new(RichText::new(text).color(Color32::GRAY)).sense(Sense::click()))
+        .on_hover_cursor(CursorIcon::PointingHand)
+        .on_hover_text(format!("Sort the items by {label}."))
+        .clicked()
+    {
+        *sort_by = Some(sort);
+    }
+}
+
+fn security_rows_filtered(ctx: &CogsApp) -> (Vec<AccessLevel>, Vec<UserAccount>) {
     match (&ctx.state.explore.category, &ctx.state.explore.kind) {
-        (ExploreCategory::Security, ExploreKind::All | ExploreKind::AccessLevel) => ctx.state.data.get_access_levels().to_vec(),
-        (ExploreCategory::Security, _) => Vec::new(),
-        _ => Vec::new(),
+        (ExploreCategory::Security, ExploreKind::All) => (ctx.state.data.get_access_levels(), ctx.state.data.get_users()),
+        (ExploreCategory::Security, ExploreKind::AccessLevel) => (ctx.state.data.get_access_levels(), Vec::new()),
+        (ExploreCategory::Security, ExploreKind::User) => (Vec::new(), ctx.state.data.get_users()),
+        _ => (Vec::new(), Vec::new()),
     }
 }
 
@@ -139,7 +215,7 @@ fn template_rows_filtered(ctx: &CogsApp) -> (Vec<ItemTemplate>, Vec<AttrTemplate
         }
 
         // Stale value (from Items mode) while in Templates: fallback to All.
-        (ExploreCategory::Templates, ExploreKind::ItemTemplateId(_) | ExploreKind::AccessLevel) => (
+        (ExploreCategory::Templates, ExploreKind::ItemTemplateId(_) | ExploreKind::AccessLevel | ExploreKind::User) => (
             ctx.state.data.get_item_templates().to_vec(),
             ctx.state.data.get_attr_templates().to_vec(),
         ),
@@ -147,12 +223,20 @@ fn template_rows_filtered(ctx: &CogsApp) -> (Vec<ItemTemplate>, Vec<AttrTemplate
         // Category = Items -> templates list isn't relevant here.
         (ExploreCategory::Items, _) => (Vec::new(), Vec::new()),
 
-        // Category = Security -> templates list isn't relevant here.
-        (ExploreCategory::Security, _) => (Vec::new(), Vec::new()),
+        // Category = Security or Trash -> templates list isn't relevant here.
+        (ExploreCategory::Security | ExploreCategory::Trash, _) => (Vec::new(), Vec::new()),
     }
 }
 
-fn show_access_levels(ctx: &mut CogsApp, ui: &mut Ui, elems: &[AccessLevel], available_height: f32, available_width: f32) {
+/// Show the security elements: the access levels, followed by the users.
+fn show_security(
+    ctx: &mut CogsApp,
+    ui: &mut Ui,
+    access_levels: &[AccessLevel],
+    users: &[UserAccount],
+    available_height: f32,
+    available_width: f32,
+) {
     ui.visuals_mut().widgets.hovered.bg_stroke = egui::Stroke::NONE;
     ui.visuals_mut().widgets.active.bg_stroke = egui::Stroke::NONE;
     ui.visuals_mut().widgets.noninteractive.bg_stroke = egui::Stroke::NONE;
@@ -166,7 +250,7 @@ fn show_access_levels(ctx: &mut CogsApp, ui: &mut Ui, elems: &[AccessLevel], ava
         .min((available_width - type_width - name_width - spacing_x * 2.0).max(160.0));
 
     let table = TableBuilder::new(ui)
-        .id_salt("explore_access_levels_table")
+        .id_salt("explore_security_table")
         .striped(false)
         .resizable(true)
         .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
@@ -189,65 +273,271 @@ fn show_access_levels(ctx: &mut CogsApp, ui: &mut Ui, elems: &[AccessLevel], ava
     });
 
     table.body(|mut body| {
-        for elem in elems {
-            let mut open_win = false;
-            let mut show_right = false;
+        for elem in access_levels {
+            let description = elem.description.as_deref().unwrap_or_default();
+            let (open_win, show_right) = security_row(&mut body, ("A.L.", "Access Level"), &elem.name, description);
+
+            if open_win {
+                ctx.state
+                    .explore
+                    .open_windows_access_level
+                    .insert(elem.id.clone(), elem.clone());
+            }
+
+            if show_right {
+                ctx.state.explore.curr_sel_elem = Some((Kind::AccessLevel, elem.id.clone()));
+            }
+        }
+
+        for elem in users {
+            let description = match elem.disabled {
+                true => format!("{} (disabled)", elem.name),
+                false => elem.name.clone(),
+            };
+            let (open_win, show_right) = security_row(&mut body, ("User", "User"), &elem.username, &description);
+
+            if open_win {
+                ctx.state.explore.open_windows_user.insert(elem.id.clone(), elem.clone());
+            }
+
+            if show_right {
+                ctx.state.explore.curr_sel_elem = Some((Kind::User, elem.id.clone()));
+            }
+        }
+
+        let widths = body.widths();
+        if widths.len() >= 3 {
+            ctx.state.explore.table_col_widths = Some([widths[0], widths[1], widths[2]]);
+        }
+    });
+}
+
+/// Show a row of a security element, having its type (label and hint), name, and description.
+/// It returns whether its window should be opened (on double click) and whether it should be previewed (on click).
+fn security_row(body: &mut TableBody<'_>, kind: (&str, &str), name: &str, description: &str) -> (bool, bool) {
+    let mut open_win = false;
+    let mut show_right = false;
+
+    body.row(20.0, |mut row| {
+        row.col(|ui| {
+            ui.label(RichText::new(kind.0).color(Color32::GRAY))
+                .on_hover_text(kind.1)
+                .on_hover_cursor(CursorIcon::Help);
+        });
+
+        row.col(|ui| {
+            let label = ui.label(name).on_hover_cursor(CursorIcon::PointingHand);
+
+            if label.double_clicked() {
+                open_win = true;
+            } else if label.clicked() {
+                show_right = true;
+            }
+        });
+
+        row.col(|ui| {
+            let label = ui
+                .label(RichText::new(description).color(Color32::GRAY))
+                .on_hover_cursor(CursorIcon::PointingHand);
+
+            if label.double_clicked() {
+                open_win = true;
+            } else if label.clicked() {
+                show_right = true;
+            }
+        });
+
+        row.response().on_hover_cursor(CursorIcon::PointingHand);
+        if row.response().double_clicked() {
+            open_win = true;
+        }
+        if row.response().clicked() {
+            show_right = true;
+        }
+    });
+
+    (open_win, show_right)
+}
+
+/// Show the elements that are in the Trash, each with the buttons to restore or purge it.
+/// Purging needs to be confirmed, since it cannot be undone.
+fn show_trash(ctx: &mut CogsApp, ui: &mut Ui, elems: &[TrashEntry], available_height: f32) {
+    if let Some(err) = &ctx.state.explore.trash_err {
+        ui.label(RichText::new(err.to_string()).color(ui.visuals().error_fg_color));
+        ui.add_space(6.0);
+    }
+    if elems.is_empty() {
+        ui.label(RichText::new("The trash is empty.").color(Color32::GRAY));
+    }
 
+    let mut restore = None;
+    let mut purge = None;
+
+    let table = TableBuilder::new(ui)
+        .id_salt("explore_trash_table")
+        .striped(false)
+        .resizable(true)
+        .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+        .column(Column::auto().at_least(40.0)) // type
+        .column(Column::initial(150.0).at_least(100.0).at_most(250.0)) // name
+        .column(Column::initial(180.0).at_least(120.0)) // deleted
+        .column(Column::remainder().at_least(120.0)) // actions
+        .max_scroll_height(available_height);
+
+    let table = table.header(20.0, |mut header| {
+        for label in ["type", "name", "deleted", ""] {
+            header.col(|ui| {
+                ui.label(RichText::new(label).color(Color32::GRAY));
+            });
+        }
+    });
+
+    table.body(|mut body| {
+        for elem in elems {
             body.row(20.0, |mut row| {
                 row.col(|ui| {
-                    ui.label(RichText::new("A.L.").color(Color32::GRAY))
-                        .on_hover_text("Access Level")
+                    let (abbr, name) = match elem.kind {
+                        Kind::Item => ("I.", "Item"),
+                        Kind::ItemTemplate => ("I.T.", "Item Template"),
+                        _ => ("A.T.", "Attribute Template"),
+                    };
+                    ui.label(RichText::new(abbr).color(Color32::GRAY))
+                        .on_hover_text(name)
                         .on_hover_cursor(CursorIcon::Help);
                 });
-
                 row.col(|ui| {
-                    let label = ui.label(&elem.name).on_hover_cursor(CursorIcon::PointingHand);
-
-                    if label.double_clicked() {
-                        open_win = true;
-                    } else if label.clicked() {
-                        show_right = true;
-                    }
+                    ui.label(&elem.name);
                 });
-
                 row.col(|ui| {
-                    let label = ui
-                        .label(RichText::new(elem.description.as_deref().unwrap_or_default()).color(Color32::GRAY))
-                        .on_hover_cursor(CursorIcon::PointingHand);
-
-                    if label.double_clicked() {
-                        open_win = true;
-                    } else if label.clicked() {
-                        show_right = true;
+                    let by = if elem.deleted_by_name.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" by {}", elem.deleted_by_name)
+                    };
+                    ui.label(RichText::new(format!("{}{by}", elem.deleted_at.format("%Y-%m-%d %H:%M UTC"))).color(Color32::GRAY));
+                });
+                row.col(|ui| {
+                    if ui.small_button("Restore").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                        restore = Some(elem.clone());
+                    }
+                    if ui
+                        .small_button("Purge")
+                        .on_hover_text("Delete it for good.")
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .clicked()
+                    {
+                        purge = Some(elem.clone());
                     }
                 });
-
-                row.response().on_hover_cursor(CursorIcon::PointingHand);
-                if row.response().double_clicked() {
-                    open_win = true;
-                }
-                if row.response().clicked() {
-                    show_right = true;
-                }
             });
+        }
+    });
 
-            if open_win {
-                ctx.state
-                    .explore
-                    .open_windows_access_level
-                    .insert(elem.id.clone(), elem.clone());
+    let ectx = ui.ctx().clone();
+    if let Some(elem) = restore {
+        ctx.state
+            .data
+            .restore_from_trash(elem.kind, elem.id, &ectx, ctx.sendr.clone());
+    }
+    if purge.is_some() {
+        ctx.state.explore.trash_purge_confirm = purge;
+    }
+
+    let Some(elem) = ctx.state.explore.trash_purge_confirm.clone() else {
+        return;
+    };
+    let (mut confirmed, mut cancelled) = (false, false);
+    egui::Modal::new(egui::Id::new("explore_trash_purge")).show(&ectx, |ui| {
+        ui.set_width(280.0);
+        ui.label(format!("'{}' will be deleted for good. This cannot be undone.", elem.name));
+        ui.add_space(10.0);
+        ui.horizontal(|ui| {
+            if ui.button("  Purge  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                confirmed = true;
             }
+            if ui.button("  Cancel  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                cancelled = true;
+            }
+        });
+    });
+    if confirmed {
+        ctx.state.data.purge(elem.kind, elem.id, &ectx, ctx.sendr.clone());
+    }
+    if confirmed || cancelled {
+        ctx.state.explore.trash_purge_confirm = None;
+    }
+}
 
-            if show_right {
-                ctx.state.explore.curr_sel_elem = Some((Kind::AccessLevel, elem.id.clone()));
+/// Show the items. Only the visible rows are laid out, and it returns whether the last one is visible.
+fn show_items(ctx: &mut CogsApp, body: TableBody<'_>, elems: &[Item]) -> bool {
+    let mut reached_end = false;
+    body.rows(20.0, elems.len(), |mut row| {
+        let Some(elem) = elems.get(row.index()) else {
+            return;
+        };
+        reached_end |= row.index() + 1 == elems.len();
+        let mut open_win = false;
+        let mut show_right = false;
+
+        row.col(|ui| {
+            if elem.kind.is_empty() {
+                ui.label(RichText::new("I.").color(Color32::GRAY))
+                    .on_hover_text("Item (created from scratch)")
+                    .on_hover_cursor(CursorIcon::Help);
+            } else {
+                ui.label(RichText::new(&elem.kind).color(Color32::GRAY))
+                    .on_hover_text("Item Template")
+                    .on_hover_cursor(CursorIcon::Help);
             }
+        });
+
+        row.col(|ui| {
+            let label = ui.label(&elem.listing_attr_value).on_hover_cursor(CursorIcon::PointingHand);
+
+            if label.double_clicked() {
+                open_win = true;
+            } else if label.clicked() {
+                show_right = true;
+            }
+        });
+
+        row.col(|ui| {
+            // The other attributes are shown as a short description.
+            let description = elem
+                .attributes()
+                .into_iter()
+                .filter(|a| a.name != elem.listing_attr_name)
+                .map(|a| format!("{}: {}", a.name, a.value))
+                .collect::<Vec<String>>()
+                .join(", ");
+            let label = ui
+                .add(egui::Label::new(RichText::new(description).color(Color32::GRAY)).truncate())
+                .on_hover_cursor(CursorIcon::PointingHand);
+
+            if label.double_clicked() {
+                open_win = true;
+            } else if label.clicked() {
+                show_right = true;
+            }
+        });
+
+        row.response().on_hover_cursor(CursorIcon::PointingHand);
+        if row.response().double_clicked() {
+            open_win = true;
+        }
+        if row.response().clicked() {
+            show_right = true;
         }
 
-        let widths = body.widths();
-        if widths.len() >= 3 {
-            ctx.state.explore.table_col_widths = Some([widths[0], widths[1], widths[2]]);
+        if open_win {
+            ctx.state.explore.open_windows_item.insert(elem.id.clone(), elem.clone());
+        }
+
+        if show_right {
+            ctx.state.explore.curr_sel_elem = Some((Kind::Item, elem.id.clone()));
         }
     });
+    reached_end
 }
 
 fn show_attr_templates(ctx: &mut CogsApp, body: &mut TableBody<'_>, elems: &[AttrTemplate]) {
@@ -1,18 +1,31 @@
 use crate::{
     CogsApp,
-    comps::{AppComponent, AttrTemplatePreview, Dropdown, DropdownItem, DropdownStyle, ItemTemplatePreview, menu_row},
-    constants::{EXPLORE_ELEMENT, ICON_ATTR_TMPL, ICON_HELP, ICON_ITEM, ICON_ITEM_TMPL, ICON_RARROW, ICON_TMPL, POPUP_ROW_WIDTH},
+    comps::{
+        AppComponent, AttrTemplatePreview, BundleImportState, Dropdown, DropdownItem, DropdownStyle, ItemPreview,
+        ItemTemplatePreview, ItemsImportState, menu_row,
+    },
+    constants::{
+        EXPLORE_ELEMENT, ICON_ATTR_TMPL, ICON_EXPORT, ICON_HELP, ICON_IMPORT, ICON_ITEM, ICON_ITEM_TMPL, ICON_RARROW, ICON_TMPL,
+        ICON_USER, POPUP_ROW_WIDTH,
+    },
     explore::{ExploreTable, show_windows},
-    security::AccessLevelPreview,
+    security::{AccessLevelPreview, UserPreview},
     views::AppView,
 };
-use cogs_shared::domain::model::{
-    Id,
-    meta::{AttrTemplate, Item, ItemTemplate, Kind},
+use cogs_shared::{
+    domain::{
+        logic::ItemQuery,
+        model::{
+            Id, UserAccount,
+            meta::{AttrTemplate, Item, ItemTemplate, Kind},
+        },
+    },
+    dtos::{ExportFormat, SearchHit},
 };
 use const_format::concatcp;
-use egui::{Color32, CursorIcon, Popup, RichText, Sense, Ui};
+use egui::{Align, Color32, CursorIcon, Key, Layout, Popup, PopupCloseBehavior, RichText, Sense, TextEdit, Ui};
 use egui_extras::{Size, Strip, StripBuilder};
+use egui_material_icons::{icon_text, icons::ICON_SEARCH};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
@@ -22,6 +35,8 @@ pub enum ExploreCategory {
     Items,
     Templates,
     Security,
+    /// The (soft) deleted items and templates.
+    Trash,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -34,6 +49,7 @@ pub enum ExploreKind {
     ItemTemplateId(Id),
     // Used when Category::Security.
     AccessLevel,
+    User,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -84,28 +100,29 @@ fn show_table_cell(ctx: &mut CogsApp, strip: &mut Strip<'_, '_>) {
         match ctx.state.explore.category {
             ExploreCategory::Templates => match ctx.state.explore.kind {
                 ExploreKind::All | ExploreKind::TemplateType(_) => {}
-                ExploreKind::ItemTemplateId(_) | ExploreKind::AccessLevel => {
+                ExploreKind::ItemTemplateId(_) | ExploreKind::AccessLevel | ExploreKind::User => {
                     ctx.state.explore.kind = ExploreKind::All;
                 }
             },
             ExploreCategory::Items => match ctx.state.explore.kind {
                 ExploreKind::All | ExploreKind::ItemTemplateId(_) => {}
-                ExploreKind::TemplateType(_) | ExploreKind::AccessLevel => {
+                ExploreKind::TemplateType(_) | ExploreKind::AccessLevel | ExploreKind::User => {
                     ctx.state.explore.kind = ExploreKind::All;
                 }
             },
             ExploreCategory::Security => match ctx.state.explore.kind {
-                ExploreKind::All | ExploreKind::AccessLevel => {}
+                ExploreKind::All | ExploreKind::AccessLevel | ExploreKind::User => {}
                 ExploreKind::TemplateType(_) | ExploreKind::ItemTemplateId(_) => {
                     ctx.state.explore.kind = ExploreKind::All;
                 }
             },
+            ExploreCategory::Trash => ctx.state.explore.kind = ExploreKind::All,
         }
     }
 
     // Optional: clear current selection if it no longer passes active filter.
     fn selection_passes_filter(ctx: &CogsApp) -> bool {
-        let Some((sel_kind, _sel_id)) = &ctx.state.explore.curr_sel_elem else {
+        let Some((sel_kind, sel_id)) = &ctx.state.explore.curr_sel_elem else {
             return true;
         };
 
@@ -118,32 +135,32 @@ fn show_table_cell(ctx: &mut CogsApp, strip: &mut Strip<'_, '_>) {
                 ExploreKind::TemplateType(TemplateTypeFilter::ItemTemplate) => *sel_kind == Kind::ItemTemplate,
                 ExploreKind::TemplateType(TemplateTypeFilter::AttributeTemplate) => *sel_kind == Kind::AttributeTemplate,
                 ExploreKind::ItemTemplateId(_) => true,
-                ExploreKind::AccessLevel => false,
+                ExploreKind::AccessLevel | ExploreKind::User => false,
             },
 
             ExploreCategory::Items => match &ctx.state.explore.kind {
-                ExploreKind::All => {
-                    // Any item visible in Items category.
-                    // Assuming you store selected item as Kind::ItemTemplate? adjust if you have Kind::Item.
-                    true
-                }
-                ExploreKind::ItemTemplateId(_tmpl_id) => {
+                ExploreKind::All => *sel_kind == Kind::Item,
+                ExploreKind::ItemTemplateId(tmpl_id) => {
                     // If selection points to an item, keep only if item's template matches tmpl_id.
-                    // TODO
-                    // ctx.state
-                    //     .data
-                    //     .get_items() // TODO
-                    //     .iter()
-                    //     .find(|it| it.id == *sel_id)
-                    //     .map(|it| it.template_id == *tmpl_id) // <- replace field name if different
-                    //     .unwrap_or(false)
-                    true
+                    *sel_kind == Kind::Item
+                        && ctx
+                            .state
+                            .data
+                            .get_item(sel_id)
+                            .is_some_and(|it| it.tmpl_id.as_ref() == Some(tmpl_id))
                 }
-                ExploreKind::TemplateType(_) => true, // normalized away above
-                ExploreKind::AccessLevel => true,     // normalized away above
+                ExploreKind::TemplateType(_) => true,                 // normalized away above
+                ExploreKind::AccessLevel | ExploreKind::User => true, // normalized away above
+            },
+
+            ExploreCategory::Security => match &ctx.state.explore.kind {
+                ExploreKind::AccessLevel => *sel_kind == Kind::AccessLevel,
+                ExploreKind::User => *sel_kind == Kind::User,
+                _ => true,
             },
 
-            ExploreCategory::Security => true,
+            // The deleted elements cannot be previewed.
+            ExploreCategory::Trash => false,
         }
     }
 
@@ -155,7 +172,14 @@ fn show_table_cell(ctx: &mut CogsApp, strip: &mut Strip<'_, '_>) {
                 show_kind(ctx, ui);
                 ui.add_space(15.0);
                 show_add_menu(ctx, ui);
+                ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                    show_search(ctx, ui);
+                });
             });
+            if ctx.state.explore.category == ExploreCategory::Items {
+                ui.add_space(6.0);
+                show_items_filter(ctx, ui);
+            }
         });
 
         // Ensure filter state coherence before rendering table.
@@ -180,6 +204,7 @@ fn show_category(ctx: &mut CogsApp, ui: &mut egui::Ui) {
             DropdownItem::new("Items", ExploreCategory::Items),
             DropdownItem::new("Templates", ExploreCategory::Templates),
             DropdownItem::new("Security", ExploreCategory::Security),
+            DropdownItem::new("Trash", ExploreCategory::Trash),
         ];
 
         if let Some(v) = Dropdown::show(
@@ -242,7 +267,13 @@ fn build_kind_options(ctx: &CogsApp) -> Vec<KindOption> {
                 value: ExploreKind::AccessLevel,
                 italic: false,
             });
+            out.push(KindOption {
+                label: "User".to_string(),
+                value: ExploreKind::User,
+                italic: false,
+            });
         }
+        ExploreCategory::Trash => {}
     }
 
     out
@@ -278,7 +309,7 @@ fn show_kind(ctx: &mut CogsApp, ui: &mut Ui) {
 
     ui.label(RichText::new(ICON_HELP).color(Color32::GRAY).size(10.0))
         .on_hover_text(
-            "If category is:\n- 'Items', you may filter by item template.\n- 'Templates', you may filter by template type.\n- 'Security', you may filter by security element type.",
+            "If category is:\n- 'Items', you may filter by item template.\n- 'Templates', you may filter by template type.\n- 'Security', you may filter by security element type.\n- 'Trash', all the deleted elements are shown.",
         )
         .on_hover_cursor(CursorIcon::Help);
 }
@@ -321,6 +352,37 @@ fn show_add_menu(ctx: &mut CogsApp, ui: &mut Ui) {
                 ui.close();
             }
 
+            if menu_row(ui, concatcp!(ICON_IMPORT, "   Import Items"), false, Some(115.0)).clicked() {
+                ctx.state.explore.items_import = Some(ItemsImportState::default());
+                ui.close();
+            }
+
+            ui.separator();
+
+            if menu_row(ui, concatcp!(ICON_EXPORT, "   Export Workspace"), false, Some(115.0)).clicked() {
+                // The bundle is downloaded by the browser (or the system's default one, on desktop).
+                let url = ctx.state.data.bundle_export_url();
+                ctx.state.data.download_export(url, ui.ctx(), ctx.sendr.clone());
+                ui.close();
+            }
+
+            if menu_row(ui, concatcp!(ICON_IMPORT, "   Import Workspace"), false, Some(115.0)).clicked() {
+                ctx.state.explore.bundle_import = Some(BundleImportState::default());
+                ui.close();
+            }
+
+            ui.separator();
+
+            if menu_row(ui, concatcp!(ICON_USER, "   User"), false, Some(115.0)).clicked() {
+                let user = UserAccount {
+                    is_anonymous: false,
+                    username: String::new(),
+                    ..Default::default()
+                };
+                ctx.state.explore.open_windows_user.insert(Id::default(), user);
+                ui.close();
+            }
+
             ui.separator();
 
             let templates_resp = menu_row(ui, concatcp!(ICON_TMPL, "   Templates  ", ICON_RARROW), false, Some(115.0));
@@ -408,6 +470,182 @@ fn show_add_menu(ctx: &mut CogsApp, ui: &mut Ui) {
         });
 }
 
+/// Show the filter bar of the items. The filter is applied when pressing Enter,
+/// if it is a valid query, and it is removed when it is emptied.
+fn show_items_filter(ctx: &mut CogsApp, ui: &mut Ui) {
+    //
+    ui.horizontal(|ui| {
+        ui.label("Filter");
+        let resp = ui.add(
+            TextEdit::singleline(&mut ctx.state.explore.items_filter_input)
+                .hint_text(r#"Status = "open" AND Priority >= 3"#)
+                .desired_width(ui.available_width() - 180.0),
+        );
+        ui.label(RichText::new(ICON_HELP).color(Color32::GRAY).size(10.0))
+            .on_hover_text(
+                "Filter the items by their attributes, using conditions such as:\n\
+                 - Status = \"open\"  (also != < <= > >=)\n\
+                 - Title CONTAINS \"web\"\n\
+                 - Priority >= 3\n\
+                 - Done = false\n\
+                 - Due < 2026-12-01  or  Due < 2026-12-01T10:30\n\
+                 combined using AND, OR, NOT, and parentheses.\n\
+                 Quote the attribute names that have spaces, such as \"Due date\".",
+            )
+            .on_hover_cursor(CursorIcon::Help);
+        if let Some(total) = ctx.state.data.get_items_total() {
+            ui.label(RichText::new(format!("{total} items")).color(Color32::GRAY).size(11.0));
+        }
+        show_items_export(ctx, ui);
+
+        let input = ctx.state.explore.items_filter_input.trim().to_string();
+        let applied = resp.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        if applied || (resp.changed() && input.is_empty()) {
+            match input.parse::<ItemQuery>() {
+                Err(err) if !input.is_empty() => ctx.state.explore.items_filter_err = Some(err.to_string()),
+                _ => {
+                    ctx.state.explore.items_filter_err = None;
+                    ctx.state.data.set_items_filter(input);
+                }
+            }
+        }
+    });
+
+    let server_err = ctx.state.data.get_items_err().map(ToString::to_string);
+    if let Some(err) = ctx.state.explore.items_filter_err.clone().or(server_err) {
+        ui.label(RichText::new(err).size(11.0).color(Color32::RED));
+    }
+}
+
+/// Show the "Export…" menu of the items that are shown (as filtered) in the table, once they are limited to an item template.
+/// The export is downloaded by the browser (or the system's default one, on desktop).
+fn show_items_export(ctx: &mut CogsApp, ui: &mut Ui) {
+    //
+    let enabled = ctx.state.data.items_export_url(ExportFormat::default()).is_some();
+    ui.add_enabled_ui(enabled, |ui| {
+        ui.menu_button("Export…", |ui| {
+            for (label, format) in [
+                ("CSV", ExportFormat::Csv),
+                ("JSON", ExportFormat::Json),
+                ("NDJSON", ExportFormat::Ndjson),
+            ] {
+                if ui.button(label).on_hover_cursor(CursorIcon::PointingHand).clicked()
+                    && let Some(url) = ctx.state.data.items_export_url(format)
+                {
+                    ctx.state.data.download_export(url, ui.ctx(), ctx.sendr.clone());
+                }
+            }
+        })
+        .response
+        .on_disabled_hover_text("Choose an item template, to export its items.");
+    });
+}
+
+/// The min number of characters of a query, for the search to start.
+const SEARCH_MIN_CHARS: usize = 2;
+
+/// Show the search box, and the results of the search (grouped by kind) in a popup below it.
+/// Clicking a result selects that element, as if it was clicked in the table.
+fn show_search(ctx: &mut CogsApp, ui: &mut Ui) {
+    //
+    let resp = ui.add(
+        TextEdit::singleline(&mut ctx.state.explore.search_query)
+            .hint_text("Search")
+            .desired_width(180.0),
+    );
+    ui.label(icon_text(ICON_SEARCH).color(Color32::GRAY));
+
+    let query = ctx.state.explore.search_query.trim().to_string();
+    if resp.changed() {
+        if query.chars().count() >= SEARCH_MIN_CHARS {
+            ctx.state.data.search(query.clone(), ui.ctx(), ctx.sendr.clone());
+        } else {
+            ctx.state.explore.search_results = None;
+        }
+    }
+    if resp.gained_focus() && query.chars().count() >= SEARCH_MIN_CHARS && ctx.state.explore.search_results.is_none() {
+        ctx.state.data.search(query, ui.ctx(), ctx.sendr.clone());
+    }
+    if ui.input(|i| i.key_pressed(Key::Escape)) {
+        ctx.state.explore.search_results = None;
+    }
+
+    let Some((_, result)) = ctx.state.explore.search_results.clone() else {
+        return;
+    };
+    let mut selected: Option<(Kind, Id)> = None;
+    Popup::from_response(&resp)
+        .id(egui::Id::new("explore_search_popup"))
+        .open(true)
+        .close_behavior(PopupCloseBehavior::IgnoreClicks)
+        .width(320.0)
+        .gap(4.0)
+        .show(|ui| match result {
+            Err(err) => {
+                ui.label(RichText::new(format!("The search failed: {err}")).color(Color32::GRAY));
+            }
+            Ok(groups) if groups.is_empty() => {
+                ui.label(RichText::new("Nothing found.").color(Color32::GRAY));
+            }
+            Ok(groups) => {
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    for group in groups {
+                        let (icon, label) = match group.kind {
+                            Kind::Item => (ICON_ITEM, "Items"),
+                            Kind::ItemTemplate => (ICON_ITEM_TMPL, "Item Templates"),
+                            Kind::AttributeTemplate => (ICON_ATTR_TMPL, "Attribute Templates"),
+                            _ => ("", "Others"),
+                        };
+                        ui.add_space(4.0);
+                        ui.label(RichText::new(label).size(11.0).color(Color32::GRAY));
+                        for hit in &group.hits {
+                            if show_search_hit(ui, icon, hit).clicked() {
+                                selected = Some((hit.kind.clone(), hit.id.clone()));
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+    if let Some((kind, id)) = selected {
+        select_element(ctx, kind, id);
+    }
+}
+
+/// Show a search hit: its name, followed by the matching text (having the matching words highlighted).
+fn show_search_hit(ui: &mut Ui, icon: &str, hit: &SearchHit) -> egui::Response {
+    let resp = ui
+        .selectable_label(false, format!("{icon}  {}", hit.name))
+        .on_hover_cursor(CursorIcon::PointingHand);
+    ui.horizontal_wrapped(|ui| {
+        ui.add_space(20.0);
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for (text, highlighted) in hit.headline_parts() {
+            let text = RichText::new(text).size(11.0);
+            ui.label(if highlighted {
+                text.strong()
+            } else {
+                text.color(Color32::GRAY)
+            });
+        }
+    });
+    resp
+}
+
+/// Select an element (of any kind), switching the category and kind filters to make it visible.
+fn select_element(ctx: &mut CogsApp, kind: Kind, id: Id) {
+    match kind {
+        Kind::Item => ctx.state.explore.category = ExploreCategory::Items,
+        Kind::ItemTemplate | Kind::AttributeTemplate => ctx.state.explore.category = ExploreCategory::Templates,
+        Kind::AccessLevel | Kind::User => ctx.state.explore.category = ExploreCategory::Security,
+        Kind::LinkTemplate => {}
+    }
+    ctx.state.explore.kind = ExploreKind::All;
+    ctx.state.explore.curr_sel_elem = Some((kind, id));
+    ctx.state.explore.search_results = None;
+}
+
 fn show_preview_cell(ctx: &mut CogsApp, ectx: &egui::Context, strip: &mut Strip<'_, '_>) {
     strip.cell(|ui| {
         ui.vertical(|ui| {
@@ -432,6 +670,16 @@ fn show_preview_cell(ctx: &mut CogsApp, ectx: &egui::Context, strip: &mut Strip<
                         }
                         ItemTemplatePreview::show(ctx, ui);
                     }
+                    Kind::Item => {
+                        // The item (such as one found by searching) may not be in the fetched pages.
+                        if ctx.state.data.should_fetch_item(id) {
+                            ctx.state.data.fetch_item(id.clone(), ectx, ctx.sendr.clone());
+                        }
+                        if let Some(elem) = ctx.state.data.get_item(id) {
+                            ectx.data_mut(|d| d.insert_temp(egui::Id::from(EXPLORE_ELEMENT), elem));
+                        }
+                        ItemPreview::show(ctx, ui);
+                    }
                     Kind::AccessLevel => {
                         for elem in ctx.state.data.get_access_levels().iter() {
                             if elem.id == *id {
@@ -441,6 +689,15 @@ fn show_preview_cell(ctx: &mut CogsApp, ectx: &egui::Context, strip: &mut Strip<
                         }
                         AccessLevelPreview::show(ctx, ui);
                     }
+                    Kind::User => {
+                        for elem in ctx.state.data.get_users().iter() {
+                            if elem.id == *id {
+                                ectx.data_mut(|d| d.insert_temp(egui::Id::from(EXPLORE_ELEMENT), elem.clone()));
+                                break;
+                            }
+                        }
+                        UserPreview::show(ctx, ui);
+                    }
                     _ => {}
                 }
             }
@@ -1,10 +1,18 @@
 use crate::{
+    comps::{BundleImportState, ItemsImportState, SaveConflict},
     explore::{ExploreCategory, ExploreKind},
     state::SourceType,
 };
-use cogs_shared::domain::model::{
-    AccessLevel, Id,
-    meta::{Attr, AttrTemplate, Item, ItemTemplate, Kind},
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::{
+        logic::{ItemTemplateChangePlan, OrphanAttrsPolicy},
+        model::{
+            AccessLevel, Id, UserAccount,
+            meta::{Attr, AttrTemplate, Item, ItemTemplate, Kind},
+        },
+    },
+    dtos::{SearchHitsGroup, TrashEntry},
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -38,10 +46,41 @@ pub struct ExploreViewState {
     // #[serde(skip)]  todo: temporary used during form dev.
     pub open_windows_access_level: HashMap<Id, AccessLevel>,
 
+    /// The open windows for creating (one) or editing (one or more) users.
+    pub open_windows_user: HashMap<Id, UserAccount>,
+
     /// The element that is currently clicked (not double clicked) in the Explore's table.
     #[serde(skip)]
     pub curr_sel_elem: Option<(Kind, Id)>,
 
+    /// The text entered in the items filter bar, and the error of parsing it (if any).
+    #[serde(skip)]
+    pub items_filter_input: String,
+    #[serde(skip)]
+    pub items_filter_err: Option<String>,
+
+    /// The text entered in the search box.
+    #[serde(skip)]
+    pub search_query: String,
+
+    /// The results of the last search, along with the query they are for.
+    /// Being set means that they are shown.
+    #[serde(skip)]
+    pub search_results: Option<(String, AppResult<Vec<SearchHitsGroup>>)>,
+
+    /// The error of the last failed attempt to restore or purge an element in the Trash.
+    #[serde(skip)]
+    pub trash_err: Option<AppError>,
+
+    /// The element in the Trash that is about to be purged, once confirmed.
+    #[serde(skip)]
+    pub trash_purge_confirm: Option<TrashEntry>,
+
+    /// The saves that were refused since the elements were changed by someone else meanwhile,
+    /// to be resolved in their (reopened) windows. This is mapped by element `Id`.
+    #[serde(skip)]
+    pub save_conflicts: HashMap<Id, SaveConflict>,
+
     // --------------------------------------------------------
     // State of `ItemTemplateWindow`s when creating or editing.
     // --------------------------------------------------------
@@ -60,6 +99,14 @@ pub struct ExploreViewState {
     #[serde(skip)]
     pub item_template_cu_add_link_template: HashMap<Id, Option<Id>>,
 
+    /// The plan of changing an (existing) item template, to be confirmed before saving it,
+    /// and the choice of what happens to the attributes of the items that become orphans.
+    /// These are mapped by item template `Id`.
+    #[serde(skip)]
+    pub item_template_cu_change_plan: HashMap<Id, AppResult<ItemTemplateChangePlan>>,
+    #[serde(skip)]
+    pub item_template_cu_orphans: HashMap<Id, OrphanAttrsPolicy>,
+
     // ------------------------------------------------
     // State of `ItemWindow`s when creating or editing.
     // ------------------------------------------------
@@ -73,4 +120,40 @@ pub struct ExploreViewState {
     /// This is mapped by item `Id`.
     // #[serde(skip)] // TODO: used during dev.
     pub item_cu_add_attr: HashMap<Id, Attr>,
+
+    /// The link (name and target item id) that is selected to be added when editing an item.
+    /// This is mapped by item `Id`.
+    #[serde(skip)]
+    pub item_cu_add_link: HashMap<Id, (String, Option<Id>)>,
+
+    /// The wizard of importing items from a CSV text, while it's open.
+    #[serde(skip)]
+    pub items_import: Option<ItemsImportState>,
+
+    /// The window of importing a workspace bundle, while it's open.
+    #[serde(skip)]
+    pub bundle_import: Option<BundleImportState>,
+
+    /// The (linked) item whose window is opened once it is fetched.
+    #[serde(skip)]
+    pub item_window_to_open: Option<Id>,
+
+    /// The error of the last failed attempt to add or delete a link of an item.
+    /// This is mapped by item `Id`.
+    #[serde(skip)]
+    pub item_cu_add_link_err: HashMap<Id, AppError>,
+
+    // ------------------------------------------------
+    // State of `UserWindow`s when creating or editing.
+    // ------------------------------------------------
+    //
+    /// The password that is entered for a new user, or for resetting the one of an existing user.
+    /// This is mapped by user `Id`.
+    #[serde(skip)]
+    pub user_cu_password: HashMap<Id, String>,
+
+    /// The error of the last failed attempt to save, disable or enable a user.
+    /// This is mapped by user `Id`.
+    #[serde(skip)]
+    pub user_cu_err: HashMap<Id, AppError>,
 }
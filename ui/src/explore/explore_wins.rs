@@ -1,10 +1,11 @@
+use cogs_shared::domain::model::Id;
 use egui::Ui;
 
 use crate::{
     CogsApp,
-    comps::{AppComponent, AttrTemplateWindow, ItemTemplateWindow, ItemWindow},
+    comps::{AppComponent, AttrTemplateWindow, BundleImportWindow, ItemTemplateWindow, ItemWindow, ItemsImportWindow},
     constants::EXPLORE_ELEMENT,
-    security::AccessLevelWindow,
+    security::{AccessLevelWindow, UserWindow},
 };
 
 pub fn show_windows(ctx: &mut CogsApp, ui: &mut Ui, ectx: &egui::Context) {
@@ -24,4 +25,30 @@ pub fn show_windows(ctx: &mut CogsApp, ui: &mut Ui, ectx: &egui::Context) {
         ectx.data_mut(|d| d.insert_temp(egui::Id::from(EXPLORE_ELEMENT), element.clone()));
         AccessLevelWindow::show(ctx, ui);
     }
+    for (_, element) in ctx.state.explore.open_windows_user.clone().iter() {
+        ectx.data_mut(|d| d.insert_temp(egui::Id::from(EXPLORE_ELEMENT), element.clone()));
+        UserWindow::show(ctx, ui);
+    }
+    if ctx.state.explore.items_import.is_some() {
+        ItemsImportWindow::show(ctx, ui);
+    }
+    if ctx.state.explore.bundle_import.is_some() {
+        BundleImportWindow::show(ctx, ui);
+    }
+}
+
+/// Open the window of an item (in view mode). If it is not known yet,
+/// it gets fetched and its window is opened afterwards.
+pub fn open_item_window(app: &mut CogsApp, id: &Id, ectx: &egui::Context) {
+    match app.state.data.get_item(id) {
+        Some(item) => {
+            app.state.explore.open_windows_item.insert(item.id.clone(), item);
+        }
+        None => {
+            app.state.explore.item_window_to_open = Some(id.clone());
+            if app.state.data.should_fetch_item(id) {
+                app.state.data.fetch_item(id.clone(), ectx, app.sendr.clone());
+            }
+        }
+    }
 }
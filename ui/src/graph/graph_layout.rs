@@ -0,0 +1,145 @@
+use cogs_shared::domain::model::Id;
+use egui::{Vec2, vec2};
+use std::collections::HashMap;
+
+/// How strongly any two nodes push each other away.
+const REPULSION: f32 = 6000.0;
+
+/// The length that a link (edge) tends to have.
+const SPRING_LEN: f32 = 110.0;
+
+/// How strongly a link pulls (or pushes) its nodes towards its length.
+const SPRING_K: f32 = 0.03;
+
+/// How strongly the nodes are pulled towards the center, so the unlinked ones do not drift away.
+const CENTER_PULL: f32 = 0.004;
+
+/// How much of its velocity a node keeps from one step to the next one.
+const DAMPING: f32 = 0.82;
+
+/// The max distance that a node moves in one step.
+const MAX_SPEED: f32 = 25.0;
+
+/// A force-directed layout of the nodes of a graph: the nodes repel each other,
+/// while the links (edges) between them act as springs.
+/// It's computed a step at a time (usually one per frame), until it settles.
+#[derive(Clone, Debug, Default)]
+pub struct GraphLayout {
+    index: HashMap<Id, usize>,
+    positions: Vec<Vec2>,
+    velocities: Vec<Vec2>,
+}
+
+impl GraphLayout {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn position(&self, id: &Id) -> Option<Vec2> {
+        self.index.get(id).and_then(|&idx| self.positions.get(idx)).copied()
+    }
+
+    pub fn set_position(&mut self, id: &Id, pos: Vec2) {
+        if let Some(&idx) = self.index.get(id) {
+            self.positions[idx] = pos;
+            self.velocities[idx] = Vec2::ZERO;
+        }
+    }
+
+    /// Add a node (unless it's already there) around the provided position.
+    /// The nodes that are added around the same position get spread on a spiral.
+    pub fn add_near(&mut self, id: &Id, around: Vec2) {
+        if self.index.contains_key(id) {
+            return;
+        }
+        // The golden angle spreads them evenly, whatever their number is.
+        let n = self.positions.len() as f32;
+        let angle = n * 2.399_963;
+        let radius = SPRING_LEN * (0.6 + 0.1 * n.sqrt());
+        self.index.insert(id.clone(), self.positions.len());
+        self.positions.push(around + radius * vec2(angle.cos(), angle.sin()));
+        self.velocities.push(Vec2::ZERO);
+    }
+
+    /// Move the nodes one step, as per the forces between them. The pinned node (such as a dragged one) does not move.
+    /// It returns how much the nodes moved in total, which gets close to zero once the layout settles.
+    pub fn step(&mut self, edges: &[(Id, Id)], pinned: Option<&Id>) -> f32 {
+        let n = self.positions.len();
+        let mut forces = vec![Vec2::ZERO; n];
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let d = self.positions[i] - self.positions[j];
+                let dist_sq = d.length_sq().max(1.0);
+                // Nodes on the same spot are pushed apart in some direction.
+                let dir = if d.length_sq() < 0.01 {
+                    vec2(1.0, 0.0)
+                } else {
+                    d.normalized()
+                };
+                let f = dir * (REPULSION / dist_sq);
+                forces[i] += f;
+                forces[j] -= f;
+            }
+        }
+        for (source, target) in edges {
+            let (Some(&s), Some(&t)) = (self.index.get(source), self.index.get(target)) else {
+                continue;
+            };
+            if s == t {
+                continue;
+            }
+            let d = self.positions[t] - self.positions[s];
+            let len = d.length().max(0.01);
+            let f = d / len * (len - SPRING_LEN) * SPRING_K;
+            forces[s] += f;
+            forces[t] -= f;
+        }
+
+        let pinned = pinned.and_then(|id| self.index.get(id)).copied();
+        let mut moved = 0.0;
+        for (idx, force) in forces.into_iter().enumerate() {
+            if Some(idx) == pinned {
+                self.velocities[idx] = Vec2::ZERO;
+                continue;
+            }
+            let force = force - self.positions[idx] * CENTER_PULL;
+            let mut velocity = (self.velocities[idx] + force) * DAMPING;
+            if velocity.length() > MAX_SPEED {
+                velocity = velocity.normalized() * MAX_SPEED;
+            }
+            self.velocities[idx] = velocity;
+            self.positions[idx] += velocity;
+            moved += velocity.length();
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GraphLayout, SPRING_LEN};
+    use cogs_shared::domain::model::Id;
+    use egui::Vec2;
+
+    #[test]
+    fn linked_nodes_settle_near_the_spring_length() {
+        let (a, b, c) = (Id::from("a"), Id::from("b"), Id::from("c"));
+        let mut layout = GraphLayout::default();
+        for id in [&a, &b, &c] {
+            layout.add_near(id, Vec2::ZERO);
+        }
+        let edges = vec![(a.clone(), b.clone()), (b.clone(), c.clone())];
+        let pinned_at = layout.position(&a);
+
+        let mut moved = f32::MAX;
+        for _ in 0..2000 {
+            moved = layout.step(&edges, Some(&a));
+        }
+
+        assert!(moved < 0.1, "the layout did not settle: {moved}");
+        assert_eq!(layout.position(&a), pinned_at, "the pinned node moved");
+        let ab = (layout.position(&b).unwrap_or_default() - layout.position(&a).unwrap_or_default()).length();
+        assert!((ab - SPRING_LEN).abs() < SPRING_LEN * 0.5, "unexpected link length: {ab}");
+    }
+}
@@ -0,0 +1,324 @@
+use crate::{
+    CogsApp,
+    colors::faded_color,
+    comps::{Dropdown, DropdownItem, DropdownStyle},
+    explore::{open_item_window, show_windows},
+    views::AppView,
+};
+use cogs_shared::{
+    domain::model::Id,
+    dtos::{GRAPH_MAX_DEPTH, GRAPH_MAX_NODES, GraphDirection, GraphNode},
+};
+use egui::{Align2, Color32, CursorIcon, DragValue, FontId, Rect, RichText, Sense, Stroke, TextEdit, Ui, Vec2, ecolor::Hsva};
+
+/// The radius of a node, when not zoomed.
+const NODE_RADIUS: f32 = 14.0;
+
+/// The max number of characters of a node's label.
+const NODE_LABEL_MAX_CHARS: usize = 24;
+
+/// The zoom is kept between these limits.
+const ZOOM_RANGE: (f32, f32) = (0.2, 4.0);
+
+pub struct GraphView {}
+
+impl AppView for GraphView {
+    type Context = CogsApp;
+
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        //
+        let ectx = ui.ctx().clone();
+        let style = ectx.global_style();
+        egui::CentralPanel::default()
+            .frame(egui::Frame::central_panel(&style).inner_margin(egui::Margin::symmetric(20, 0)))
+            .show(ui, |ui| {
+                ui.add_space(17.0);
+                ui.label(
+                    RichText::new(
+                        "In this view, you can explore how the items are linked. Drag to pan, scroll to zoom, double click an item to expand its neighbors, and right click it for more.",
+                    )
+                    .color(Color32::GRAY),
+                );
+                ui.add_space(12.0);
+
+                // The graph of the root item gets fetched (again), once it's reset.
+                let graph = &mut ctx.state.graph;
+                if let Some(root) = graph.root.clone()
+                    && graph.nodes.is_empty()
+                    && graph.pending.is_empty()
+                    && graph.err.is_none()
+                {
+                    graph.pending.push(root.clone());
+                    ctx.state.data.fetch_item_graph(root, &graph.params(), &ectx, ctx.sendr.clone());
+                }
+
+                show_toolbar(ctx, ui);
+                ui.add_space(8.0);
+                if ctx.state.graph.root.is_some() {
+                    show_canvas(ctx, ui);
+                } else {
+                    ui.add_space(40.0);
+                    ui.vertical_centered(|ui| {
+                        ui.label(
+                            RichText::new("Select an item in the Explore view, and click \"Graph\" in its preview.")
+                                .color(faded_color(ui)),
+                        );
+                    });
+                }
+
+                show_windows(ctx, ui, &ectx);
+            });
+    }
+}
+
+/// Show the params of the graph, the legend of the node colors, and the outcome of the last fetch.
+fn show_toolbar(ctx: &mut CogsApp, ui: &mut Ui) {
+    let graph = &mut ctx.state.graph;
+    ui.horizontal(|ui| {
+        ui.label("Depth");
+        ui.add(DragValue::new(&mut graph.depth).range(1..=GRAPH_MAX_DEPTH));
+        ui.add_space(12.0);
+
+        ui.label("Direction");
+        let directions = [
+            DropdownItem::new("outgoing", GraphDirection::Out),
+            DropdownItem::new("incoming", GraphDirection::In),
+            DropdownItem::new("both", GraphDirection::Both),
+        ];
+        if let Some(direction) = Dropdown::show(
+            ui,
+            ui.id().with("graph_direction"),
+            &graph.direction,
+            &directions,
+            DropdownStyle::default(),
+        ) {
+            graph.direction = direction;
+        }
+        ui.add_space(12.0);
+
+        ui.label("Links");
+        ui.add(TextEdit::singleline(&mut graph.links).hint_text("all").desired_width(160.0))
+            .on_hover_text("The names of the links to follow, comma separated.");
+        ui.add_space(12.0);
+
+        let resp = ui
+            .add_enabled(graph.root.is_some(), egui::Button::new("Reload"))
+            .on_hover_cursor(CursorIcon::PointingHand)
+            .on_hover_text("Show the graph again, as per these params.");
+        if resp.clicked() {
+            graph.reset();
+        }
+        if !graph.pending.is_empty() {
+            ui.spinner();
+        }
+    });
+
+    let graph = &ctx.state.graph;
+    if let Some(err) = &graph.err {
+        ui.label(RichText::new(err.to_string()).size(11.0).color(Color32::RED));
+    } else if graph.truncated {
+        ui.label(
+            RichText::new(format!("Only the nearest {GRAPH_MAX_NODES} items are shown."))
+                .size(11.0)
+                .color(faded_color(ui)),
+        );
+    }
+
+    // The legend of the colors, one per item template.
+    let mut tmpls: Vec<(Option<&Id>, &str)> = Vec::new();
+    for node in &graph.nodes {
+        if !tmpls.iter().any(|(id, _)| *id == node.tmpl_id.as_ref()) {
+            tmpls.push((node.tmpl_id.as_ref(), node.tmpl_name.as_str()));
+        }
+    }
+    tmpls.sort_by(|a, b| a.1.cmp(b.1));
+    ui.horizontal_wrapped(|ui| {
+        for (tmpl_id, name) in tmpls {
+            ui.label(RichText::new("●").color(tmpl_color(tmpl_id)));
+            let name = if name.is_empty() { "(no template)" } else { name };
+            ui.label(RichText::new(name).size(11.0).color(faded_color(ui)));
+            ui.add_space(8.0);
+        }
+    });
+}
+
+/// Show the graph on a canvas that can be panned (by dragging it) and zoomed (by scrolling).
+fn show_canvas(ctx: &mut CogsApp, ui: &mut Ui) {
+    let ectx = ui.ctx().clone();
+    let (resp, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+    let rect = resp.rect;
+    painter.rect_filled(rect, 4.0, ui.visuals().extreme_bg_color);
+
+    let graph = &mut ctx.state.graph;
+    if resp.dragged() && graph.dragged.is_none() {
+        graph.pan += resp.drag_delta();
+    }
+    if resp.hovered() {
+        let (scroll, zoom_delta) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+        let factor = (scroll * 0.002).exp() * zoom_delta;
+        if factor != 1.0 {
+            let zoom = (graph.zoom * factor).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1);
+            // The point under the pointer stays in place.
+            if let Some(pointer) = resp.hover_pos() {
+                let at = (pointer - rect.center() - graph.pan) / graph.zoom;
+                graph.pan = pointer - rect.center() - at * zoom;
+            }
+            graph.zoom = zoom;
+        }
+    }
+
+    if graph.animating || graph.dragged.is_some() {
+        let moved = graph.layout.step(&graph.edge_ends(), graph.dragged.as_ref());
+        graph.animating = moved > 0.5;
+        ectx.request_repaint();
+    }
+
+    let zoom = graph.zoom;
+    let origin = rect.center() + graph.pan;
+    let to_screen = |pos: Vec2| origin + pos * zoom;
+    let radius = (NODE_RADIUS * zoom).max(3.0);
+    let painter = painter.with_clip_rect(rect);
+
+    // The edges, each one as an arrow (from its source to its target) with its name in the middle.
+    let edge_color = ui.visuals().text_color().gamma_multiply(0.35);
+    for edge in &graph.edges {
+        let (Some(source), Some(target)) = (
+            graph.layout.position(&edge.source_item_id),
+            graph.layout.position(&edge.target_item_id),
+        ) else {
+            continue;
+        };
+        let (from, to) = (to_screen(source), to_screen(target));
+        let dir = (to - from).normalized();
+        if !dir.is_finite() {
+            continue;
+        }
+        let start = from + dir * radius;
+        let end = to - dir * radius;
+        painter.arrow(start, end - start, Stroke::new(1.0, edge_color));
+        if zoom >= 0.6 {
+            painter.text(
+                start + (end - start) / 2.0,
+                Align2::CENTER_BOTTOM,
+                &edge.name,
+                FontId::proportional(10.0),
+                faded_color(ui),
+            );
+        }
+    }
+
+    // The nodes, on top of the edges.
+    let mut expand: Option<Id> = None;
+    let mut open: Option<Id> = None;
+    let mut new_root: Option<Id> = None;
+    let nodes = graph.nodes.clone();
+    for node in &nodes {
+        let Some(pos) = graph.layout.position(&node.id) else {
+            continue;
+        };
+        let center = to_screen(pos);
+        let node_rect = Rect::from_center_size(center, Vec2::splat(radius * 2.0));
+        if !rect.intersects(node_rect) {
+            continue;
+        }
+        let node_resp = ui
+            .interact(node_rect, ui.id().with(("graph_node", &node.id)), Sense::click_and_drag())
+            .on_hover_cursor(CursorIcon::PointingHand)
+            .on_hover_text(node_hover_text(node));
+
+        if node_resp.drag_started() {
+            graph.dragged = Some(node.id.clone());
+        }
+        if node_resp.dragged() {
+            graph.layout.set_position(&node.id, pos + node_resp.drag_delta() / zoom);
+        }
+        if node_resp.drag_stopped() {
+            graph.dragged = None;
+            graph.animating = true;
+        }
+        if node_resp.clicked() {
+            graph.selected = Some(node.id.clone());
+        }
+        if node_resp.double_clicked() {
+            expand = Some(node.id.clone());
+        }
+        node_resp.context_menu(|ui| {
+            if ui.button("Open").clicked() {
+                open = Some(node.id.clone());
+                ui.close();
+            }
+            if ui.button("Expand neighbors").clicked() {
+                expand = Some(node.id.clone());
+                ui.close();
+            }
+            if ui.button("Show its graph").clicked() {
+                new_root = Some(node.id.clone());
+                ui.close();
+            }
+        });
+
+        let is_root = graph.root.as_ref() == Some(&node.id);
+        let is_selected = graph.selected.as_ref() == Some(&node.id);
+        let stroke = if is_selected {
+            Stroke::new(2.5, ui.visuals().selection.stroke.color)
+        } else if is_root {
+            Stroke::new(2.5, ui.visuals().text_color())
+        } else {
+            Stroke::new(1.0, ui.visuals().text_color().gamma_multiply(0.5))
+        };
+        painter.circle(center, radius, tmpl_color(node.tmpl_id.as_ref()), stroke);
+        if zoom >= 0.4 {
+            painter.text(
+                center + Vec2::new(0.0, radius + 2.0),
+                Align2::CENTER_TOP,
+                node_label(node),
+                FontId::proportional(11.0),
+                ui.visuals().text_color(),
+            );
+        }
+    }
+
+    if let Some(id) = expand
+        && !graph.pending.contains(&id)
+    {
+        graph.pending.push(id.clone());
+        let params = graph.expand_params();
+        ctx.state.data.fetch_item_graph(id, &params, &ectx, ctx.sendr.clone());
+    }
+    if let Some(id) = open {
+        open_item_window(ctx, &id, &ectx);
+    }
+    if let Some(id) = new_root {
+        ctx.state.graph.set_root(id);
+    }
+}
+
+/// The color of the nodes of an item template. It's the same one (for a template) on each run.
+fn tmpl_color(tmpl_id: Option<&Id>) -> Color32 {
+    let Some(tmpl_id) = tmpl_id else {
+        return Color32::GRAY;
+    };
+    // FNV-1a, as it's stable (unlike the std hasher).
+    let hash = tmpl_id.to_string().bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let hue = (hash % 360) as f32 / 360.0;
+    Hsva::new(hue, 0.5, 0.85, 1.0).into()
+}
+
+fn node_label(node: &GraphNode) -> String {
+    let mut label: String = node.listing_attr_value.chars().take(NODE_LABEL_MAX_CHARS).collect();
+    if node.listing_attr_value.chars().count() > NODE_LABEL_MAX_CHARS {
+        label.push('…');
+    }
+    label
+}
+
+fn node_hover_text(node: &GraphNode) -> String {
+    let tmpl = if node.tmpl_name.is_empty() {
+        "(no template)"
+    } else {
+        node.tmpl_name.as_str()
+    };
+    format!("{}\n{tmpl}, {} link(s) away", node.listing_attr_value, node.depth)
+}
@@ -0,0 +1,162 @@
+use crate::graph::GraphLayout;
+use cogs_shared::{
+    app::{AppError, AppResult},
+    domain::model::Id,
+    dtos::{GraphDirection, GraphEdge, GraphNode, GraphParams, ItemGraph},
+};
+use egui::Vec2;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GraphViewState {
+    /// The item whose graph (neighborhood) is shown.
+    pub root: Option<Id>,
+
+    /// How many links away from the root item to go.
+    pub depth: u32,
+
+    pub direction: GraphDirection,
+
+    /// The names of the links to follow, comma separated. All the links are followed if it's blank.
+    pub links: String,
+
+    #[serde(skip)]
+    pub nodes: Vec<GraphNode>,
+
+    #[serde(skip)]
+    pub edges: Vec<GraphEdge>,
+
+    #[serde(skip)]
+    pub layout: GraphLayout,
+
+    /// Whether the layout is still settling, thus the view must be repainted.
+    #[serde(skip)]
+    pub animating: bool,
+
+    /// The offset of the graph's origin from the center of the canvas.
+    #[serde(skip)]
+    pub pan: Vec2,
+
+    #[serde(skip)]
+    pub zoom: f32,
+
+    /// The node that is being dragged, if any.
+    #[serde(skip)]
+    pub dragged: Option<Id>,
+
+    /// The node that was clicked last, if any.
+    #[serde(skip)]
+    pub selected: Option<Id>,
+
+    /// The items whose graphs are being fetched.
+    #[serde(skip)]
+    pub pending: Vec<Id>,
+
+    /// Whether some nodes were left out (by the server), since there were too many.
+    #[serde(skip)]
+    pub truncated: bool,
+
+    #[serde(skip)]
+    pub err: Option<AppError>,
+}
+
+impl Default for GraphViewState {
+    fn default() -> Self {
+        Self {
+            root: None,
+            depth: 2,
+            direction: GraphDirection::Both,
+            links: String::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            layout: GraphLayout::default(),
+            animating: false,
+            pan: Vec2::ZERO,
+            zoom: 1.0,
+            dragged: None,
+            selected: None,
+            pending: Vec::new(),
+            truncated: false,
+            err: None,
+        }
+    }
+}
+
+impl GraphViewState {
+    /// Show the graph of the provided item, from scratch.
+    pub fn set_root(&mut self, id: Id) {
+        self.root = Some(id);
+        self.reset();
+    }
+
+    /// Forget the shown graph, so it gets fetched again.
+    pub fn reset(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+        self.layout.clear();
+        self.pan = Vec2::ZERO;
+        self.selected = None;
+        self.dragged = None;
+        self.truncated = false;
+        self.err = None;
+    }
+
+    /// The params of fetching the graph of the root item.
+    pub fn params(&self) -> GraphParams {
+        GraphParams {
+            depth: Some(self.depth),
+            links: Some(self.links.clone()).filter(|links| !links.trim().is_empty()),
+            direction: self.direction,
+        }
+    }
+
+    /// The params of expanding a node, by getting its direct neighbors.
+    pub fn expand_params(&self) -> GraphParams {
+        GraphParams {
+            depth: Some(1),
+            ..self.params()
+        }
+    }
+
+    pub fn node(&self, id: &Id) -> Option<&GraphNode> {
+        self.nodes.iter().find(|n| &n.id == id)
+    }
+
+    /// Take the fetched graph of an item: the root's one replaces the shown graph, if that's empty,
+    /// otherwise (as on expanding a node) the new nodes and edges are added to it, around that item.
+    pub fn on_graph_fetched(&mut self, id: Id, graph: AppResult<ItemGraph>) {
+        self.pending.retain(|p| p != &id);
+        let graph = match graph {
+            Ok(graph) => graph,
+            Err(err) => {
+                self.err = Some(err);
+                return;
+            }
+        };
+        self.err = None;
+        self.truncated |= graph.truncated;
+
+        let around = self.layout.position(&id).unwrap_or(Vec2::ZERO);
+        for node in graph.nodes {
+            if self.node(&node.id).is_none() {
+                self.layout.add_near(&node.id, around);
+                self.nodes.push(node);
+            }
+        }
+        for edge in graph.edges {
+            if !self.edges.iter().any(|e| e.id == edge.id) {
+                self.edges.push(edge);
+            }
+        }
+        self.animating = true;
+    }
+
+    /// The (source, target) pairs of the edges, as needed by the layout.
+    pub fn edge_ends(&self) -> Vec<(Id, Id)> {
+        self.edges
+            .iter()
+            .map(|e| (e.source_item_id.clone(), e.target_item_id.clone()))
+            .collect()
+    }
+}
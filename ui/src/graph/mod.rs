@@ -0,0 +1,8 @@
+mod graph_layout;
+pub use graph_layout::*;
+
+mod graph_view;
+pub use graph_view::*;
+
+mod graph_view_state;
+pub use graph_view_state::*;
@@ -4,10 +4,12 @@ mod app;
 pub use app::CogsApp;
 
 mod app_msg_handlers;
-pub(self) use app_msg_handlers::*;
+use app_msg_handlers::*;
 
 pub mod explore;
 
+pub mod graph;
+
 mod colors;
 mod constants;
 
@@ -1,14 +1,24 @@
 use cogs_shared::{
     app::{AppError, AppResult},
-    domain::model::{
-        AccessLevel, Id, UserAccount,
-        meta::{AttrTemplate, ItemTemplate, Kind},
+    domain::{
+        logic::ItemTemplateChangePlan,
+        model::{
+            AccessLevel, ApiToken, Id, Revision, UserAccount,
+            meta::{AttrTemplate, Dependency, Item, ItemLink, ItemTemplate, Kind},
+        },
+    },
+    dtos::{
+        ApiTokenCreated, BundleImportReport, DownloadToken, ItemGraph, ItemsImportReport, ItemsParams, Page, PageRequest,
+        SearchHitsGroup, TrashEntry,
     },
 };
+use serde_json::Value;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum UiMessage {
     Login(Result<Option<(UserAccount, String)>, AppError>),
+    /// The login was refused, and another one may be attempted only after the provided number of seconds.
+    LoginRetryAfter(u64),
     Logout,
 
     Settings,
@@ -21,8 +31,73 @@ pub enum UiMessage {
     ElementCreated(Kind, AppResult<Id>),
     ElementUpdated(Kind, AppResult<Id>),
     ElementDeleted(Kind, AppResult<Id>),
+    /// The save of the element (with the provided id) was refused, since it was changed by someone else meanwhile.
+    /// It includes the user's copy and the current (saved) copy of the element.
+    ElementSaveConflict(Kind, Id, Value, Value),
+    /// The element was taken out of the Trash.
+    ElementRestored(Kind, AppResult<Id>),
+    /// The element (that was in the Trash) was deleted for good.
+    ElementPurged(Kind, AppResult<Id>),
 
     AttrTemplatesFetched(Result<Vec<AttrTemplate>, AppError>),
     ItemTemplatesFetched(Result<Vec<ItemTemplate>, AppError>),
     AccessLevelsFetched(Result<Vec<AccessLevel>, AppError>),
+    UsersFetched(AppResult<Vec<UserAccount>>),
+    /// The user of the window with the provided id (the zero one, for a new user) was saved.
+    UserSaved(Id, AppResult<Id>),
+    /// The user with the provided id was disabled (if `true`) or enabled.
+    UserDisabledSet(Id, bool, AppResult<Id>),
+    /// The user with the provided id must set a new password on the next login.
+    UserPasswordResetForced(Id, AppResult<Id>),
+    /// The user with the provided id was unlocked, after being locked due to repeated failed logins.
+    UserUnlocked(Id, AppResult<Id>),
+    /// The profile of the user that is logged in was updated, the account being included as saved.
+    ProfileUpdated(AppResult<UserAccount>),
+    /// The password of the user that is logged in was changed.
+    PasswordChanged(AppResult<Id>),
+    /// The API tokens of the user that is logged in were fetched.
+    ApiTokensFetched(AppResult<Vec<ApiToken>>),
+    /// An API token was created for the user that is logged in, its secret being included (just this once).
+    ApiTokenCreated(AppResult<ApiTokenCreated>),
+    /// The API token with the provided id was revoked.
+    ApiTokenRevoked(Id, AppResult<Id>),
+    /// A download token was issued, for downloading the export at the provided URL.
+    DownloadTokenIssued(String, AppResult<DownloadToken>),
+    /// A page of the items (that match the provided parameters) was fetched.
+    ItemsFetched(ItemsParams, PageRequest, AppResult<Page<Item>>),
+    /// The item with the provided id was fetched (on its own).
+    ItemFetched(Id, AppResult<Item>),
+    /// The items that can be link targets (of the provided item template, or of any if `None`) were fetched.
+    LinkTargetsFetched(Option<Id>, AppResult<Page<Item>>),
+
+    /// The outgoing links of the item (with the provided id) were fetched.
+    ItemLinksFetched(Id, AppResult<Vec<ItemLink>>),
+    /// A link was created from the item with the provided id.
+    ItemLinkCreated(Id, AppResult<Id>),
+    /// A link was deleted from the item with the provided id.
+    ItemLinkDeleted(Id, AppResult<Id>),
+
+    /// The elements that use the template (with the provided id) were fetched.
+    UsagesFetched(Id, AppResult<Vec<Dependency>>),
+
+    /// The revisions of the element (with the provided id) were fetched.
+    RevisionsFetched(Id, AppResult<Vec<Revision>>),
+
+    /// The elements that are in the Trash were fetched.
+    TrashFetched(AppResult<Vec<TrashEntry>>),
+
+    /// The plan of changing the item template (with the provided id) was computed.
+    ItemTemplateChangePlanned(Id, AppResult<ItemTemplateChangePlan>),
+
+    /// The items were imported (or, on a dry run, checked) from a CSV text.
+    ItemsImported(AppResult<ItemsImportReport>),
+
+    /// A workspace bundle was imported.
+    BundleImported(AppResult<BundleImportReport>),
+
+    /// The graph (neighborhood) of the item (with the provided id) was fetched.
+    ItemGraphFetched(Id, AppResult<ItemGraph>),
+
+    /// The search (for the provided query) was done.
+    SearchDone(String, AppResult<Vec<SearchHitsGroup>>),
 }
@@ -1,8 +1,8 @@
 use crate::{
+    CogsApp,
     comps::AppComponent,
     constants::{EXPLORE_ELEMENT, ICON_USER},
     utils::strong_separator,
-    CogsApp,
 };
 use cogs_shared::domain::model::AccessLevel;
 use egui::{CursorIcon, FontId, Grid, Label, RichText, TextStyle};
@@ -37,6 +37,11 @@ impl AppComponent for AccessLevelPreview {
                 ui.add_enabled(false, Label::new(RichText::new("description")));
                 ui.add(Label::new(element.description.as_deref().unwrap_or_default()));
                 ui.end_row();
+
+                ui.add_enabled(false, Label::new(RichText::new("rank")));
+                ui.add(Label::new(element.rank.to_string()))
+                    .on_hover_text("The higher it is, the more restricted the items with this access level are.");
+                ui.end_row();
             });
 
             ui.add_space(4.0);
@@ -0,0 +1,36 @@
+use crate::{
+    CogsApp,
+    comps::{Dropdown, DropdownItem, DropdownStyle},
+};
+use cogs_shared::domain::model::Id;
+use egui::Ui;
+
+/// Show the selection of the access level of an element, where none means that it's seen by everyone.
+/// The access levels are listed from the least to the most restricted one, and they get fetched if needed.
+/// It returns the selected access level, once it's changed.
+pub fn access_level_select(
+    app: &mut CogsApp,
+    ui: &mut Ui,
+    id: egui::Id,
+    selected: &Option<Id>,
+    enabled: bool,
+) -> Option<Option<Id>> {
+    if app.state.data.should_fetch_access_levels() {
+        app.state.data.fetch_all_access_levels(ui.ctx(), app.sendr.clone());
+    }
+
+    let mut access_levels = app.state.data.get_access_levels();
+    access_levels.sort_by(|a, b| a.rank.cmp(&b.rank).then_with(|| a.name.cmp(&b.name)));
+    let mut items = vec![DropdownItem {
+        italic: true,
+        ..DropdownItem::new("(none)", None)
+    }];
+    items.extend(access_levels.into_iter().map(|al| DropdownItem::new(al.name, Some(al.id))));
+
+    let style = DropdownStyle {
+        min_width: 160.0,
+        ..Default::default()
+    };
+    ui.add_enabled_ui(enabled, |ui| Dropdown::show(ui, id, selected, &items, style))
+        .inner
+}
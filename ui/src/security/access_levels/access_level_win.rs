@@ -1,6 +1,6 @@
-use crate::{comps::AppComponent, constants::EXPLORE_ELEMENT, CogsApp};
+use crate::{CogsApp, comps::AppComponent, constants::EXPLORE_ELEMENT};
 use cogs_shared::domain::model::{AccessLevel, Id};
-use egui::{vec2, Align, Button, CursorIcon, Layout, Margin, RichText, TextEdit, Window};
+use egui::{Align, Button, CursorIcon, Layout, Margin, RichText, TextEdit, Window, vec2};
 use egui_material_icons::{
     icon_button, icon_text,
     icons::{ICON_CLOSE, ICON_DELETE, ICON_EDIT, ICON_INFO},
@@ -62,6 +62,7 @@ impl AppComponent for AccessLevelWindow {
         let id = element.id.clone();
         let mut name = element.name.clone();
         let mut description = element.description.clone().unwrap_or_default();
+        let mut rank = element.rank.to_string();
         let mut should_close = false;
 
         Window::new(format!("access_level_{}_win", element.id))
@@ -79,6 +80,8 @@ impl AppComponent for AccessLevelWindow {
                     Self::render_field(ui, "name", &mut name);
                     ui.add_space(22.0);
                     Self::render_field(ui, "description", &mut description);
+                    ui.add_space(22.0);
+                    Self::render_field(ui, "rank", &mut rank);
                 })
                 .response
                 .on_hover_cursor(CursorIcon::Grab);
@@ -3,3 +3,6 @@ pub use access_level_preview::*;
 
 mod access_level_win;
 pub use access_level_win::*;
+
+mod access_level_select;
+pub use access_level_select::*;
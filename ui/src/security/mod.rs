@@ -1,2 +1,5 @@
 mod access_levels;
 pub use access_levels::*;
+
+mod users;
+pub use users::*;
@@ -0,0 +1,5 @@
+mod user_preview;
+pub use user_preview::*;
+
+mod user_win;
+pub use user_win::*;
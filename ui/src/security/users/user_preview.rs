@@ -0,0 +1,82 @@
+use crate::{
+    CogsApp,
+    comps::AppComponent,
+    constants::{EXPLORE_ELEMENT, ICON_USER},
+    utils::strong_separator,
+};
+use cogs_shared::domain::model::UserAccount;
+use egui::{CursorIcon, FontId, Grid, Label, RichText, TextStyle};
+
+pub struct UserPreview {}
+
+impl AppComponent for UserPreview {
+    type Context = CogsApp;
+
+    /// It shows the properties of a user.
+    /// It expects to get the user in `ui`'s `.data()` key named `EXPLORE_ELEMENT`.
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        let element = ui
+            .ctx()
+            .data(|d| d.get_temp::<UserAccount>(egui::Id::from(EXPLORE_ELEMENT)))
+            .clone()
+            .unwrap_or_default();
+
+        ui.label(format!("{} {}", ICON_USER, element.username.as_str()))
+            .on_hover_cursor(CursorIcon::Help)
+            .on_hover_text("This is a user.");
+
+        ui.add_space(4.0);
+        strong_separator(ui, ui.available_width());
+        ui.add_space(2.0);
+
+        let access_level = element
+            .access_level_id
+            .as_ref()
+            .and_then(|id| ctx.state.data.get_access_levels().into_iter().find(|al| al.id == *id))
+            .map(|al| al.name)
+            .unwrap_or_else(|| "(none)".to_string());
+
+        ui.scope(|ui| {
+            let base = FontId::proportional(12.0);
+            ui.style_mut().text_styles.insert(TextStyle::Body, base);
+
+            Grid::new("explore_curr_elem_preview").num_columns(2).show(ui, |ui| {
+                ui.add_enabled(false, Label::new(RichText::new("name")));
+                ui.add(Label::new(element.name.as_str()));
+                ui.end_row();
+
+                ui.add_enabled(false, Label::new(RichText::new("email")));
+                ui.add(Label::new(element.email.as_str()));
+                ui.end_row();
+
+                ui.add_enabled(false, Label::new(RichText::new("access level")));
+                ui.add(Label::new(access_level))
+                    .on_hover_text("The user sees only the items up to this access level.");
+                ui.end_row();
+
+                ui.add_enabled(false, Label::new(RichText::new("permissions")));
+                ui.add(Label::new(element.permissions.join(", ")));
+                ui.end_row();
+
+                ui.add_enabled(false, Label::new(RichText::new("state")));
+                let state = match (element.disabled, element.locked) {
+                    (true, _) => "disabled",
+                    (false, true) => "locked",
+                    (false, false) => "active",
+                };
+                ui.add(Label::new(state));
+                ui.end_row();
+
+                if element.must_reset_password {
+                    ui.add_enabled(false, Label::new(RichText::new("password")));
+                    ui.add(Label::new("must be reset"))
+                        .on_hover_text("The user must set a new password on the next login.");
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(4.0);
+            strong_separator(ui, ui.available_width());
+        });
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{CogsApp, comps::AppComponent, constants::EXPLORE_ELEMENT, security::access_level_select};
+use cogs_shared::{
+    domain::model::{Id, PERMISSIONS, UserAccount},
+    dtos::UserUpsertRequest,
+};
+use egui::{Align, Button, Checkbox, Color32, CursorIcon, Grid, Label, Layout, Margin, RichText, TextEdit, Window, vec2};
+use egui_material_icons::{
+    icon_button, icon_text,
+    icons::{ICON_CLOSE, ICON_INFO},
+};
+
+pub struct UserWindow;
+
+impl UserWindow {
+    fn render_header(ui: &mut egui::Ui, element: &UserAccount) -> bool {
+        let mut should_close = false;
+
+        ui.horizontal(|ui| {
+            ui.add_space(18.0);
+            let title = if element.id.is_zero() { "New User" } else { "User" };
+            ui.label(RichText::new(title).heading());
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                ui.add_space(12.0);
+
+                if icon_button(ui, ICON_CLOSE)
+                    .on_hover_text("Close")
+                    .on_hover_cursor(CursorIcon::PointingHand)
+                    .clicked()
+                {
+                    should_close = true;
+                }
+
+                if !element.id.is_zero() {
+                    ui.label(icon_text(ICON_INFO).size(18.0))
+                        .on_hover_text(format!("id: {}", element.id))
+                        .on_hover_cursor(CursorIcon::Help);
+                }
+            });
+        });
+
+        should_close
+    }
+
+    fn render_form_grid(app: &mut CogsApp, ui: &mut egui::Ui, element: &mut UserAccount, password: &mut String) {
+        Grid::new(format!("user_win_{}_grid", element.id))
+            .spacing([10.0, 10.0])
+            .num_columns(2)
+            .show(ui, |ui| {
+                Self::row_text(ui, "username", &mut element.username);
+                Self::row_text(ui, "name", &mut element.name);
+                Self::row_text(ui, "email", &mut element.email);
+                Self::row_text(ui, "bio", &mut element.bio);
+
+                ui.add_enabled(false, Label::new("access level"));
+                let select_id = egui::Id::new(format!("user_win_{}_access_level", element.id));
+                if let Some(access_level_id) = access_level_select(app, ui, select_id, &element.access_level_id, true) {
+                    element.access_level_id = access_level_id;
+                }
+                ui.end_row();
+
+                ui.add_enabled(false, Label::new("permissions"));
+                ui.vertical(|ui| {
+                    for perm in PERMISSIONS {
+                        let mut granted = element.permissions.iter().any(|p| p == perm);
+                        if ui.add(Checkbox::new(&mut granted, perm)).changed() {
+                            element.permissions.retain(|p| p != perm);
+                            if granted {
+                                element.permissions.push(perm.to_string());
+                            }
+                        }
+                    }
+                });
+                ui.end_row();
+
+                let label = if element.id.is_zero() { "password" } else { "new password" };
+                ui.add_enabled(false, Label::new(label));
+                let hint = if element.id.is_zero() { "" } else { "keep the current one" };
+                ui.add(TextEdit::singleline(password).password(true).hint_text(hint));
+                ui.end_row();
+            });
+    }
+
+    fn row_text(ui: &mut egui::Ui, label: &str, value: &mut String) {
+        ui.add_enabled(false, Label::new(label));
+        ui.add(TextEdit::singleline(value).desired_width(280.0));
+        ui.end_row();
+    }
+
+    /// Render the buttons of saving, cancelling, disabling (or enabling) and unlocking the user, and of forcing its password reset.
+    /// It returns whether the window should be closed.
+    fn render_footer_buttons(
+        app: &mut CogsApp,
+        ui: &mut egui::Ui,
+        ectx: &egui::Context,
+        element: &UserAccount,
+        password: &str,
+    ) -> bool {
+        let mut should_close = false;
+
+        ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+            ui.add_space(18.0);
+
+            let enabled = !element.username.trim().is_empty() && (!element.id.is_zero() || !password.is_empty());
+            let resp = ui
+                .add_enabled(enabled, Button::new("    Save    "))
+                .on_disabled_hover_text("Provide at least a username and, for a new user, a password.");
+            if resp.clicked() {
+                let req = UserUpsertRequest {
+                    user: element.clone(),
+                    password: (!password.is_empty()).then(|| password.to_string()),
+                };
+                // The window is closed once the user is saved, otherwise the error is shown.
+                app.state.explore.user_cu_err.remove(&element.id);
+                app.state.data.save_user(element.id.clone(), req, ectx, app.sendr.clone());
+            }
+
+            ui.add_space(8.0);
+
+            if ui.button("  Cancel  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                should_close = true;
+            }
+
+            if !element.id.is_zero() {
+                ui.with_layout(Layout::left_to_right(Align::Min), |ui| {
+                    ui.add_space(18.0);
+                    let label = if element.disabled { "  Enable   " } else { "  Disable  " };
+                    let resp = ui
+                        .button(label)
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .on_hover_text("A disabled user cannot log in anymore.");
+                    if resp.clicked() {
+                        app.state.explore.user_cu_err.remove(&element.id);
+                        app.state
+                            .data
+                            .set_user_disabled(element.id.clone(), !element.disabled, ectx, app.sendr.clone());
+                    }
+
+                    ui.add_space(8.0);
+                    let resp = ui
+                        .add_enabled(!element.must_reset_password, Button::new("  Force password reset  "))
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .on_hover_text("The user must set a new password on the next login.")
+                        .on_disabled_hover_text("The user must already set a new password on the next login.");
+                    if resp.clicked() {
+                        app.state.explore.user_cu_err.remove(&element.id);
+                        app.state
+                            .data
+                            .force_password_reset(element.id.clone(), ectx, app.sendr.clone());
+                    }
+
+                    if element.locked {
+                        ui.add_space(8.0);
+                        let resp = ui
+                            .button("  Unlock  ")
+                            .on_hover_cursor(CursorIcon::PointingHand)
+                            .on_hover_text("The user is temporarily locked, due to repeated failed logins.");
+                        if resp.clicked() {
+                            app.state.explore.user_cu_err.remove(&element.id);
+                            app.state.data.unlock_user(element.id.clone(), ectx, app.sendr.clone());
+                        }
+                    }
+                });
+            }
+        });
+
+        should_close
+    }
+}
+
+impl AppComponent for UserWindow {
+    type Context = CogsApp;
+
+    /// Shows the window of creating or editing a user.
+    /// In `ui.ctx().data` it expects a `UserAccount` under `EXPLORE_ELEMENT`.
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
+        let ectx = ui.ctx().clone();
+        let mut element = ectx
+            .data(|d| d.get_temp::<UserAccount>(egui::Id::from(EXPLORE_ELEMENT)))
+            .unwrap_or_default();
+
+        let id = element.id.clone();
+        let mut password = ctx.state.explore.user_cu_password.get(&id).cloned().unwrap_or_default();
+        let mut should_close = false;
+
+        Window::new(format!("user_{}_win", element.id))
+            .title_bar(false)
+            .resizable(true)
+            .default_size(vec2(520.0, 420.0))
+            .min_size(vec2(420.0, 320.0))
+            .frame(egui::Frame::window(&ectx.global_style()).inner_margin(Margin::symmetric(24, 20)))
+            .show(&ectx, |ui| {
+                ui.vertical(|ui| {
+                    should_close |= Self::render_header(ui, &element);
+                    ui.add_space(24.0);
+
+                    Self::render_form_grid(ctx, ui, &mut element, &mut password);
+                    ui.add_space(16.0);
+
+                    if let Some(err) = ctx.state.explore.user_cu_err.get(&id) {
+                        ui.label(RichText::new(err.to_string()).color(Color32::RED));
+                        ui.add_space(8.0);
+                    }
+
+                    should_close |= Self::render_footer_buttons(ctx, ui, &ectx, &element, &password);
+                });
+            });
+
+        if should_close {
+            close_user_window(ctx, &id);
+        } else if ctx.state.explore.open_windows_user.contains_key(&id) {
+            ctx.state.explore.open_windows_user.insert(id.clone(), element);
+            ctx.state.explore.user_cu_password.insert(id, password);
+        }
+    }
+}
+
+/// Close the window of a user (with the provided id, or the zero one for a new user), forgetting its state.
+pub fn close_user_window(ctx: &mut CogsApp, id: &Id) {
+    ctx.state.explore.open_windows_user.remove(id);
+    ctx.state.explore.user_cu_password.remove(id);
+    ctx.state.explore.user_cu_err.remove(id);
+}
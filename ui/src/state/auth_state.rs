@@ -17,6 +17,18 @@ pub struct AuthState {
 
     pub login_error: Option<AppError>,
 
+    #[serde(skip)]
+    /// The user must set a new password (as an admin requested it) to log in.
+    pub login_reset_required: bool,
+
+    #[serde(skip)]
+    /// The new password, when the user must reset it to log in.
+    pub new_pass: String,
+
+    #[serde(skip)]
+    /// When (in the UI's time, in seconds) another login may be attempted, as the server hinted it.
+    pub login_retry_until: Option<f64>,
+
     pub user_account: Option<UserAccount>,
     pub user_session: Option<String>,
 }
@@ -1,19 +1,36 @@
-use crate::messages::UiMessage;
+use crate::{constants::SESSION_HEADER, messages::UiMessage};
 use cogs_shared::{
     app::{AppError, AppResult},
-    domain::model::{
-        AccessLevel, Id,
-        meta::{AttrTemplate, ItemTemplate, Kind},
+    domain::{
+        logic::{AttrViolation, ItemTemplateChangePlan, OrphanAttrsPolicy},
+        model::{
+            AccessLevel, ApiToken, Id, Revision, UserAccount,
+            meta::{AttrTemplate, Dependency, Item, ItemLink, ItemTemplate, Kind},
+        },
+    },
+    dtos::{
+        ApiTokenCreateRequest, ApiTokenCreated, BundleImportReport, BundleImportRequest, DownloadToken, ErrorResponse,
+        ExportFormat, GraphParams, IdDto, ItemGraph, ItemsImportReport, ItemsImportRequest, ItemsParams, PAGE_DEFAULT_LIMIT,
+        PAGE_MAX_LIMIT, Page, PageRequest, PasswordChangeRequest, ProfileUpdateRequest, SearchHitsGroup, SortDirection,
+        TrashEntry, UserUpsertRequest,
     },
-    dtos::IdDto,
 };
+use percent_encoding::{NON_ALPHANUMERIC, utf8_percent_encode};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::sync::mpsc::Sender;
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::mpsc::Sender,
+};
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DataState {
+    /// The id of the user session, sent (as the `Authorization` header) with every request.
+    #[serde(skip)]
+    session: Option<String>,
+
     #[serde(skip)]
     attr_templates: Vec<AttrTemplate>,
 
@@ -34,6 +51,92 @@ pub struct DataState {
 
     #[serde(skip)]
     access_levels_fetch_requested: bool,
+
+    #[serde(skip)]
+    users: Vec<UserAccount>,
+
+    #[serde(skip)]
+    fetched_users: bool,
+
+    #[serde(skip)]
+    users_fetch_requested: bool,
+
+    /// The (already fetched pages of) items that are shown in the Explore table.
+    #[serde(skip)]
+    items: Vec<Item>,
+
+    /// The total number of items (that match `items_params`), known once their first page is fetched.
+    #[serde(skip)]
+    items_total: Option<u64>,
+
+    #[serde(skip)]
+    items_fetch_requested: bool,
+
+    /// The error of fetching the last page of items (if any).
+    #[serde(skip)]
+    items_err: Option<AppError>,
+
+    /// The filter (in the `ItemQuery` syntax) and the item template of the items that are shown in the Explore table.
+    #[serde(skip)]
+    items_params: ItemsParams,
+
+    /// The field (and direction) that the items are sorted by.
+    #[serde(skip)]
+    items_sort: Option<String>,
+    #[serde(skip)]
+    items_sort_dir: SortDirection,
+
+    /// The items that were fetched one by one, mapped by their id.
+    /// These are the ones that are needed, but may not be in the fetched pages.
+    #[serde(skip)]
+    items_by_id: HashMap<Id, Item>,
+
+    /// The ids of the items that were requested to be fetched one by one.
+    #[serde(skip)]
+    item_fetch_requested: HashSet<Id>,
+
+    /// The items that can be the targets of new links, mapped by their item template
+    /// (with `None` meaning any item).
+    #[serde(skip)]
+    link_targets: HashMap<Option<Id>, Page<Item>>,
+
+    /// The item templates (or `None` for any item) whose link targets were requested to be fetched.
+    #[serde(skip)]
+    link_targets_fetch_requested: HashSet<Option<Id>>,
+
+    /// The outgoing links of items, mapped by the (source) item id.
+    #[serde(skip)]
+    item_links: HashMap<Id, Vec<ItemLink>>,
+
+    /// The ids of the items whose links were requested to be fetched.
+    #[serde(skip)]
+    item_links_fetch_requested: HashSet<Id>,
+
+    /// The elements that use a template ("where used"), mapped by the template id.
+    #[serde(skip)]
+    usages: HashMap<Id, Vec<Dependency>>,
+
+    /// The ids of the templates whose usages were requested to be fetched.
+    #[serde(skip)]
+    usages_fetch_requested: HashSet<Id>,
+
+    /// The revisions of the elements (the newest first), mapped by the element id.
+    #[serde(skip)]
+    revisions: HashMap<Id, Vec<Revision>>,
+
+    /// The ids of the elements whose revisions were requested to be fetched.
+    #[serde(skip)]
+    revisions_fetch_requested: HashSet<Id>,
+
+    /// The (soft) deleted elements, the most recently deleted first.
+    #[serde(skip)]
+    trash: Vec<TrashEntry>,
+
+    #[serde(skip)]
+    fetched_trash: bool,
+
+    #[serde(skip)]
+    trash_fetch_requested: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -45,6 +148,11 @@ pub enum SourceType {
 impl DataState {
     //
 
+    /// Set (on login) or clear (on logout) the id of the user session that is sent with the requests.
+    pub fn set_session(&mut self, session: Option<String>) {
+        self.session = session;
+    }
+
     pub fn has_fetched_all(&self) -> bool {
         self.fetched_attr_templates && self.fetched_item_templates
     }
@@ -61,6 +169,46 @@ impl DataState {
         !self.fetched_access_levels && !self.access_levels_fetch_requested
     }
 
+    pub fn should_fetch_users(&self) -> bool {
+        !self.fetched_users && !self.users_fetch_requested
+    }
+
+    /// Tell if the first page of items should be fetched.
+    pub fn should_fetch_items(&self) -> bool {
+        self.items_total.is_none() && !self.items_fetch_requested && self.items_err.is_none()
+    }
+
+    /// Tell if there are more items (after the already fetched pages) that can be fetched.
+    pub fn should_fetch_more_items(&self) -> bool {
+        self.items_total.is_some_and(|total| (self.items.len() as u64) < total)
+            && !self.items_fetch_requested
+            && self.items_err.is_none()
+    }
+
+    pub fn should_fetch_item(&self, id: &Id) -> bool {
+        self.get_item(id).is_none() && !self.item_fetch_requested.contains(id)
+    }
+
+    pub fn should_fetch_link_targets(&self, tmpl_id: &Option<Id>) -> bool {
+        !self.link_targets.contains_key(tmpl_id) && !self.link_targets_fetch_requested.contains(tmpl_id)
+    }
+
+    pub fn should_fetch_item_links(&self, item_id: &Id) -> bool {
+        !self.item_links.contains_key(item_id) && !self.item_links_fetch_requested.contains(item_id)
+    }
+
+    pub fn should_fetch_usages(&self, id: &Id) -> bool {
+        !self.usages.contains_key(id) && !self.usages_fetch_requested.contains(id)
+    }
+
+    pub fn should_fetch_revisions(&self, id: &Id) -> bool {
+        !self.revisions.contains_key(id) && !self.revisions_fetch_requested.contains(id)
+    }
+
+    pub fn should_fetch_trash(&self) -> bool {
+        !self.fetched_trash && !self.trash_fetch_requested
+    }
+
     // ------------------------
     // Attribute Templates mgmt
     // ------------------------
@@ -71,28 +219,19 @@ impl DataState {
             "http://localhost:9010/api/attribute_templates",
             serde_json::json!(element).to_string().into_bytes(),
         );
-        req.headers.insert("content-type", "application/json");
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
-        let is_new = element.id.is_zero();
+        let (id, mine) = (element.id.clone(), serde_json::json!(element));
         ehttp::fetch(req, move |rsp| {
             log::info!("[DataState::save_attr_template] Response: {:?}", rsp);
-            if let Ok(rsp) = rsp {
-                let dto: IdDto = serde_json::from_str(rsp.text().unwrap_or_default()).unwrap_or_else(|e| {
-                    log::error!("[DataState::save_attr_template] Error: {e}");
-                    // TODO: tell the caller.
-                    IdDto::default()
-                });
-                let ui_msg = if is_new {
-                    log::debug!("[DataState::save_attr_template] Got id: {}", dto.id);
-                    UiMessage::ElementCreated(Kind::AttributeTemplate, Ok(dto.id))
-                } else {
-                    UiMessage::ElementUpdated(Kind::AttributeTemplate, Ok(dto.id))
-                };
-                if let Err(e) = sender.send(ui_msg) {
-                    log::info!("[DataState::save_attr_template] Failed to send UiMessage. Error: {e}");
-                }
-                ectx.request_repaint();
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to save attribute template".to_string(), err)),
+            };
+            if let Err(e) = sender.send(saved_message(Kind::AttributeTemplate, id, mine, ars)) {
+                log::info!("[DataState::save_attr_template] Failed to send UiMessage. Error: {e}");
             }
+            ectx.request_repaint();
         });
     }
 
@@ -115,31 +254,34 @@ impl DataState {
         self.attr_templates.clone()
     }
 
+    /// Fetch all the attribute templates (page by page), as they are all needed by the pickers.
     pub fn fetch_all_attr_templates(&self, ectx: &egui::Context, sender: Sender<UiMessage>) {
         //
-        let mut req = ehttp::Request::get("http://localhost:9010/api/attribute_templates");
-        req.headers.insert("content-type", "application/json");
         let ectx = ectx.clone();
-        ehttp::fetch(req, move |rsp| {
-            if let Ok(rsp) = rsp {
-                let data: Vec<AttrTemplate> = serde_json::from_str(rsp.text().unwrap_or_default()).unwrap();
+        fetch_all_pages::<AttrTemplate>("attribute_templates", self.session.clone(), Vec::new(), move |result| {
+            if let Ok(data) = &result {
                 log::trace!("[DataState::fetch_all_attr_templates] Got {} elements.", data.len());
-                if let Err(e) = sender.send(UiMessage::AttrTemplatesFetched(Ok(data))) {
-                    log::error!("[DataState::fetch_all_attr_templates] Failed to send UiMessage. Error: {e}");
-                }
-                ectx.request_repaint();
             }
+            if let Err(e) = sender.send(UiMessage::AttrTemplatesFetched(result)) {
+                log::error!("[DataState::fetch_all_attr_templates] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
         });
     }
 
     pub fn delete_attr_template(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
         //
         let mut req = ehttp::Request::post(format!("http://localhost:9010/api/attribute_templates/{}/delete", id), vec![]);
-        req.headers.insert("content-type", "application/json");
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
         ehttp::fetch(req, move |rsp| {
             log::trace!("[DataState::delete_attr_template] Got response: {:?}", rsp);
-            if let Err(e) = sender.send(UiMessage::ElementDeleted(Kind::AttributeTemplate, Ok(id))) {
+            // The deletion is refused (as a conflict) while the template is still used.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<serde_json::Value>(&rsp).map(|_| id),
+                Err(err) => Err(AppError::ErrDetails("failed to delete attribute template".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ElementDeleted(Kind::AttributeTemplate, ars)) {
                 log::info!("[DataState::delete_attr_template] Failed to send UiMessage. Error: {e}");
             }
             ectx.request_repaint();
@@ -150,60 +292,70 @@ impl DataState {
     // Item Templates mgmt
     // -------------------
 
-    pub fn save_item_template(&self, element: ItemTemplate, ectx: &egui::Context, sender: Sender<UiMessage>) {
+    /// Save an item template. On update, the attributes of the existing items that are
+    /// no longer declared by the template are handled according to `orphans`.
+    pub fn save_item_template(
+        &self,
+        element: ItemTemplate,
+        orphans: OrphanAttrsPolicy,
+        ectx: &egui::Context,
+        sender: Sender<UiMessage>,
+    ) {
         //
         let mut req = ehttp::Request::post(
-            "http://localhost:9010/api/item_templates",
+            format!("http://localhost:9010/api/item_templates?orphans={orphans}"),
             serde_json::json!(element).to_string().into_bytes(),
         );
-        req.headers.insert("content-type", "application/json");
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
+        let (id, mine) = (element.id.clone(), serde_json::json!(element));
         ehttp::fetch(req, move |rsp| {
             log::info!("[DataState::save_item_template] Response: {:?}", rsp);
-            match rsp {
-                Ok(rsp) => {
-                    let ars: AppResult<Id>;
-
-                    if rsp.status != 200 {
-                        ars = Err(AppError::ErrDetails(
-                            format!("{}", rsp.status),
-                            rsp.text().unwrap_or_default().into(),
-                        ));
-                    } else {
-                        let dto: IdDto = serde_json::from_str(rsp.text().unwrap_or_default()).unwrap();
-                        log::trace!("[DataState::save_item_template] Got saved id: {}", dto.id);
-                        ars = Ok(dto.id);
-                    }
-
-                    if let Err(e) = sender.send(UiMessage::ElementCreated(Kind::ItemTemplate, ars)) {
-                        log::error!("[DataState::save_item_template] Failed to send UiMessage. Error: {e}");
-                    }
-                }
-                Err(err) => {
-                    let ars = Err(AppError::from(err));
-                    if let Err(e) = sender.send(UiMessage::ElementUpdated(Kind::ItemTemplate, ars)) {
-                        log::error!("[DataState::save_item_template] Failed to send UiMessage. Error: {e}");
-                    }
-                }
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to save item template".to_string(), err)),
+            };
+            if let Err(e) = sender.send(saved_message(Kind::ItemTemplate, id, mine, ars)) {
+                log::error!("[DataState::save_item_template] Failed to send UiMessage. Error: {e}");
             }
             ectx.request_repaint();
         });
     }
 
-    pub fn fetch_all_item_templates(&self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+    /// Get (as a dry-run) the plan of changing an (existing) item template to the provided shape.
+    pub fn plan_item_template_change(&self, element: ItemTemplate, ectx: &egui::Context, sender: Sender<UiMessage>) {
         //
-        let mut req = ehttp::Request::get("http://localhost:9010/api/item_templates");
-        req.headers.insert("content-type", "application/json");
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/item_templates/plan",
+            serde_json::json!(element).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
         ehttp::fetch(req, move |rsp| {
-            if let Ok(rsp) = rsp {
-                let data: Vec<ItemTemplate> = serde_json::from_str(rsp.text().unwrap_or_default()).unwrap();
+            log::info!("[DataState::plan_item_template_change] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<ItemTemplateChangePlan>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to plan item template change".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemTemplateChangePlanned(element.id, ars)) {
+                log::error!("[DataState::plan_item_template_change] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Fetch all the item templates (page by page), as they are all needed by the pickers.
+    pub fn fetch_all_item_templates(&self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let ectx = ectx.clone();
+        fetch_all_pages::<ItemTemplate>("item_templates", self.session.clone(), Vec::new(), move |result| {
+            if let Ok(data) = &result {
                 log::trace!("[DataState::fetch_all_item_templates] Got {} elements.", data.len());
-                if let Err(e) = sender.send(UiMessage::ItemTemplatesFetched(Ok(data))) {
-                    log::info!("[DataState::fetch_all_item_templates] Failed to send UiMessage. Error: {e}");
-                }
-                ectx.request_repaint();
             }
+            if let Err(e) = sender.send(UiMessage::ItemTemplatesFetched(result)) {
+                log::info!("[DataState::fetch_all_item_templates] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
         });
     }
 
@@ -226,76 +378,1145 @@ impl DataState {
     pub fn delete_item_template(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
         //
         let mut req = ehttp::Request::post(format!("http://localhost:9010/api/item_templates/{}/delete", id), vec![]);
-        req.headers.insert("content-type", "application/json");
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
         ehttp::fetch(req, move |rsp| {
             log::trace!("[DataState::delete_item_template] Got response: {:?}", rsp);
-            if let Err(e) = sender.send(UiMessage::ElementDeleted(Kind::ItemTemplate, Ok(id))) {
+            // The deletion is refused (as a conflict) while the template is still used.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<serde_json::Value>(&rsp).map(|_| id),
+                Err(err) => Err(AppError::ErrDetails("failed to delete item template".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ElementDeleted(Kind::ItemTemplate, ars)) {
                 log::error!("[DataState::delete_item_template] Failed to send UiMessage. Error: {e}");
             }
             ectx.request_repaint();
         });
     }
 
-    pub fn fetch_all_access_levels(&mut self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+    // ----------
+    // Items mgmt
+    // ----------
+
+    pub fn save_item(&self, element: Item, ectx: &egui::Context, sender: Sender<UiMessage>) {
         //
-        self.access_levels_fetch_requested = true;
-        let mut req = ehttp::Request::get("http://localhost:9010/api/access_levels");
-        req.headers.insert("content-type", "application/json");
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/items",
+            serde_json::json!(element).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        let (id, mine) = (element.id.clone(), serde_json::json!(element));
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::save_item] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to save item".to_string(), err)),
+            };
+            if let Err(e) = sender.send(saved_message(Kind::Item, id, mine, ars)) {
+                log::error!("[DataState::save_item] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Import items from a CSV text (or, on a dry run, only check its rows).
+    pub fn import_items(&self, req: ItemsImportRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/items/import",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::trace!("[DataState::import_items] Got response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<ItemsImportReport>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to import items".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemsImported(ars)) {
+                log::error!("[DataState::import_items] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Import a workspace bundle.
+    pub fn import_bundle(&self, req: BundleImportRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/bundle/import",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::trace!("[DataState::import_bundle] Got response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<BundleImportReport>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to import the bundle".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::BundleImported(ars)) {
+                log::error!("[DataState::import_bundle] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn delete_item(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/items/{}/delete", id), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::trace!("[DataState::delete_item] Got response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) if rsp.ok => Ok(id),
+                Ok(rsp) => Err(AppError::ErrDetails(
+                    format!("HTTP {} {}", rsp.status, rsp.status_text),
+                    rsp.text().unwrap_or_default().to_string(),
+                )),
+                Err(err) => Err(AppError::ErrDetails("failed to delete item".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ElementDeleted(Kind::Item, ars)) {
+                log::error!("[DataState::delete_item] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Fetch the next page of the items (that match the current filter and item template),
+    /// in the current sort order.
+    pub fn fetch_items_page(&mut self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.items_fetch_requested = true;
+        let params = self.items_params.clone();
+        let mut page_req = PageRequest::new(self.items.len() as u32, PAGE_DEFAULT_LIMIT);
+        page_req.sort.clone_from(&self.items_sort);
+        page_req.dir = self.items_sort_dir;
+        let url = format!("http://localhost:9010/api/items?{}", items_query_string(&params, &page_req));
+        let mut req = ehttp::Request::get(url);
+        add_headers(&mut req, self.session.as_deref());
         let ectx = ectx.clone();
         ehttp::fetch(req, move |rsp| {
             let result = match rsp {
-                Ok(rsp) => decode_json_response::<Vec<AccessLevel>>(&rsp),
-                Err(err) => Err(AppError::ErrDetails("failed to fetch access levels".to_string(), err)),
+                Ok(rsp) => decode_json_response::<Page<Item>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch items".to_string(), err)),
             };
 
-            if let Ok(data) = &result {
-                log::trace!("[DataState::fetch_all_access_levels] Got {} elements.", data.len());
+            if let Ok(page) = &result {
+                log::trace!(
+                    "[DataState::fetch_items_page] Got {} of {} elements.",
+                    page.items.len(),
+                    page.total
+                );
             }
 
-            if let Err(e) = sender.send(UiMessage::AccessLevelsFetched(result)) {
-                log::error!("[DataState::fetch_all_access_levels] Failed to send UiMessage. Error: {e}");
+            if let Err(e) = sender.send(UiMessage::ItemsFetched(params, page_req, result)) {
+                log::error!("[DataState::fetch_items_page] Failed to send UiMessage. Error: {e}");
             }
             ectx.request_repaint();
         });
     }
-}
 
-fn decode_json_response<T: DeserializeOwned>(rsp: &ehttp::Response) -> AppResult<T> {
-    let body = rsp.text().unwrap_or("<response body is not valid UTF-8>");
+    /// Append a page of items. It is ignored if it is not the next page of the current items,
+    /// such as when the filter or the sort order was changed meanwhile.
+    pub fn set_items_page(&mut self, params: ItemsParams, page_req: PageRequest, result: AppResult<Page<Item>>) {
+        if params != self.items_params
+            || page_req.sort != self.items_sort
+            || page_req.dir != self.items_sort_dir
+            || page_req.offset as usize != self.items.len()
+        {
+            return;
+        }
+        self.items_fetch_requested = false;
+        match result {
+            Ok(page) => {
+                self.items.extend(page.items);
+                self.items_total = Some(page.total);
+            }
+            Err(err) => self.items_err = Some(err),
+        }
+    }
 
-    if !rsp.ok {
-        return Err(AppError::ErrDetails(
-            format!("HTTP {} {}", rsp.status, rsp.status_text),
-            body.to_string(),
-        ));
+    /// Forget the fetched pages of items, so that they get fetched again (from the first one).
+    fn reset_items(&mut self) {
+        self.items.clear();
+        self.items_total = None;
+        self.items_fetch_requested = false;
+        self.items_err = None;
     }
 
-    rsp.json()
-        .map_err(|err| AppError::ErrDetails("invalid JSON response".to_string(), format!("{err}; response body: {body}")))
-}
+    /// Forget the (cached) items and their links, so that they get fetched again when needed.
+    pub fn invalidate_items(&mut self) {
+        self.reset_items();
+        self.items_by_id.clear();
+        self.item_fetch_requested.clear();
+        self.link_targets.clear();
+        self.link_targets_fetch_requested.clear();
+        self.item_links.clear();
+        self.item_links_fetch_requested.clear();
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::decode_json_response;
-    use cogs_shared::domain::model::AccessLevel;
+    pub fn get_items(&self) -> Vec<Item> {
+        self.items.clone()
+    }
 
-    fn response(status: u16, body: &str) -> ehttp::Response {
-        ehttp::Response {
-            url: "http://localhost/api/access_levels".to_string(),
-            ok: (200..300).contains(&status),
-            status,
-            status_text: String::new(),
-            headers: ehttp::Headers::default(),
-            bytes: body.as_bytes().to_vec(),
+    /// Get the total number of items (that match the current filter and item template), once known.
+    pub fn get_items_total(&self) -> Option<u64> {
+        self.items_total
+    }
+
+    pub fn get_items_err(&self) -> Option<&AppError> {
+        self.items_err.as_ref()
+    }
+
+    /// Get an item, out of the ones that are fetched (as part of a page, or one by one).
+    pub fn get_item(&self, id: &Id) -> Option<Item> {
+        self.items
+            .iter()
+            .chain(self.link_targets.values().flat_map(|page| page.items.iter()))
+            .chain(self.items_by_id.get(id))
+            .find(|i| &i.id == id)
+            .cloned()
+    }
+
+    /// Set the filter of the items that are shown in the Explore table.
+    /// An empty one means that all the items are shown.
+    pub fn set_items_filter(&mut self, filter: String) {
+        let filter = filter.trim().to_string();
+        let params = ItemsParams {
+            filter: (!filter.is_empty()).then_some(filter),
+            ..self.items_params.clone()
+        };
+        self.set_items_params(params);
+    }
+
+    /// Set the item template of the items that are shown in the Explore table.
+    /// `None` means that the items of any template are shown.
+    pub fn set_items_tmpl_id(&mut self, tmpl_id: Option<Id>) {
+        let params = ItemsParams {
+            tmpl_id,
+            ..self.items_params.clone()
+        };
+        self.set_items_params(params);
+    }
+
+    /// Get the URL of exporting (in the provided format) the items that are shown in the Explore table.
+    /// It's `None` unless they are limited to an item template, since an export has the columns of one.
+    pub fn items_export_url(&self, format: ExportFormat) -> Option<String> {
+        let tmpl_id = self.items_params.tmpl_id.as_ref()?;
+        let mut url = format!("http://localhost:9010/api/item_templates/{tmpl_id}/items/export?format={format}");
+        if let Some(filter) = &self.items_params.filter {
+            url.push_str(&format!("&filter={}", utf8_percent_encode(filter, NON_ALPHANUMERIC)));
         }
+        Some(url)
     }
 
-    #[test]
-    fn access_level_error_object_is_not_treated_as_a_list() {
-        let result = decode_json_response::<Vec<AccessLevel>>(&response(404, r#"{"error":"not found"}"#));
+    /// Get the URL of exporting the workspace as a bundle.
+    pub fn bundle_export_url(&self) -> String {
+        "http://localhost:9010/api/bundle".to_string()
+    }
 
-        assert!(result.is_err());
+    /// Get a download token, for downloading the export at the provided URL in the browser.
+    /// Opened in the browser, the download cannot include the session header, thus it carries the token instead.
+    pub fn download_export(&self, url: String, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post("http://localhost:9010/api/account/download_token", vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            // The response is not logged, since it includes the token.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<DownloadToken>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to get a download token".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::DownloadTokenIssued(url, ars)) {
+                log::error!("[DataState::download_export] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    fn set_items_params(&mut self, params: ItemsParams) {
+        if params != self.items_params {
+            self.items_params = params;
+            self.reset_items();
+        }
+    }
+
+    /// Sort the items by the provided field. If they are already sorted by it, the direction is reversed.
+    pub fn set_items_sort(&mut self, sort: &str) {
+        if self.items_sort.as_deref() == Some(sort) {
+            self.items_sort_dir = self.items_sort_dir.toggled();
+        } else {
+            self.items_sort = Some(sort.to_string());
+            self.items_sort_dir = SortDirection::Asc;
+        }
+        self.reset_items();
+    }
+
+    /// Get the field (and direction) that the items are sorted by, if one was chosen.
+    pub fn get_items_sort(&self) -> Option<(&str, SortDirection)> {
+        self.items_sort.as_deref().map(|sort| (sort, self.items_sort_dir))
+    }
+
+    /// Fetch an item that is needed, but it is not part of the fetched pages.
+    pub fn fetch_item(&mut self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.item_fetch_requested.insert(id.clone());
+        let mut req = ehttp::Request::get(format!("http://localhost:9010/api/items/{}", id));
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Item>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch item".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemFetched(id, result)) {
+                log::error!("[DataState::fetch_item] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_item(&mut self, item: Item) {
+        self.item_fetch_requested.remove(&item.id);
+        self.items_by_id.insert(item.id.clone(), item);
+    }
+
+    /// Fetch the items that can be the targets of new links: the ones created from the provided
+    /// item template, or any item if it is `None`. Only the first (max size) page of them is fetched.
+    pub fn fetch_link_targets(&mut self, tmpl_id: Option<Id>, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.link_targets_fetch_requested.insert(tmpl_id.clone());
+        let params = ItemsParams {
+            tmpl_id: tmpl_id.clone(),
+            ..Default::default()
+        };
+        let page_req = PageRequest::new(0, PAGE_MAX_LIMIT).sorted_by("name", SortDirection::Asc);
+        let url = format!("http://localhost:9010/api/items?{}", items_query_string(&params, &page_req));
+        let mut req = ehttp::Request::get(url);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Page<Item>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch items".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::LinkTargetsFetched(tmpl_id, result)) {
+                log::error!("[DataState::fetch_link_targets] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_link_targets(&mut self, tmpl_id: Option<Id>, page: Page<Item>) {
+        self.link_targets_fetch_requested.remove(&tmpl_id);
+        self.link_targets.insert(tmpl_id, page);
+    }
+
+    pub fn get_link_targets(&self, tmpl_id: &Option<Id>) -> Option<&Page<Item>> {
+        self.link_targets.get(tmpl_id)
+    }
+
+    // ---------------
+    // Item Links mgmt
+    // ---------------
+
+    pub fn fetch_item_links(&mut self, item_id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.item_links_fetch_requested.insert(item_id.clone());
+        let mut req = ehttp::Request::get(format!("http://localhost:9010/api/items/{}/links", item_id));
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Vec<ItemLink>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch item links".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemLinksFetched(item_id, result)) {
+                log::error!("[DataState::fetch_item_links] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_item_links(&mut self, item_id: Id, data: Vec<ItemLink>) {
+        self.item_links_fetch_requested.remove(&item_id);
+        self.item_links.insert(item_id, data);
+    }
+
+    pub fn get_item_links(&self, item_id: &Id) -> Option<&Vec<ItemLink>> {
+        self.item_links.get(item_id)
+    }
+
+    /// Forget the (cached) links of an item, so that they get fetched again when needed.
+    pub fn invalidate_item_links(&mut self, item_id: &Id) {
+        self.item_links.remove(item_id);
+        self.item_links_fetch_requested.remove(item_id);
+    }
+
+    pub fn create_item_link(&self, link: ItemLink, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let source_item_id = link.source_item_id.clone();
+        let mut req = ehttp::Request::post(
+            format!("http://localhost:9010/api/items/{}/links", source_item_id),
+            serde_json::json!(link).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::create_item_link] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to create item link".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemLinkCreated(source_item_id, ars)) {
+                log::error!("[DataState::create_item_link] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn delete_item_link(&self, source_item_id: Id, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            format!("http://localhost:9010/api/items/{}/links/{}/delete", source_item_id, id),
+            vec![],
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::trace!("[DataState::delete_item_link] Got response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) if rsp.ok => Ok(id),
+                Ok(rsp) => Err(AppError::ErrDetails(
+                    format!("HTTP {} {}", rsp.status, rsp.status_text),
+                    rsp.text().unwrap_or_default().to_string(),
+                )),
+                Err(err) => Err(AppError::ErrDetails("failed to delete item link".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemLinkDeleted(source_item_id, ars)) {
+                log::error!("[DataState::delete_item_link] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    // -----------
+    // Usages mgmt
+    // -----------
+
+    /// Fetch the elements that use an attribute or item template.
+    pub fn fetch_usages(&mut self, kind: &Kind, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let path = match kind {
+            Kind::AttributeTemplate => "attribute_templates",
+            Kind::ItemTemplate => "item_templates",
+            _ => {
+                log::warn!("[DataState::fetch_usages] Usages of {kind:?} elements are not supported.");
+                return;
+            }
+        };
+        self.usages_fetch_requested.insert(id.clone());
+        let mut req = ehttp::Request::get(format!("http://localhost:9010/api/{path}/{id}/usages"));
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Vec<Dependency>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch usages".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::UsagesFetched(id, result)) {
+                log::error!("[DataState::fetch_usages] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_usages(&mut self, id: Id, data: Vec<Dependency>) {
+        self.usages_fetch_requested.remove(&id);
+        self.usages.insert(id, data);
+    }
+
+    pub fn get_usages(&self, id: &Id) -> Option<&Vec<Dependency>> {
+        self.usages.get(id)
+    }
+
+    /// Forget all the (cached) usages, so that they get fetched again when needed.
+    pub fn invalidate_usages(&mut self) {
+        self.usages.clear();
+        self.usages_fetch_requested.clear();
+    }
+
+    /// Fetch all the access levels (page by page), as they are all needed by the pickers.
+    pub fn fetch_all_access_levels(&mut self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.access_levels_fetch_requested = true;
+        let ectx = ectx.clone();
+        fetch_all_pages::<AccessLevel>("access_levels", self.session.clone(), Vec::new(), move |result| {
+            if let Ok(data) = &result {
+                log::trace!("[DataState::fetch_all_access_levels] Got {} elements.", data.len());
+            }
+
+            if let Err(e) = sender.send(UiMessage::AccessLevelsFetched(result)) {
+                log::error!("[DataState::fetch_all_access_levels] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    // ----------
+    // Users mgmt
+    // ----------
+
+    /// Fetch all the user accounts (page by page).
+    pub fn fetch_all_users(&mut self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.users_fetch_requested = true;
+        let ectx = ectx.clone();
+        fetch_all_pages::<UserAccount>("users", self.session.clone(), Vec::new(), move |result| {
+            if let Ok(data) = &result {
+                log::trace!("[DataState::fetch_all_users] Got {} elements.", data.len());
+            }
+
+            if let Err(e) = sender.send(UiMessage::UsersFetched(result)) {
+                log::error!("[DataState::fetch_all_users] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_users(&mut self, data: Vec<UserAccount>) {
+        self.users = data;
+        self.fetched_users = true;
+        self.users_fetch_requested = false;
+    }
+
+    pub fn get_users(&self) -> Vec<UserAccount> {
+        self.users.clone()
+    }
+
+    /// Create or update a user account. The result is reported for the window with the provided id
+    /// (the user's id, or the zero one for a new user).
+    pub fn save_user(&self, win_id: Id, req: UserUpsertRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/users",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            // The response is not logged as a whole, since the request included a password.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to save user".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::UserSaved(win_id, ars)) {
+                log::error!("[DataState::save_user] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Disable (so that it cannot log in anymore) or enable a user account.
+    pub fn set_user_disabled(&self, id: Id, disabled: bool, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let action = if disabled { "disable" } else { "enable" };
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/users/{id}/{action}"), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::set_user_disabled] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails(format!("failed to {action} user"), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::UserDisabledSet(id, disabled, ars)) {
+                log::error!("[DataState::set_user_disabled] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Require a user to set a new password on the next login.
+    pub fn force_password_reset(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/users/{id}/force_password_reset"), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::force_password_reset] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to force the password reset".into(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::UserPasswordResetForced(id, ars)) {
+                log::error!("[DataState::force_password_reset] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Unlock a user account that was locked due to repeated failed logins.
+    pub fn unlock_user(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/users/{id}/unlock"), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::unlock_user] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to unlock user".into(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::UserUnlocked(id, ars)) {
+                log::error!("[DataState::unlock_user] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    // ------------
+    // Account mgmt
+    // ------------
+
+    /// Update the profile (name, email and bio) of the user that is logged in.
+    pub fn update_my_profile(&self, req: ProfileUpdateRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/account/profile",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::update_my_profile] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<UserAccount>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to update the profile".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ProfileUpdated(ars)) {
+                log::error!("[DataState::update_my_profile] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Change the password of the user that is logged in.
+    pub fn change_my_password(&self, req: PasswordChangeRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/account/password",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            // The response is not logged as a whole, since the request included the passwords.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to change the password".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::PasswordChanged(ars)) {
+                log::error!("[DataState::change_my_password] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Fetch the API tokens of the user that is logged in.
+    pub fn fetch_my_api_tokens(&self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::get("http://localhost:9010/api/account/api_tokens");
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::fetch_my_api_tokens] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<Vec<ApiToken>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to get the API tokens".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ApiTokensFetched(ars)) {
+                log::error!("[DataState::fetch_my_api_tokens] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Create an API token for the user that is logged in.
+    pub fn create_my_api_token(&self, req: ApiTokenCreateRequest, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(
+            "http://localhost:9010/api/account/api_tokens",
+            serde_json::json!(req).to_string().into_bytes(),
+        );
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            // The response is not logged as a whole, since it includes the token's secret.
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<ApiTokenCreated>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to create the API token".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ApiTokenCreated(ars)) {
+                log::error!("[DataState::create_my_api_token] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Revoke an API token of the user that is logged in.
+    pub fn revoke_my_api_token(&self, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/account/api_tokens/{id}/revoke"), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::info!("[DataState::revoke_my_api_token] Response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to revoke the API token".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ApiTokenRevoked(id, ars)) {
+                log::error!("[DataState::revoke_my_api_token] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    // --------------
+    // Revisions mgmt
+    // --------------
+
+    /// Fetch the revisions of an element.
+    pub fn fetch_revisions(&mut self, kind: &Kind, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let path = match kind {
+            Kind::Item => "items",
+            Kind::ItemTemplate => "item_templates",
+            Kind::AttributeTemplate => "attribute_templates",
+            Kind::AccessLevel => "access_levels",
+            Kind::LinkTemplate | Kind::User => {
+                log::warn!("[DataState::fetch_revisions] Revisions of {kind:?} elements are not supported.");
+                return;
+            }
+        };
+        self.revisions_fetch_requested.insert(id.clone());
+        let mut req = ehttp::Request::get(format!("http://localhost:9010/api/{path}/{id}/revisions"));
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Vec<Revision>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to fetch revisions".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::RevisionsFetched(id, result)) {
+                log::error!("[DataState::fetch_revisions] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_revisions(&mut self, id: Id, data: Vec<Revision>) {
+        self.revisions_fetch_requested.remove(&id);
+        self.revisions.insert(id, data);
+    }
+
+    pub fn get_revisions(&self, id: &Id) -> Option<&Vec<Revision>> {
+        self.revisions.get(id)
+    }
+
+    /// Forget all the (cached) revisions, so that they get fetched again when needed.
+    pub fn invalidate_revisions(&mut self) {
+        self.revisions.clear();
+        self.revisions_fetch_requested.clear();
+    }
+
+    /// Restore an element (of the provided kind) as it was in a revision.
+    pub fn restore_revision(&self, kind: Kind, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut req = ehttp::Request::post(format!("http://localhost:9010/api/revisions/{id}/restore"), vec![]);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            log::trace!("[DataState::restore_revision] Got response: {:?}", rsp);
+            let ars = match rsp {
+                Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+                Err(err) => Err(AppError::ErrDetails("failed to restore revision".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ElementUpdated(kind, ars)) {
+                log::error!("[DataState::restore_revision] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    // ----------
+    // Trash mgmt
+    // ----------
+
+    /// Fetch all the elements that are in the Trash.
+    pub fn fetch_trash(&mut self, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        self.trash_fetch_requested = true;
+        let ectx = ectx.clone();
+        fetch_all_pages::<TrashEntry>("trash", self.session.clone(), Vec::new(), move |result| {
+            if let Ok(data) = &result {
+                log::trace!("[DataState::fetch_trash] Got {} elements.", data.len());
+            }
+
+            if let Err(e) = sender.send(UiMessage::TrashFetched(result)) {
+                log::error!("[DataState::fetch_trash] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    pub fn set_trash(&mut self, mut data: Vec<TrashEntry>) {
+        data.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_at));
+        self.trash = data;
+        self.fetched_trash = true;
+        self.trash_fetch_requested = false;
+    }
+
+    pub fn get_trash(&self) -> &[TrashEntry] {
+        &self.trash
+    }
+
+    /// Forget the (cached) Trash, so that it gets fetched again when needed.
+    pub fn invalidate_trash(&mut self) {
+        self.fetched_trash = false;
+        self.trash_fetch_requested = false;
+    }
+
+    /// Take an element out of the Trash.
+    pub fn restore_from_trash(&self, kind: Kind, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        trash_action("restore", kind, id, self.session.as_deref(), ectx, move |kind, ars| {
+            if let Err(e) = sender.send(UiMessage::ElementRestored(kind, ars)) {
+                log::error!("[DataState::restore_from_trash] Failed to send UiMessage. Error: {e}");
+            }
+        });
+    }
+
+    /// Delete for good an element that is in the Trash.
+    pub fn purge(&self, kind: Kind, id: Id, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        trash_action("purge", kind, id, self.session.as_deref(), ectx, move |kind, ars| {
+            if let Err(e) = sender.send(UiMessage::ElementPurged(kind, ars)) {
+                log::error!("[DataState::purge] Failed to send UiMessage. Error: {e}");
+            }
+        });
+    }
+
+    // ------
+    // Search
+    // ------
+
+    /// Search the items and the templates. The query is included in the result message,
+    /// so that the responses of older searches can be ignored.
+    pub fn search(&self, query: String, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let q = utf8_percent_encode(&query, NON_ALPHANUMERIC);
+        let mut req = ehttp::Request::get(format!("http://localhost:9010/api/search?q={q}"));
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<Vec<SearchHitsGroup>>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to search".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::SearchDone(query, result)) {
+                log::error!("[DataState::search] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+
+    /// Fetch the graph (neighborhood) of an item, as reached by following its links (as per the params).
+    pub fn fetch_item_graph(&self, id: Id, params: &GraphParams, ectx: &egui::Context, sender: Sender<UiMessage>) {
+        //
+        let mut url = format!(
+            "http://localhost:9010/api/items/{id}/graph?depth={}&direction={}",
+            params.depth(),
+            params.direction
+        );
+        if let Some(links) = &params.links {
+            url.push_str(&format!("&links={}", utf8_percent_encode(links, NON_ALPHANUMERIC)));
+        }
+        let mut req = ehttp::Request::get(url);
+        add_headers(&mut req, self.session.as_deref());
+        let ectx = ectx.clone();
+        ehttp::fetch(req, move |rsp| {
+            let result = match rsp {
+                Ok(rsp) => decode_json_response::<ItemGraph>(&rsp),
+                Err(err) => Err(AppError::ErrDetails("failed to get the item graph".to_string(), err)),
+            };
+            if let Err(e) = sender.send(UiMessage::ItemGraphFetched(id, result)) {
+                log::error!("[DataState::fetch_item_graph] Failed to send UiMessage. Error: {e}");
+            }
+            ectx.request_repaint();
+        });
+    }
+}
+
+/// Fetch all the pages of a list (from the `/api/{path}` endpoint), one after another,
+/// and then provide all the elements (or the error of fetching any page) to `on_done`.
+fn fetch_all_pages<T>(
+    path: &'static str,
+    session: Option<String>,
+    mut fetched: Vec<T>,
+    on_done: impl FnOnce(AppResult<Vec<T>>) + Send + 'static,
+) where
+    T: DeserializeOwned + Send + 'static,
+{
+    let page_req = PageRequest::new(fetched.len() as u32, PAGE_MAX_LIMIT);
+    let mut req = ehttp::Request::get(format!("http://localhost:9010/api/{path}?{}", page_req.to_query_string()));
+    add_headers(&mut req, session.as_deref());
+    ehttp::fetch(req, move |rsp| {
+        let result = match rsp {
+            Ok(rsp) => decode_json_response::<Page<T>>(&rsp),
+            Err(err) => Err(AppError::ErrDetails(format!("failed to fetch {path}"), err)),
+        };
+        match result {
+            Ok(page) => {
+                let has_more = page.has_more() && !page.items.is_empty();
+                fetched.extend(page.items);
+                if has_more {
+                    fetch_all_pages(path, session, fetched, on_done);
+                } else {
+                    on_done(Ok(fetched));
+                }
+            }
+            Err(err) => on_done(Err(err)),
+        }
+    });
+}
+
+/// Call the `/api/{kind path}/{id}/{action}` endpoint (`restore` or `purge`) of an element in the Trash,
+/// and then provide its result to `on_done`.
+fn trash_action(
+    action: &'static str,
+    kind: Kind,
+    id: Id,
+    session: Option<&str>,
+    ectx: &egui::Context,
+    on_done: impl FnOnce(Kind, AppResult<Id>) + Send + 'static,
+) {
+    let path = match kind {
+        Kind::Item => "items",
+        Kind::ItemTemplate => "item_templates",
+        Kind::AttributeTemplate => "attribute_templates",
+        _ => {
+            log::warn!("[DataState::trash_action] {kind:?} elements cannot be in the Trash.");
+            return;
+        }
+    };
+    let mut req = ehttp::Request::post(format!("http://localhost:9010/api/{path}/{id}/{action}"), vec![]);
+    add_headers(&mut req, session);
+    let ectx = ectx.clone();
+    ehttp::fetch(req, move |rsp| {
+        log::trace!("[DataState::trash_action] Got {action} response: {:?}", rsp);
+        // The purge is refused (as a conflict) while the element is still used.
+        let ars = match rsp {
+            Ok(rsp) => decode_json_response::<IdDto>(&rsp).map(|dto| dto.id),
+            Err(err) => Err(AppError::ErrDetails(format!("failed to {action} {kind:?}"), err)),
+        };
+        on_done(kind, ars);
+        ectx.request_repaint();
+    });
+}
+
+/// Add the headers of an API request, including the id of the user session (if logged in).
+fn add_headers(req: &mut ehttp::Request, session: Option<&str>) {
+    req.headers.insert("content-type", "application/json");
+    if let Some(session) = session {
+        req.headers.insert(SESSION_HEADER, session);
+    }
+}
+
+/// Get the query string of getting a page of items.
+fn items_query_string(params: &ItemsParams, page_req: &PageRequest) -> String {
+    let mut qs = page_req.to_query_string();
+    if let Some(filter) = &params.filter {
+        qs.push_str(&format!("&filter={}", utf8_percent_encode(filter, NON_ALPHANUMERIC)));
+    }
+    if let Some(tmpl_id) = &params.tmpl_id {
+        qs.push_str(&format!("&tmpl_id={}", utf8_percent_encode(&tmpl_id.0, NON_ALPHANUMERIC)));
+    }
+    qs
+}
+
+#[derive(Deserialize)]
+struct ViolationsResponse {
+    violations: Vec<AttrViolation>,
+}
+
+#[derive(Deserialize)]
+struct DependenciesResponse {
+    dependencies: Vec<Dependency>,
+}
+
+#[derive(Deserialize)]
+struct StaleVersionResponse {
+    current: Value,
+}
+
+/// The message about the save of an element (with the provided id, and as sent, in `mine`).
+/// A save that was refused since the element was changed meanwhile is reported as a conflict.
+fn saved_message(kind: Kind, id: Id, mine: Value, ars: AppResult<Id>) -> UiMessage {
+    match ars {
+        Err(AppError::StaleVersion(current)) => UiMessage::ElementSaveConflict(kind, id, mine, current),
+        ars if id.is_zero() => UiMessage::ElementCreated(kind, ars),
+        ars => UiMessage::ElementUpdated(kind, ars),
+    }
+}
+
+fn decode_json_response<T: DeserializeOwned>(rsp: &ehttp::Response) -> AppResult<T> {
+    let body = rsp.text().unwrap_or("<response body is not valid UTF-8>");
+
+    // A request without a (valid) session or without the required permission is refused.
+    if rsp.status == 401 || rsp.status == 403 {
+        let msg = rsp
+            .json::<ErrorResponse>()
+            .map(|body| body.error)
+            .unwrap_or_else(|_| body.to_string());
+        // The server's message already starts with the kind of error (e.g. "unauthorized: login required").
+        return Err(match rsp.status {
+            401 => AppError::Unauthorized(msg.strip_prefix("unauthorized: ").unwrap_or(&msg).to_string()),
+            _ => AppError::Forbidden(msg.strip_prefix("forbidden: ").unwrap_or(&msg).to_string()),
+        });
+    }
+
+    // The attributes that violate the item template are reported by the server as a list.
+    if rsp.status == 422
+        && let Ok(body) = rsp.json::<ViolationsResponse>()
+    {
+        return Err(AppError::ValidationFailed(body.violations));
+    }
+
+    // The elements that still use the one to be deleted are reported by the server as a list.
+    if rsp.status == 409
+        && let Ok(body) = rsp.json::<DependenciesResponse>()
+    {
+        return Err(AppError::DependenciesExist(body.dependencies));
+    }
+
+    // A save of an element that was changed by someone else meanwhile is refused, including its current copy.
+    if rsp.status == 409
+        && let Ok(body) = rsp.json::<StaleVersionResponse>()
+    {
+        return Err(AppError::StaleVersion(body.current));
+    }
+
+    // An invalid input (including a duplicate, e.g. an email that is already used) is reported by the server
+    // with the error's message, which is mapped back to the error.
+    if rsp.status == 400
+        && let Ok(body) = rsp.json::<ErrorResponse>()
+    {
+        let msg = body.error;
+        return Err(if let Some(details) = msg.strip_prefix("invalid input: ") {
+            AppError::InvalidInput(details.to_string())
+        } else if let Some(what) = msg.strip_suffix(" already exists") {
+            AppError::AlreadyExists(what.to_string())
+        } else {
+            AppError::InvalidInput(msg)
+        });
+    }
+
+    if !rsp.ok {
+        return Err(AppError::ErrDetails(
+            format!("HTTP {} {}", rsp.status, rsp.status_text),
+            body.to_string(),
+        ));
+    }
+
+    rsp.json()
+        .map_err(|err| AppError::ErrDetails("invalid JSON response".to_string(), format!("{err}; response body: {body}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_json_response;
+    use cogs_shared::{app::AppError, domain::model::AccessLevel};
+
+    fn response(status: u16, body: &str) -> ehttp::Response {
+        ehttp::Response {
+            url: "http://localhost/api/access_levels".to_string(),
+            ok: (200..300).contains(&status),
+            status,
+            status_text: String::new(),
+            headers: ehttp::Headers::default(),
+            bytes: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn access_level_error_object_is_not_treated_as_a_list() {
+        let result = decode_json_response::<Vec<AccessLevel>>(&response(404, r#"{"error":"not found"}"#));
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn conflict_reports_the_dependencies() {
+        let body =
+            r#"{"error":"dependencies exist","dependencies":[{"kind":"Item","id":"i-1","name":"srv-1","relation":"template"}]}"#;
+        let result = decode_json_response::<serde_json::Value>(&response(409, body));
+
+        match result {
+            Err(AppError::DependenciesExist(deps)) => {
+                assert_eq!(deps.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), vec!["srv-1"]);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn conflict_reports_the_current_copy_of_a_stale_version() {
+        let body = r#"{"error":"it was changed by someone else meanwhile","current":{"id":"at-1","version":3}}"#;
+        let result = decode_json_response::<serde_json::Value>(&response(409, body));
+
+        match result {
+            Err(AppError::StaleVersion(current)) => assert_eq!(current["version"], 3),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_session_and_permission_are_reported() {
+        let result = decode_json_response::<serde_json::Value>(&response(401, r#"{"error":"unauthorized: login required"}"#));
+        assert_eq!(result, Err(AppError::Unauthorized("login required".to_string())));
+
+        let body = r#"{"error":"forbidden: 'Admin::Write' permission is required"}"#;
+        let result = decode_json_response::<serde_json::Value>(&response(403, body));
+        assert_eq!(
+            result,
+            Err(AppError::Forbidden("'Admin::Write' permission is required".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_input_and_duplicate_are_reported() {
+        let body = r#"{"error":"invalid input: the current password is wrong"}"#;
+        let result = decode_json_response::<serde_json::Value>(&response(400, body));
+        assert_eq!(
+            result,
+            Err(AppError::InvalidInput("the current password is wrong".to_string()))
+        );
+
+        let body = r#"{"error":"an account with email 'jdoe@example.com' already exists"}"#;
+        let result = decode_json_response::<serde_json::Value>(&response(400, body));
+        assert_eq!(
+            result,
+            Err(AppError::AlreadyExists(
+                "an account with email 'jdoe@example.com'".to_string()
+            ))
+        );
     }
 
     #[test]
@@ -1,8 +1,9 @@
 use crate::{
     explore::ExploreViewState,
+    graph::GraphViewState,
     messages::UiMessage,
     state::{AuthState, DataState},
-    views::ViewName,
+    views::{SettingsViewState, ViewName},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::mpsc::Sender;
@@ -20,6 +21,11 @@ pub struct UiState {
 
     pub explore: ExploreViewState,
 
+    pub graph: GraphViewState,
+
+    #[serde(skip)]
+    pub settings: SettingsViewState,
+
     pub data: DataState,
 
     #[serde(skip)]
@@ -62,6 +68,7 @@ impl UiState {
         let hash = match self.curr_view {
             ViewName::Home => "#/",
             ViewName::Explore => "#/explore",
+            ViewName::Graph => "#/graph",
             ViewName::Settings => "#/settings",
             ViewName::Login => "#/login",
         };
@@ -77,6 +84,8 @@ impl Default for UiState {
             prev_view: ViewName::default(),
             auth: AuthState::default(),
             explore: ExploreViewState::default(),
+            graph: GraphViewState::default(),
+            settings: SettingsViewState::default(),
             data: DataState::default(),
             sender: None,
             ui_theme: egui::Theme::Dark,
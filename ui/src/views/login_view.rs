@@ -7,10 +7,10 @@ use crate::{
 };
 use cogs_shared::{
     app::AppError,
-    dtos::{LoginRequest, LoginResponse},
+    dtos::{ErrorResponse, LoginRequest, LoginResponse},
 };
-use egui::{Align2, Id, RichText, Shadow, Stroke};
-use std::sync::mpsc::Sender;
+use egui::{Align2, Button, Color32, Id, RichText, Shadow, Stroke};
+use std::{sync::mpsc::Sender, time::Duration};
 
 pub struct LoginView {}
 
@@ -32,7 +32,7 @@ impl AppView for LoginView {
                 .id(egui::Id::new("login_window_id"))
                 .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
                 .frame(frame)
-                .max_size((340.0, 200.0))
+                .max_size((340.0, 280.0))
                 .resizable(false)
                 .title_bar(false);
 
@@ -63,12 +63,52 @@ impl AppView for LoginView {
                     PasswordInput::show_input_entered(ui, &mut ctx.state.auth.pass, &mut ctx.state.auth.login_pass_enter);
                 });
 
+                if ctx.state.auth.login_reset_required {
+                    ui.add_space(10.0);
+                    ui.label("You must set a new password to continue.");
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        ui.label("New pass: ");
+                        ui.add_space(4.0);
+                        PasswordInput::show_input_entered(ui, &mut ctx.state.auth.new_pass, &mut ctx.state.auth.login_pass_enter);
+                    });
+                }
+
+                if let Some(AppError::InvalidInput(msg)) = &ctx.state.auth.login_error {
+                    ui.add_space(10.0);
+                    ui.label(RichText::new(msg).color(Color32::RED));
+                }
+
+                // After too many failed logins, the server tells how long to wait before the next attempt.
+                let now = ui.input(|i| i.time);
+                let wait = ctx
+                    .state
+                    .auth
+                    .login_retry_until
+                    .map(|until| (until - now).ceil() as u64)
+                    .filter(|secs| *secs > 0);
+                if let Some(secs) = wait {
+                    let text = match ctx.state.auth.login_error {
+                        Some(AppError::LoginLocked(_)) => format!(
+                            "The account is temporarily locked, due to repeated failed logins. Try again in {}.",
+                            wait_text(secs)
+                        ),
+                        _ => format!("Too many failed logins. Try again in {}.", wait_text(secs)),
+                    };
+                    ui.add_space(10.0);
+                    ui.label(RichText::new(text).color(Color32::ORANGE));
+                    ectx.request_repaint_after(Duration::from_secs(1));
+                } else {
+                    ctx.state.auth.login_retry_until = None;
+                }
+
                 ui.vertical_centered(|ui| {
                     ui.add_space(20.0);
-                    if ui.button("   Login   ").clicked() {
+                    if ui.add_enabled(wait.is_none(), Button::new("   Login   ")).clicked() {
                         handle_login(
                             ctx.state.auth.user.clone(),
                             ctx.state.auth.pass.clone(),
+                            new_password(ctx),
                             ctx.sendr.clone(),
                             ectx.clone(),
                         );
@@ -76,11 +116,12 @@ impl AppView for LoginView {
                     ui.add_space(10.0);
                 });
 
-                if ctx.state.auth.login_pass_enter && !ctx.state.auth.login_user_focus {
+                if ctx.state.auth.login_pass_enter && !ctx.state.auth.login_user_focus && wait.is_none() {
                     ctx.state.auth.login_pass_enter = false;
                     handle_login(
                         ctx.state.auth.user.clone(),
                         ctx.state.auth.pass.clone(),
+                        new_password(ctx),
                         ctx.sendr.clone(),
                         ectx.clone(),
                     );
@@ -106,8 +147,23 @@ impl AppView for LoginView {
     }
 }
 
-fn handle_login(user: String, pass: String, sender: Sender<UiMessage>, ectx: egui::Context) {
-    let body = LoginRequest::new(user, pass);
+/// The time to wait, in words.
+fn wait_text(secs: u64) -> String {
+    match secs {
+        1 => "1 second".to_string(),
+        2..60 => format!("{secs} seconds"),
+        _ => format!("{} minutes", secs.div_ceil(60)),
+    }
+}
+
+/// The new password to send along with the credentials, if the user must reset it.
+fn new_password(ctx: &CogsApp) -> Option<String> {
+    ctx.state.auth.login_reset_required.then(|| ctx.state.auth.new_pass.clone())
+}
+
+fn handle_login(user: String, pass: String, new_pass: Option<String>, sender: Sender<UiMessage>, ectx: egui::Context) {
+    let mut body = LoginRequest::new(user, pass);
+    body.new_password = new_pass;
     let mut req = ehttp::Request::post("http://localhost:9010/api/login", body.as_json().as_bytes().to_vec());
     req.headers.insert("Content-Type", "application/json".to_string());
     ehttp::fetch(req, move |rsp| {
@@ -139,8 +195,24 @@ fn handle_login(user: String, pass: String, sender: Sender<UiMessage>, ectx: egu
                     }
                 } else {
                     log::info!("[handle_login] Login failed! HTTP status code: {}", rsp.status);
+                    let retry_after = rsp
+                        .headers
+                        .get("Retry-After")
+                        .and_then(|value| value.trim().parse::<u64>().ok());
+                    if let Some(secs) = retry_after
+                        && let Err(e) = sender.send(UiMessage::LoginRetryAfter(secs))
+                    {
+                        log::info!("[handle_login] Failed to send LoginRetryAfter message. Error: {e}");
+                    }
                     let aerr = match rsp.status {
                         401 => AppError::LoginWrongCredentials,
+                        403 => AppError::LoginPasswordResetRequired,
+                        423 => AppError::LoginLocked(retry_after.unwrap_or_default()),
+                        429 => AppError::LoginThrottled(retry_after.unwrap_or_default()),
+                        400 => match serde_json::from_slice::<ErrorResponse>(rsp.bytes.as_slice()) {
+                            Ok(err_rsp) => AppError::InvalidInput(err_rsp.error),
+                            Err(_) => AppError::InvalidInput(format!("{}", rsp.status)),
+                        },
                         _ => AppError::from(format!("{}", rsp.status)),
                     };
                     if let Err(e) = sender.send(UiMessage::Login(Err(aerr))) {
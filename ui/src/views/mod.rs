@@ -7,6 +7,9 @@ pub use login_view::*;
 mod settings_view;
 pub use settings_view::*;
 
+mod settings_view_state;
+pub use settings_view_state::*;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,6 +17,7 @@ pub enum ViewName {
     #[default]
     Home,
     Explore,
+    Graph,
     Settings,
     Login,
 }
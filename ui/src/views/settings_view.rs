@@ -1,11 +1,22 @@
-use crate::{CogsApp, views::AppView};
+use crate::{
+    CogsApp,
+    views::{AppView, error_text},
+};
+use cogs_shared::{
+    domain::{logic::API_TOKEN_MAX_DAYS, model::PERMISSIONS},
+    dtos::ProfileUpdateRequest,
+};
+use egui::{Button, Color32, CursorIcon, DragValue, Grid, Label, RichText, TextEdit};
+
+/// The number of days that a new API token expires in, by default.
+const API_TOKEN_DEFAULT_DAYS: u32 = 90;
 
 pub struct SettingsView {}
 
 impl AppView for SettingsView {
     type Context = CogsApp;
 
-    fn show(_ctx: &mut Self::Context, ui: &mut egui::Ui) {
+    fn show(ctx: &mut Self::Context, ui: &mut egui::Ui) {
         egui::CentralPanel::default().show(ui, |ui| {
             // The central panel is the region left after adding TopPanel's and SidePanel's
 
@@ -13,7 +24,326 @@ impl AppView for SettingsView {
             ui.heading("Settings");
             ui.add_space(10.0);
 
-            ui.label("This is the Settings view.");
+            if ctx.state.auth.user_account.is_none() {
+                ui.label("Log in to manage your account.");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.set_max_width(560.0);
+                ui.label(RichText::new("Account").strong().size(16.0));
+                ui.add_space(12.0);
+                show_profile(ctx, ui);
+                ui.add_space(24.0);
+                show_password(ctx, ui);
+                ui.add_space(24.0);
+                show_api_tokens(ctx, ui);
+            });
+        });
+    }
+}
+
+/// Show the profile (name, email and bio) of the user that is logged in, to be edited.
+fn show_profile(ctx: &mut CogsApp, ui: &mut egui::Ui) {
+    //
+    let Some(account) = ctx.state.auth.user_account.clone() else {
+        return;
+    };
+    let saved = ProfileUpdateRequest::from(&account);
+    let mut profile = ctx.state.settings.profile.take().unwrap_or_else(|| saved.clone());
+
+    ui.label(RichText::new("Profile").strong());
+    ui.add_space(8.0);
+    Grid::new("settings_profile_grid")
+        .spacing([10.0, 10.0])
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.add_enabled(false, Label::new("username"));
+            ui.label(account.username.as_str());
+            ui.end_row();
+
+            ui.add_enabled(false, Label::new("name"));
+            ui.add(TextEdit::singleline(&mut profile.name).desired_width(320.0));
+            ui.end_row();
+
+            ui.add_enabled(false, Label::new("email"));
+            ui.add(TextEdit::singleline(&mut profile.email).desired_width(320.0));
+            ui.end_row();
+
+            ui.add_enabled(false, Label::new("bio"));
+            ui.add(TextEdit::multiline(&mut profile.bio).desired_width(320.0).desired_rows(3));
+            ui.end_row();
+        });
+
+    let changed = profile != saved;
+    if changed {
+        ctx.state.settings.profile_saved = false;
+    }
+    ctx.state.settings.profile = Some(profile);
+    let invalid = ctx.state.settings.profile_error();
+
+    ui.add_space(10.0);
+    ui.horizontal(|ui| {
+        let enabled = changed && invalid.is_none() && !ctx.state.settings.profile_saving;
+        let resp = ui
+            .add_enabled(enabled, Button::new("  Save profile  "))
+            .on_hover_cursor(CursorIcon::PointingHand);
+        if resp.clicked()
+            && let Some(profile) = ctx.state.settings.profile.clone()
+        {
+            ctx.state.settings.profile_saving = true;
+            ctx.state.settings.profile_err = None;
+            ctx.state.data.update_my_profile(profile, ui.ctx(), ctx.sendr.clone());
+        }
+        if changed && ui.button("  Undo  ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+            ctx.state.settings.profile = None;
+            ctx.state.settings.profile_err = None;
+        }
+    });
+
+    if let Some(err) = &ctx.state.settings.profile_err {
+        ui.add_space(6.0);
+        ui.label(RichText::new(error_text(err)).color(Color32::RED));
+    } else if changed && let Some(invalid) = invalid {
+        ui.add_space(6.0);
+        ui.label(RichText::new(invalid).color(Color32::ORANGE));
+    } else if ctx.state.settings.profile_saved {
+        ui.add_space(6.0);
+        ui.label("The profile was saved.");
+    }
+}
+
+/// Show the fields of changing the password of the user that is logged in.
+fn show_password(ctx: &mut CogsApp, ui: &mut egui::Ui) {
+    //
+    let settings = &mut ctx.state.settings;
+    ui.label(RichText::new("Password").strong());
+    ui.add_space(8.0);
+    Grid::new("settings_password_grid")
+        .spacing([10.0, 10.0])
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (label, value) in [
+                ("current password", &mut settings.current_password),
+                ("new password", &mut settings.new_password),
+                ("confirm new password", &mut settings.confirm_password),
+            ] {
+                ui.add_enabled(false, Label::new(label));
+                if ui
+                    .add(TextEdit::singleline(value).password(true).desired_width(240.0))
+                    .changed()
+                {
+                    settings.password_changed = false;
+                    settings.password_err = None;
+                }
+                ui.end_row();
+            }
+        });
+
+    let entered = !settings.new_password.is_empty() || !settings.confirm_password.is_empty();
+    let invalid = settings.password_error();
+
+    ui.add_space(10.0);
+    let enabled = invalid.is_none() && !settings.password_saving;
+    let resp = ui
+        .add_enabled(enabled, Button::new("  Change password  "))
+        .on_hover_cursor(CursorIcon::PointingHand);
+    if resp.clicked() {
+        settings.password_saving = true;
+        settings.password_err = None;
+        let req = settings.password_change_request();
+        ctx.state.data.change_my_password(req, ui.ctx(), ctx.sendr.clone());
+    }
+
+    let settings = &ctx.state.settings;
+    if let Some(err) = &settings.password_err {
+        ui.add_space(6.0);
+        ui.label(RichText::new(error_text(err)).color(Color32::RED));
+    } else if entered && let Some(invalid) = invalid {
+        ui.add_space(6.0);
+        ui.label(RichText::new(invalid).color(Color32::ORANGE));
+    } else if settings.password_changed {
+        ui.add_space(6.0);
+        ui.label("The password was changed.");
+    }
+}
+
+/// Show the API tokens of the user that is logged in, along with the fields of creating a new one.
+fn show_api_tokens(ctx: &mut CogsApp, ui: &mut egui::Ui) {
+    //
+    let Some(account) = ctx.state.auth.user_account.clone() else {
+        return;
+    };
+    let settings = &mut ctx.state.settings;
+    if settings.api_tokens.is_none() && !settings.api_tokens_fetching && settings.api_tokens_err.is_none() {
+        settings.api_tokens_fetching = true;
+        ctx.state.data.fetch_my_api_tokens(ui.ctx(), ctx.sendr.clone());
+    }
+
+    ui.label(RichText::new("API tokens").strong());
+    ui.add_space(4.0);
+    ui.label(
+        RichText::new("Scripts and integrations may use the API with a token (as `Authorization: Bearer <token>`).")
+            .color(Color32::GRAY),
+    );
+    ui.add_space(8.0);
+
+    if let Some(created) = &settings.created_api_token {
+        let mut dismissed = false;
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.label(format!(
+                "The token '{}' was created. Copy it now, as it will not be shown again.",
+                created.token.name
+            ));
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                ui.add(Label::new(RichText::new(&created.secret).monospace()).selectable(true));
+                if ui.button(" Copy ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    ui.ctx().copy_text(created.secret.clone());
+                }
+                if ui.button(" Done ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    dismissed = true;
+                }
+            });
         });
+        if dismissed {
+            settings.created_api_token = None;
+        }
+        ui.add_space(10.0);
+    }
+
+    if let Some(err) = &settings.api_tokens_err {
+        ui.label(RichText::new(error_text(err)).color(Color32::RED));
+        if ui.button(" Retry ").on_hover_cursor(CursorIcon::PointingHand).clicked() {
+            settings.api_tokens_err = None;
+        }
+        ui.add_space(10.0);
+    }
+    match settings.api_tokens.clone() {
+        None if settings.api_tokens_fetching => {
+            ui.label("Loading the tokens ...");
+        }
+        None => {}
+        Some(tokens) if tokens.is_empty() => {
+            ui.label("You have no API tokens.");
+        }
+        Some(tokens) => {
+            Grid::new("settings_api_tokens_grid")
+                .spacing([14.0, 8.0])
+                .num_columns(6)
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in ["name", "token", "permissions", "expires", "last used", ""] {
+                        ui.add_enabled(false, Label::new(header));
+                    }
+                    ui.end_row();
+                    for token in tokens {
+                        ui.label(token.name.as_str());
+                        ui.label(RichText::new(format!("{}...", token.hint)).monospace());
+                        ui.label(token.permissions.join(", "));
+                        match token.expires_at {
+                            _ if token.expired => ui.label(RichText::new("expired").color(Color32::ORANGE)),
+                            Some(expires_at) => ui.label(expires_at.format("%Y-%m-%d").to_string()),
+                            None => ui.label("never"),
+                        };
+                        match token.last_used_at {
+                            Some(used_at) => ui.label(used_at.format("%Y-%m-%d %H:%M UTC").to_string()),
+                            None => ui.label("never"),
+                        };
+                        let enabled = settings.api_token_revoking.is_none();
+                        let resp = ui
+                            .add_enabled(enabled, Button::new(" Revoke "))
+                            .on_hover_text(format!("created on {}", token.created_at.format("%Y-%m-%d %H:%M UTC")))
+                            .on_hover_cursor(CursorIcon::PointingHand);
+                        if resp.clicked() {
+                            settings.api_token_revoking = Some(token.id.clone());
+                            settings.api_tokens_err = None;
+                            ctx.state.data.revoke_my_api_token(token.id, ui.ctx(), ctx.sendr.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        }
+    }
+
+    ui.add_space(16.0);
+    ui.label("New token");
+    ui.add_space(8.0);
+    let new_token = &mut settings.new_api_token;
+    let mut changed = false;
+    Grid::new("settings_new_api_token_grid")
+        .spacing([10.0, 10.0])
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.add_enabled(false, Label::new("name"));
+            changed |= ui
+                .add(TextEdit::singleline(&mut new_token.name).desired_width(240.0))
+                .changed();
+            ui.end_row();
+
+            ui.add_enabled(false, Label::new("permissions"));
+            ui.horizontal(|ui| {
+                for perm in PERMISSIONS
+                    .iter()
+                    .filter(|perm| account.permissions.iter().any(|p| p == *perm))
+                {
+                    let mut checked = new_token.permissions.iter().any(|p| p == perm);
+                    if ui.checkbox(&mut checked, *perm).changed() {
+                        changed = true;
+                        if checked {
+                            new_token.permissions.push(perm.to_string());
+                        } else {
+                            new_token.permissions.retain(|p| p != perm);
+                        }
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.add_enabled(false, Label::new("expires"));
+            ui.horizontal(|ui| {
+                let mut expires = new_token.expires_in_days.is_some();
+                if ui.checkbox(&mut expires, "in").changed() {
+                    changed = true;
+                    new_token.expires_in_days = expires.then_some(API_TOKEN_DEFAULT_DAYS);
+                }
+                match &mut new_token.expires_in_days {
+                    Some(days) => {
+                        changed |= ui.add(DragValue::new(days).range(1..=API_TOKEN_MAX_DAYS)).changed();
+                        ui.label("days");
+                    }
+                    None => {
+                        ui.label("(never)");
+                    }
+                }
+            });
+            ui.end_row();
+        });
+    if changed {
+        settings.api_token_err = None;
+    }
+
+    let entered = !settings.new_api_token.name.is_empty() || !settings.new_api_token.permissions.is_empty();
+    let invalid = settings.api_token_error(&account);
+
+    ui.add_space(10.0);
+    let enabled = invalid.is_none() && !settings.api_token_saving;
+    let resp = ui
+        .add_enabled(enabled, Button::new("  Create token  "))
+        .on_hover_cursor(CursorIcon::PointingHand);
+    if resp.clicked() {
+        settings.api_token_saving = true;
+        settings.api_token_err = None;
+        let req = settings.new_api_token.clone();
+        ctx.state.data.create_my_api_token(req, ui.ctx(), ctx.sendr.clone());
+    }
+
+    let settings = &ctx.state.settings;
+    if let Some(err) = &settings.api_token_err {
+        ui.add_space(6.0);
+        ui.label(RichText::new(error_text(err)).color(Color32::RED));
+    } else if entered && let Some(invalid) = invalid {
+        ui.add_space(6.0);
+        ui.label(RichText::new(invalid).color(Color32::ORANGE));
     }
 }
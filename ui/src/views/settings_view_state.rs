@@ -0,0 +1,163 @@
+use cogs_shared::{
+    app::AppError,
+    domain::{
+        logic::{check_api_token_request, check_new_password, check_user_profile},
+        model::{ApiToken, Id, UserAccount},
+    },
+    dtos::{ApiTokenCreateRequest, ApiTokenCreated, PasswordChangeRequest, ProfileUpdateRequest},
+};
+
+/// The state of the Settings view, including the account (profile, password and API tokens) of the user
+/// that is logged in. It is not persisted, as it includes passwords and secrets.
+#[derive(Clone, Debug, Default)]
+pub struct SettingsViewState {
+    /// The profile being edited. It's taken from the user's account when the view is shown.
+    pub profile: Option<ProfileUpdateRequest>,
+    pub profile_saving: bool,
+    pub profile_saved: bool,
+    pub profile_err: Option<AppError>,
+
+    pub current_password: String,
+    pub new_password: String,
+    pub confirm_password: String,
+    pub password_saving: bool,
+    pub password_changed: bool,
+    pub password_err: Option<AppError>,
+
+    /// The API tokens of the user, once they are fetched.
+    pub api_tokens: Option<Vec<ApiToken>>,
+    pub api_tokens_fetching: bool,
+    pub api_tokens_err: Option<AppError>,
+    /// The id of the API token that is being revoked, if any.
+    pub api_token_revoking: Option<Id>,
+
+    /// The API token being created.
+    pub new_api_token: ApiTokenCreateRequest,
+    pub api_token_saving: bool,
+    pub api_token_err: Option<AppError>,
+    /// The API token that was just created, whose secret is shown (just this once) until it is dismissed.
+    pub created_api_token: Option<ApiTokenCreated>,
+}
+
+impl SettingsViewState {
+    /// Check the profile being edited, getting the reason why it cannot be saved (if any).
+    pub fn profile_error(&self) -> Option<String> {
+        let profile = self.profile.as_ref()?;
+        check_user_profile(&profile.name, &profile.email)
+            .err()
+            .map(|err| error_text(&err))
+    }
+
+    /// Check the passwords being entered, getting the reason why the password cannot be changed (if any).
+    pub fn password_error(&self) -> Option<String> {
+        if self.current_password.is_empty() {
+            return Some("Provide your current password.".to_string());
+        }
+        if let Err(err) = check_new_password(&self.new_password) {
+            return Some(error_text(&err));
+        }
+        if self.new_password != self.confirm_password {
+            return Some("The new password and its confirmation do not match.".to_string());
+        }
+        None
+    }
+
+    /// The request of changing the password, as entered.
+    pub fn password_change_request(&self) -> PasswordChangeRequest {
+        PasswordChangeRequest {
+            current_password: self.current_password.clone(),
+            new_password: self.new_password.clone(),
+        }
+    }
+
+    /// Check the API token being created, getting the reason why it cannot be created (if any).
+    pub fn api_token_error(&self, user: &UserAccount) -> Option<String> {
+        check_api_token_request(&self.new_api_token, user)
+            .err()
+            .map(|err| error_text(&err))
+    }
+
+    /// Forget the passwords that were entered.
+    pub fn clear_passwords(&mut self) {
+        self.current_password.clear();
+        self.new_password.clear();
+        self.confirm_password.clear();
+    }
+}
+
+/// The text that describes an error of saving the profile or changing the password, as shown to the user.
+pub fn error_text(err: &AppError) -> String {
+    match err {
+        AppError::AlreadyExists(_) => "This email is already used by another account.".to_string(),
+        AppError::InvalidInput(msg) => {
+            let mut chars = msg.chars();
+            match chars.next() {
+                Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+                None => "The input is not valid.".to_string(),
+            }
+        }
+        AppError::Unauthorized(_) => "Your session has expired. Please log in again.".to_string(),
+        _ => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SettingsViewState, error_text};
+    use cogs_shared::{
+        app::AppError,
+        domain::model::{PERM_ADMIN_READ, PERM_ADMIN_WRITE, UserAccount},
+    };
+
+    #[test]
+    fn password_change_requires_a_matching_confirmation() {
+        let mut state = SettingsViewState {
+            current_password: "old password".to_string(),
+            new_password: "new password".to_string(),
+            confirm_password: "new pasword".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            state.password_error(),
+            Some("The new password and its confirmation do not match.".to_string())
+        );
+
+        state.confirm_password = "new password".to_string();
+        assert_eq!(state.password_error(), None);
+
+        state.new_password = "short".to_string();
+        state.confirm_password = "short".to_string();
+        assert_eq!(
+            state.password_error(),
+            Some("The password must have at least 8 characters.".to_string())
+        );
+        assert_eq!(
+            error_text(&AppError::InvalidInput("the current password is wrong".to_string())),
+            "The current password is wrong."
+        );
+    }
+
+    #[test]
+    fn api_token_requires_a_name_and_permissions_of_the_user() {
+        let user = UserAccount {
+            permissions: vec![PERM_ADMIN_READ.to_string()],
+            is_anonymous: false,
+            ..Default::default()
+        };
+        let mut state = SettingsViewState::default();
+        assert_eq!(
+            state.api_token_error(&user),
+            Some("The token name must have 1 to 64 characters.".to_string())
+        );
+
+        state.new_api_token.name = "nightly export".to_string();
+        state.new_api_token.permissions = vec![PERM_ADMIN_READ.to_string(), PERM_ADMIN_WRITE.to_string()];
+        assert_eq!(
+            state.api_token_error(&user),
+            Some("You do not have the 'Admin::Write' permission.".to_string())
+        );
+
+        state.new_api_token.permissions.pop();
+        assert_eq!(state.api_token_error(&user), None);
+    }
+}